@@ -0,0 +1,257 @@
+//!bench.rs
+
+use crate::budget::{self, CancelToken};
+use crate::selection::{Part, FIRST_DAY, LAST_DAY};
+use crate::solution::DynSolution;
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub iterations: usize,
+    // compare medians against this baseline
+    pub baseline: Option<PathBuf>,
+    pub save_baseline: Option<PathBuf>,
+    // allowed slow down in percent before a phase counts as regression
+    pub tolerance: f64,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 1,
+            iterations: 10,
+            baseline: None,
+            save_baseline: None,
+            tolerance: 10.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    Parse,
+    Solve(Part),
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // pad to support alignment in bench tables
+        match self {
+            Phase::Parse => f.pad("parse"),
+            Phase::Solve(part) => f.pad(&format!("part_{}", part)),
+        }
+    }
+}
+
+impl TryFrom<&str> for Phase {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "parse" => Ok(Phase::Parse),
+            _ => value
+                .strip_prefix("part_")
+                .ok_or(anyhow!("bad phase '{}'", value))
+                .and_then(Part::try_from)
+                .map(Phase::Solve),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl From<&[Duration]> for Stats {
+    fn from(value: &[Duration]) -> Self {
+        let mut timings = value.to_vec();
+        timings.sort();
+        Self {
+            min: timings.first().copied().unwrap_or_default(),
+            median: timings.get(timings.len() / 2).copied().unwrap_or_default(),
+            max: timings.last().copied().unwrap_or_default(),
+        }
+    }
+}
+
+// each run of f gets its own time budget
+fn measure<T>(
+    config: &BenchConfig,
+    budget: Option<Duration>,
+    mut f: impl FnMut() -> Result<T>,
+) -> Result<Stats> {
+    let mut run = || budget::with_token(&CancelToken::with_budget(budget), &mut f);
+    for _ in 0..config.warmup {
+        run()?;
+    }
+    let mut timings: Vec<Duration> = Vec::with_capacity(config.iterations);
+    for _ in 0..config.iterations.max(1) {
+        let start = Instant::now();
+        run()?;
+        timings.push(start.elapsed());
+    }
+    Ok(Stats::from(timings.as_slice()))
+}
+
+// times parse and each given part of one day separately,
+// budget limits each run of a part
+pub fn bench_day(
+    solution: &dyn DynSolution,
+    input: &str,
+    parts: &[Part],
+    config: &BenchConfig,
+    budget: Option<Duration>,
+) -> Result<Vec<(Phase, Stats)>> {
+    let mut results = vec![(
+        Phase::Parse,
+        measure(config, None, || solution.parse(input))?,
+    )];
+    let parsed = solution.parse(input)?;
+    for part in parts.iter() {
+        let stats = measure(config, budget, || solution.solve(parsed.as_ref(), *part))?;
+        results.push((Phase::Solve(*part), stats));
+    }
+    Ok(results)
+}
+
+// median timings of a previous run, stored as toml with nanoseconds per day and phase
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, Phase), Duration>,
+}
+
+impl TryFrom<&str> for Baseline {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let table = value.parse::<toml::Table>()?;
+        let mut medians = BTreeMap::new();
+        for (day_key, phases) in table.iter() {
+            let day = day_key
+                .strip_prefix("day_")
+                .and_then(|d| d.parse::<u8>().ok())
+                .filter(|d| (FIRST_DAY..=LAST_DAY).contains(d))
+                .ok_or(anyhow!("bad day key '{}', expected day_NN", day_key))?;
+            let phases = phases
+                .as_table()
+                .ok_or(anyhow!("'{}' must be a table of phase timings", day_key))?;
+            for (phase_key, nanos) in phases.iter() {
+                let phase = Phase::try_from(phase_key.as_str())?;
+                let nanos = nanos
+                    .as_integer()
+                    .and_then(|n| u64::try_from(n).ok())
+                    .ok_or(anyhow!("{}.{} must be nanoseconds", day_key, phase_key))?;
+                medians.insert((day, phase), Duration::from_nanos(nanos));
+            }
+        }
+        Ok(Self { medians })
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# median timings in nanoseconds")?;
+        let mut current_day: Option<u8> = None;
+        for ((day, phase), median) in self.medians.iter() {
+            if current_day != Some(*day) {
+                writeln!(f, "\n[day_{:02}]", day)?;
+                current_day = Some(*day);
+            }
+            writeln!(f, "{} = {}", phase, median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let baseline = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline {}", path.display()))?;
+        Baseline::try_from(baseline.as_str())
+            .with_context(|| format!("failed to parse baseline {}", path.display()))
+    }
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write baseline {}", path.display()))
+    }
+    pub fn insert(&mut self, day: u8, phase: Phase, median: Duration) {
+        self.medians.insert((day, phase), median);
+    }
+    pub fn get(&self, day: u8, phase: Phase) -> Option<Duration> {
+        self.medians.get(&(day, phase)).copied()
+    }
+    // relative change of median in percent, positive values are slow downs
+    pub fn change(&self, day: u8, phase: Phase, median: Duration) -> Option<f64> {
+        self.get(day, phase)
+            .filter(|base| !base.is_zero())
+            .map(|base| (median.as_secs_f64() / base.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_stats_and_baseline() -> Result<()> {
+        let timings: Vec<Duration> = [5, 1, 3, 4, 2].map(Duration::from_micros).to_vec();
+        let stats = Stats::from(timings.as_slice());
+        assert_eq!(stats.min, Duration::from_micros(1));
+        assert_eq!(stats.median, Duration::from_micros(3));
+        assert_eq!(stats.max, Duration::from_micros(5));
+
+        let mut baseline = Baseline::default();
+        baseline.insert(17, Phase::Parse, Duration::from_nanos(2_000));
+        baseline.insert(17, Phase::Solve(Part::Two), Duration::from_nanos(1_000));
+        baseline.insert(3, Phase::Solve(Part::One), Duration::from_nanos(500));
+        let saved = baseline.to_string();
+        assert_eq!(Baseline::try_from(saved.as_str())?, baseline);
+        let change = baseline.change(17, Phase::Solve(Part::Two), Duration::from_nanos(1_250));
+        assert!((change.unwrap() - 25.0).abs() < 1e-9);
+        assert_eq!(
+            baseline.change(16, Phase::Parse, Duration::from_nanos(1)),
+            None
+        );
+        assert!(Baseline::try_from("[day_01]\npart_3 = 1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bench_day() -> Result<()> {
+        let config = BenchConfig {
+            warmup: 0,
+            iterations: 3,
+            ..Default::default()
+        };
+        let input = "1000\n2000\n\n4000\n\n5000\n6000";
+        let results = bench_day(days::get(1).unwrap(), input, &Part::both(), &config, None)?;
+        let phases: Vec<Phase> = results.iter().map(|(p, _)| *p).collect();
+        assert_eq!(
+            phases,
+            vec![
+                Phase::Parse,
+                Phase::Solve(Part::One),
+                Phase::Solve(Part::Two)
+            ]
+        );
+        assert!(results
+            .iter()
+            .all(|(_, s)| s.min <= s.median && s.median <= s.max));
+
+        let input = include_str!("../assets/day_11_example.txt");
+        let budget = Some(Duration::ZERO);
+        let err = bench_day(days::get(11).unwrap(), input, &[Part::Two], &config, budget);
+        assert!(budget::is_cancelled(&err.unwrap_err()));
+        Ok(())
+    }
+}
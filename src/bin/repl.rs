@@ -0,0 +1,32 @@
+//!repl.rs
+// usage: repl [DAY [FILE]], loads puzzle input of DAY or FILE and reads commands of stdin
+
+use advent_of_code_2022::input::InputSource;
+use advent_of_code_2022::repl::{run_repl, Repl};
+use anyhow::{anyhow, Result};
+
+fn run() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mut repl = Repl::new(InputSource::Embedded);
+    if let Some(day) = args.next() {
+        let day: u8 = day
+            .parse()
+            .map_err(|_| anyhow!("day expected, got '{}'", day))?;
+        let source = args
+            .next()
+            .map_or(InputSource::Embedded, |p| InputSource::from(p.as_str()));
+        repl.load_source(day, &source)?;
+        println!("loaded day {:02}, type help for commands", day);
+    }
+    run_repl(&mut repl, std::io::stdin().lock(), std::io::stdout())
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error occured: {}", err);
+        for source in err.chain().skip(1) {
+            eprintln!("Source of error: {}", source);
+        }
+        std::process::exit(1);
+    }
+}
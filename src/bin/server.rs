@@ -0,0 +1,31 @@
+//!server.rs
+// usage: server [OPTIONS], answers POST /solve with answers of solvers, see --help
+
+use advent_of_code_2022::server::{Server, ServerConfig, USAGE};
+use advent_of_code_2022::trace;
+use anyhow::{Context, Result};
+use std::net::TcpListener;
+use std::sync::Arc;
+
+fn run() -> Result<()> {
+    let config = ServerConfig::parse(std::env::args().skip(1))?;
+    if config.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    trace::init(None)?;
+    let listener = TcpListener::bind(&config.addr)
+        .with_context(|| format!("failed to listen on {}", config.addr))?;
+    println!("serving solvers on http://{}", listener.local_addr()?);
+    Arc::new(Server::new(config)).serve(listener)
+}
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("Error occured: {}", err);
+        for source in err.chain().skip(1) {
+            eprintln!("Source of error: {}", source);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,154 @@
+//!budget.rs
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// error of solvers, which stopped because their token was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled, time budget exceeded")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+// cancelled either explicitly or by reaching its deadline
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // None never runs out of time
+    pub fn with_budget(budget: Option<Duration>) -> Self {
+        Self {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: budget.map(|budget| Instant::now() + budget),
+        }
+    }
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// reading the clock on every check slows down hot loops
+const CHECK_INTERVAL: u32 = 64;
+
+thread_local! {
+    static CURRENT_TOKEN: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+    static CHECKS_UNTIL_CLOCK: Cell<u32> = const { Cell::new(0) };
+}
+
+// restores previous token of thread, even if solver panics
+struct RestoreToken(Option<CancelToken>);
+
+impl Drop for RestoreToken {
+    fn drop(&mut self) {
+        CURRENT_TOKEN.with(|current| current.replace(self.0.take()));
+    }
+}
+
+// runs f with token as current token of this thread, which solvers poll with check()
+pub fn with_token<T>(token: &CancelToken, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_TOKEN.with(|current| current.replace(Some(token.clone())));
+    let _restore = RestoreToken(previous);
+    CHECKS_UNTIL_CLOCK.with(|count| count.set(0));
+    f()
+}
+
+// called periodically by long running solvers, without current token it never fails
+pub fn check() -> Result<(), Cancelled> {
+    let read_clock = CHECKS_UNTIL_CLOCK.with(|count| match count.get() {
+        0 => {
+            count.set(CHECK_INTERVAL - 1);
+            true
+        }
+        n => {
+            count.set(n - 1);
+            false
+        }
+    });
+    if !read_clock {
+        return Ok(());
+    }
+    CURRENT_TOKEN.with(|current| match current.borrow().as_ref() {
+        Some(token) => token.check(),
+        None => Ok(()),
+    })
+}
+
+// true, if err was caused by a cancelled token
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.chain().any(|source| source.is::<Cancelled>())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn spin() -> anyhow::Result<u64> {
+        let mut rounds = 0;
+        loop {
+            check()?;
+            rounds += 1;
+            if rounds == 1_000_000 {
+                return Ok(rounds);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cancel_token() {
+        assert_eq!(spin().unwrap(), 1_000_000);
+        let token = CancelToken::with_budget(Some(Duration::ZERO));
+        let err = with_token(&token, spin).unwrap_err();
+        assert!(is_cancelled(&err));
+        assert_eq!(err.to_string(), "cancelled, time budget exceeded");
+        // token is only current inside of with_token()
+        assert!(spin().is_ok());
+
+        let token = CancelToken::new();
+        assert!(with_token(&token, spin).is_ok());
+        token.cancel();
+        assert_eq!(token.check(), Err(Cancelled));
+        let unlimited = CancelToken::with_budget(None);
+        assert!(!unlimited.is_cancelled());
+
+        // searches of slow days stop at once
+        let token = CancelToken::with_budget(Some(Duration::ZERO));
+        for day in [11, 16, 17, 19, 24] {
+            let input = crate::input::InputSource::Embedded.load(day).unwrap();
+            let solution = crate::days::get(day).unwrap();
+            let parsed = solution.parse(&input).unwrap();
+            for part in solution.parts() {
+                let err =
+                    with_token(&token, || solution.solve(parsed.as_ref(), *part)).unwrap_err();
+                assert!(is_cancelled(&err), "day {} part {}: {}", day, part, err);
+            }
+        }
+    }
+}
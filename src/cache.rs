@@ -0,0 +1,213 @@
+//!cache.rs
+
+use crate::answer::Answer;
+use crate::fetch::{FetchConfig, YEAR};
+use crate::selection::Part;
+use crate::solution::DynSolution;
+use crate::trace;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub const CACHE_FILE: &str = "results.jsonl";
+
+// FNV-1a, which unlike the hasher of std is stable between compiler versions
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+// version of crate and of solver of day, cached answers of other versions are not used
+pub fn solver_version(solution: &dyn DynSolution) -> String {
+    format!("{}+{}", env!("CARGO_PKG_VERSION"), solution.version())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub day: u8,
+    pub part: Part,
+    pub input_hash: String,
+    pub version: String,
+}
+
+impl Key {
+    pub fn new(solution: &dyn DynSolution, part: Part, input: &str) -> Self {
+        Self {
+            day: solution.day(),
+            part,
+            input_hash: input_hash(input),
+            version: solver_version(solution),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResult {
+    pub key: Key,
+    pub answer: Answer,
+}
+
+impl TryFrom<&str> for CachedResult {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let json: serde_json::Value = serde_json::from_str(value)?;
+        let text = |key: &str| {
+            json[key]
+                .as_str()
+                .map(String::from)
+                .ok_or(anyhow!("missing text '{}' of cached result", key))
+        };
+        let number = |key: &str| {
+            json[key]
+                .as_u64()
+                .ok_or(anyhow!("missing number '{}' of cached result", key))
+        };
+        let answer = match &json["answer"] {
+            serde_json::Value::Number(n) => Answer::Number(
+                n.as_i64()
+                    .ok_or(anyhow!("bad answer {} of cached result", n))?,
+            ),
+            serde_json::Value::String(t) => Answer::Text(t.to_owned()),
+            _ => return Err(anyhow!("missing answer of cached result")),
+        };
+        Ok(Self {
+            key: Key {
+                day: u8::try_from(number("day")?)?,
+                part: Part::try_from(number("part")?.to_string().as_str())?,
+                input_hash: text("input_hash")?,
+                version: text("version")?,
+            },
+            answer,
+        })
+    }
+}
+
+impl CachedResult {
+    pub fn to_json(&self) -> serde_json::Value {
+        let answer = match &self.answer {
+            Answer::Number(n) => serde_json::Value::from(*n),
+            Answer::Text(t) => serde_json::Value::from(t.as_str()),
+        };
+        serde_json::json!({
+            "day": self.key.day,
+            "part": u8::from(self.key.part),
+            "input_hash": self.key.input_hash,
+            "version": self.key.version,
+            "answer": answer,
+        })
+    }
+}
+
+// answers of earlier runs, appended to a file; later lines win over earlier lines of same key
+#[derive(Debug, Clone, Default)]
+pub struct ResultCache {
+    path: PathBuf,
+    answers: HashMap<Key, Answer>,
+}
+
+impl ResultCache {
+    // missing cache file is an empty cache, bad lines, e.g. of an interrupted append, are skipped
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut answers = HashMap::new();
+        if path.is_file() {
+            let cache = std::fs::read(path)
+                .with_context(|| format!("failed to read result cache {}", path.display()))?;
+            for (index, line) in cache.split(|b| *b == b'\n').enumerate() {
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                let result = std::str::from_utf8(line)
+                    .map_err(anyhow::Error::from)
+                    .and_then(CachedResult::try_from);
+                match result {
+                    Ok(result) => {
+                        answers.insert(result.key, result.answer);
+                    }
+                    Err(err) => trace::event!(
+                        Warn,
+                        "skipped bad line {} of result cache {}: {:#}",
+                        index + 1,
+                        path.display(),
+                        err
+                    ),
+                }
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            answers,
+        })
+    }
+    pub fn get(&self, key: &Key) -> Option<&Answer> {
+        self.answers.get(key)
+    }
+    // appends answer of key, unless it is already cached
+    pub fn insert(&mut self, key: Key, answer: Answer) -> Result<()> {
+        if self.answers.get(&key) == Some(&answer) {
+            return Ok(());
+        }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("failed to open result cache {}", self.path.display()))?;
+        let result = CachedResult { key, answer };
+        writeln!(file, "{}", result.to_json())?;
+        self.answers.insert(result.key, result.answer);
+        Ok(())
+    }
+}
+
+// results of all accounts share one file, since inputs are told apart by their hash
+pub fn cache_path(config: &FetchConfig) -> PathBuf {
+    config.cache_dir.join(YEAR.to_string()).join(CACHE_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_result_cache() -> Result<()> {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+        let day_01 = days::get(1).unwrap();
+        let key = Key::new(day_01, Part::One, "1000\n2000");
+        assert_eq!(key.version, format!("{}+1", env!("CARGO_PKG_VERSION")));
+        assert_ne!(key, Key::new(day_01, Part::Two, "1000\n2000"));
+        assert_ne!(key, Key::new(day_01, Part::One, "1000\n2001"));
+
+        let dir = std::env::temp_dir().join(format!("aoc_2022_cache_{}", std::process::id()));
+        let path = dir.join(CACHE_FILE);
+        let mut cache = ResultCache::load(&path)?;
+        assert_eq!(cache.get(&key), None);
+        cache.insert(key.clone(), Answer::from(3000))?;
+        cache.insert(key.clone(), Answer::from(3000))?;
+        let snafu = Key::new(days::get(25).unwrap(), Part::One, "1=");
+        cache.insert(snafu.clone(), Answer::from("1="))?;
+        let cache = ResultCache::load(&path)?;
+        assert_eq!(cache.get(&key), Some(&Answer::from(3000)));
+        assert_eq!(cache.get(&snafu), Some(&Answer::from("1=")));
+        assert_eq!(std::fs::read_to_string(&path)?.lines().count(), 2);
+
+        // line cut by an interrupted append is skipped
+        let mut lines = std::fs::read(&path)?;
+        lines.extend_from_slice(b"{\"day\":1,\"part\":1,\"input_hash\":\"\xe2\x82");
+        std::fs::write(&path, &lines)?;
+        let cache = ResultCache::load(&path)?;
+        assert_eq!(cache.get(&key), Some(&Answer::from(3000)));
+        std::fs::write(&path, "{\"day\":1}\n")?;
+        assert_eq!(ResultCache::load(&path)?.get(&key), None);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+//!cli.rs
+
+use crate::bench::BenchConfig;
+use crate::fetch::FetchConfig;
+use crate::input::InputSource;
+use crate::pool;
+use crate::progress::{ProgressLine, ProgressSink};
+use crate::report::Format;
+use crate::selection::Selection;
+use anyhow::{anyhow, Result};
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+usage: advent-of-code-2022 [OPTIONS] [SELECTOR]...
+
+selectors (default: all days and parts):
+    7            day 7, both parts
+    10..=15      days 10 to 15
+    10..15       days 10 to 14
+    22:2         day 22, part 2 only
+
+options:
+    -i, --input <FILE>      read input of the single selected day from FILE, '-' for stdin
+    -d, --input-dir <DIR>   read inputs from DIR/day_NN.txt, missing days use embedded assets
+        --stream            solve line oriented days 1, 2, 3, 4, 6, 9, 10 and 25 while reading
+                            their input, which does not need to fit into memory
+        --fetch             download inputs and cache them per year, day and account
+        --refresh           download inputs of --fetch again, even if cached
+        --config <FILE>     session token, account, base url and cache dir of --fetch
+                            (default: aoc.toml, overridden by AOC_* environment variables)
+        --submit            submit answer of the single selected part, e.g. 7:1, with session
+                            of --config; known wrong answers and cooldowns are refused
+        --visualize         animate simulation of the single selected day in the terminal;
+                            p + enter pauses and resumes, enter steps, q + enter quits
+        --fps <N>           frames per second of --visualize (default: 10)
+        --dump-frames <DIR> write frames of --visualize as text files to DIR/part_N
+        --generate <DIR>    write random inputs of selected days to DIR/day_NN.txt
+        --seed <N>          seed of --generate, same seed gives same inputs (default: 2022);
+                            first seed of --differential
+        --size <N>          number of lines or items of --generate (default: 100)
+                            and of --differential (default: 10)
+        --differential      compare optimized solvers of selected days with their naive reference
+                            solvers on generated inputs, report first mismatch of each part
+        --runs <N>          number of generated inputs of --differential (default: 100)
+    -v, --verify            compare answers with expected answers of answers.toml next to inputs;
+                            answers are recomputed, even if they are cached
+    -m, --matrix <DIR>      verify selected days on every input set DIR/<NAME>/day_NN.txt with
+                            answers of DIR/<NAME>/answers.toml, print a grid of verdicts
+        --no-cache          solve all parts instead of serving answers of earlier runs, which
+                            are cached per day, part, input hash and solver version
+    -a, --answers <FILE>    read expected answers of --verify from FILE
+    -f, --format <FORMAT>   output format: text (default), json (one record per line) or csv
+    -j, --jobs <N>          solve N days in parallel, 0 for one per cpu (default: 1)
+        --include-slow      solve slow parts, which are skipped by default
+        --budget <SECONDS>  time budget of each part, implies --include-slow;
+                            parts exceeding it are reported as timed out
+        --trace <FILTER>    print debug events of days and modules on stderr, e.g. day_22=trace
+                            or days=debug,info (default: AOC_TRACE environment variable)
+        --no-progress       hide progress line of long running searches, which is shown
+                            on stderr if it is a terminal
+    -b, --bench             benchmark parse, part 1 and part 2 of selected days separately
+        --warmup <N>        untimed runs of each phase before benchmarking (default: 1)
+        --iterations <N>    timed runs of each phase (default: 10)
+        --baseline <FILE>   compare median timings with baseline FILE
+        --save-baseline <FILE>
+                            save median timings as baseline FILE
+        --tolerance <PCT>   slow down in percent reported as regression (default: 10)
+    -l, --list              list available days
+    -h, --help              print this help";
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub selection: Selection,
+    pub input: InputSource,
+    pub stream: bool,
+    pub fetch: bool,
+    pub refresh: bool,
+    pub config: Option<PathBuf>,
+    pub submit: bool,
+    pub visualize: bool,
+    // None uses DEFAULT_FPS
+    pub fps: Option<f64>,
+    pub dump_frames: Option<PathBuf>,
+    pub generate: Option<PathBuf>,
+    // None uses DEFAULT_SEED and DEFAULT_SIZE
+    pub seed: Option<u64>,
+    pub size: Option<usize>,
+    pub differential: bool,
+    // None uses DEFAULT_RUNS
+    pub runs: Option<u64>,
+    pub verify: bool,
+    pub no_cache: bool,
+    // directory of input sets, one sub directory per account
+    pub matrix: Option<PathBuf>,
+    pub answers: Option<PathBuf>,
+    pub format: Format,
+    // None solves days sequentially
+    pub jobs: Option<usize>,
+    pub include_slow: bool,
+    // time budget per part
+    pub budget: Option<Duration>,
+    pub no_progress: bool,
+    // filter of trace events, None uses environment variable
+    pub trace: Option<String>,
+    pub bench: bool,
+    pub bench_config: BenchConfig,
+    pub list: bool,
+    pub help: bool,
+}
+
+pub(crate) fn parse_value<S: AsRef<str>, T: std::str::FromStr>(
+    value: Option<S>,
+    option: &str,
+) -> Result<T> {
+    let value = value.ok_or(anyhow!("missing value of {}", option))?;
+    value
+        .as_ref()
+        .parse::<T>()
+        .map_err(|_| anyhow!("bad value '{}' of {}", value.as_ref(), option))
+}
+
+impl Options {
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "-i" | "--input" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --input"))?;
+                    options.input = InputSource::from(value.as_ref());
+                }
+                "-d" | "--input-dir" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --input-dir"))?;
+                    options.input = InputSource::Dir(value.as_ref().into());
+                }
+                "--stream" => options.stream = true,
+                "--fetch" => options.fetch = true,
+                "--refresh" => options.refresh = true,
+                "--config" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --config"))?;
+                    options.config = Some(value.as_ref().into());
+                }
+                "--submit" => options.submit = true,
+                "--visualize" => options.visualize = true,
+                "--fps" => {
+                    let fps: f64 = parse_value(args.next(), "--fps")?;
+                    if !(fps > 0.0 && fps.is_finite()) {
+                        return Err(anyhow!("bad value '{}' of --fps", fps));
+                    }
+                    options.fps = Some(fps);
+                }
+                "--dump-frames" => {
+                    let value = args
+                        .next()
+                        .ok_or(anyhow!("missing value of --dump-frames"))?;
+                    options.dump_frames = Some(value.as_ref().into());
+                }
+                "--generate" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --generate"))?;
+                    options.generate = Some(value.as_ref().into());
+                }
+                "--seed" => options.seed = Some(parse_value(args.next(), "--seed")?),
+                "--size" => options.size = Some(parse_value(args.next(), "--size")?),
+                "--differential" => options.differential = true,
+                "--runs" => options.runs = Some(parse_value(args.next(), "--runs")?),
+                "-v" | "--verify" => options.verify = true,
+                "--no-cache" => options.no_cache = true,
+                "-m" | "--matrix" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --matrix"))?;
+                    options.matrix = Some(value.as_ref().into());
+                }
+                "-a" | "--answers" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --answers"))?;
+                    options.answers = Some(value.as_ref().into());
+                }
+                "-f" | "--format" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --format"))?;
+                    options.format = Format::try_from(value.as_ref())?;
+                }
+                "-j" | "--jobs" => options.jobs = Some(parse_value(args.next(), "--jobs")?),
+                "--include-slow" => options.include_slow = true,
+                "--budget" => {
+                    let seconds: f64 = parse_value(args.next(), "--budget")?;
+                    let budget = Duration::try_from_secs_f64(seconds)
+                        .map_err(|_| anyhow!("bad value '{}' of --budget", seconds))?;
+                    options.budget = Some(budget);
+                }
+                "--no-progress" => options.no_progress = true,
+                "--trace" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --trace"))?;
+                    options.trace = Some(value.as_ref().to_string());
+                }
+                "-b" | "--bench" => options.bench = true,
+                "--warmup" => options.bench_config.warmup = parse_value(args.next(), "--warmup")?,
+                "--iterations" => {
+                    options.bench_config.iterations = parse_value(args.next(), "--iterations")?
+                }
+                "--baseline" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --baseline"))?;
+                    options.bench_config.baseline = Some(value.as_ref().into());
+                }
+                "--save-baseline" => {
+                    let value = args
+                        .next()
+                        .ok_or(anyhow!("missing value of --save-baseline"))?;
+                    options.bench_config.save_baseline = Some(value.as_ref().into());
+                }
+                "--tolerance" => {
+                    options.bench_config.tolerance = parse_value(args.next(), "--tolerance")?
+                }
+                "-l" | "--list" => options.list = true,
+                "-h" | "--help" => options.help = true,
+                flag if flag.starts_with('-') => {
+                    return Err(anyhow!("unknown option '{}'\n\n{}", flag, USAGE))
+                }
+                selector => options.selection.add(selector)?,
+            }
+        }
+        if options.fetch && options.input != InputSource::Embedded {
+            return Err(anyhow!(
+                "--fetch cannot be combined with --input or --input-dir"
+            ));
+        }
+        if options.matrix.is_some() && (options.fetch || options.input != InputSource::Embedded) {
+            return Err(anyhow!(
+                "--matrix cannot be combined with --fetch, --input or --input-dir"
+            ));
+        }
+        Ok(options)
+    }
+    // replaces input source by fetched inputs, which needs config file and environment
+    pub fn resolve_fetch(&mut self) -> Result<()> {
+        if self.fetch {
+            let mut config = FetchConfig::load(self.config.as_deref())?;
+            config.refresh = self.refresh;
+            self.input = InputSource::Fetch(config);
+        }
+        Ok(())
+    }
+    // submissions use config of --fetch, even if inputs are not fetched
+    pub fn fetch_config(&self) -> Result<FetchConfig> {
+        match &self.input {
+            InputSource::Fetch(config) => Ok(config.clone()),
+            _ => FetchConfig::load(self.config.as_deref()),
+        }
+    }
+    // slow parts run, if they are explicitly included or limited by a budget
+    pub fn runs_slow_parts(&self) -> bool {
+        self.include_slow || self.budget.is_some()
+    }
+    // progress line would garble output redirected to a file
+    pub fn progress_sink(&self) -> Option<Arc<dyn ProgressSink>> {
+        if self.no_progress || !std::io::stderr().is_terminal() {
+            return None;
+        }
+        Some(Arc::new(ProgressLine))
+    }
+    pub fn workers(&self) -> usize {
+        match self.jobs {
+            None => 1,
+            Some(0) => pool::available_workers(),
+            Some(jobs) => jobs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::selection::Part;
+
+    #[test]
+    fn test_parse_options() -> Result<()> {
+        let options = Options::parse(["--list", "3", "5:1"])?;
+        assert!(options.list);
+        assert!(!options.help);
+        let selected: Vec<(u8, Part)> = options.selection.iter().collect();
+        assert_eq!(
+            selected,
+            vec![(3, Part::One), (3, Part::Two), (5, Part::One)]
+        );
+        assert_eq!(options.input, InputSource::Embedded);
+        assert!(Options::parse(["--verbose"]).is_err());
+        assert!(Options::parse(["--input"]).is_err());
+
+        let options = Options::parse(["-i", "-", "7"])?;
+        assert_eq!(options.input, InputSource::Stdin);
+        let options = Options::parse(["--input-dir", "inputs"])?;
+        assert_eq!(options.input, InputSource::Dir("inputs".into()));
+        assert!(!options.verify);
+        assert!(!options.stream);
+        assert!(Options::parse(["--stream", "-i", "-", "1"])?.stream);
+
+        let options = Options::parse(["--verify", "-a", "answers.toml"])?;
+        assert!(options.verify && !options.no_cache);
+        assert_eq!(options.answers, Some("answers.toml".into()));
+        assert!(Options::parse(["--no-cache"])?.no_cache);
+        let options = Options::parse(["--matrix", "inputs", "15..=16"])?;
+        assert_eq!(options.matrix, Some("inputs".into()));
+        assert!(Options::parse(["-m", "inputs", "-d", "inputs/alice"]).is_err());
+        assert_eq!(options.format, Format::Text);
+
+        let options = Options::parse(["--format", "json"])?;
+        assert_eq!(options.format, Format::Json);
+        assert!(Options::parse(["--format", "yaml"]).is_err());
+
+        let options = Options::parse(["--bench", "--iterations", "5", "--tolerance", "2.5"])?;
+        assert!(options.bench);
+        assert_eq!(options.bench_config.iterations, 5);
+        assert_eq!(options.bench_config.warmup, 1);
+        assert_eq!(options.bench_config.tolerance, 2.5);
+        assert!(Options::parse(["--iterations", "many"]).is_err());
+
+        assert_eq!(Options::parse(["-j", "3"])?.workers(), 3);
+        assert_eq!(Options::parse(["7"])?.workers(), 1);
+        assert!(Options::parse(["-j", "0"])?.workers() >= 1);
+
+        assert!(!Options::parse(["11"])?.runs_slow_parts());
+        assert!(Options::parse(["--include-slow"])?.runs_slow_parts());
+        let options = Options::parse(["--budget", "1.5"])?;
+        assert_eq!(options.budget, Some(Duration::from_millis(1_500)));
+        assert!(options.runs_slow_parts());
+        assert!(Options::parse(["--budget", "-1"]).is_err());
+        assert!(Options::parse(["--no-progress"])?.progress_sink().is_none());
+        let options = Options::parse(["--trace", "day_22=trace", "22"])?;
+        assert_eq!(options.trace.as_deref(), Some("day_22=trace"));
+
+        let options = Options::parse(["--fetch", "--refresh", "--config", "aoc.toml"])?;
+        assert!(options.fetch && options.refresh);
+        assert_eq!(options.config, Some("aoc.toml".into()));
+        assert!(Options::parse(["--fetch", "-d", "inputs"]).is_err());
+        assert!(Options::parse(["--submit", "1:1"])?.submit);
+
+        let options = Options::parse(["--visualize", "--fps", "2.5", "--dump-frames", "frames"])?;
+        assert!(options.visualize);
+        assert_eq!(options.fps, Some(2.5));
+        assert_eq!(options.dump_frames, Some("frames".into()));
+        assert!(Options::parse(["--fps", "0"]).is_err());
+
+        let options = Options::parse(["--generate", "random", "--seed", "7", "--size", "20"])?;
+        assert_eq!(options.generate, Some("random".into()));
+        assert_eq!((options.seed, options.size), (Some(7), Some(20)));
+        assert!(Options::parse(["--seed", "-1"]).is_err());
+
+        let options = Options::parse(["--differential", "--runs", "5", "16"])?;
+        assert!(options.differential);
+        assert_eq!(options.runs, Some(5));
+        assert!(Options::parse(["--runs"]).is_err());
+        Ok(())
+    }
+}
@@ -1,26 +1,96 @@
 //!day_01.rs
+//!
+//! Calorie counting, `Day01::parse` sums up calories carried by each elve, sorted ascending.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::parse::{number, read_lines, ParseError};
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
+use std::io::BufRead;
 
-pub fn day_01() -> Result<()> {
-    let input = include_str!("../../assets/day_01.txt");
-    let mut max_calories: Vec<u64> = Vec::new();
-    for calorie_package in input.split("\n\n") {
-        let calorie_sum: u64 = calorie_package
-            .lines()
-            .map(|l| l.parse::<u64>().expect("bad input"))
-            .sum();
-        max_calories.push(calorie_sum);
-    }
-    max_calories.sort();
+// calories of elves, sorted ascending, keeping only the elves with most calories
+struct ElveCalories {
+    max_calories: Vec<u64>,
+    calories: Option<u64>,
+    keep: usize,
+}
 
-    let result_part1 = max_calories.last().unwrap();
-    println!("result day 01 part 1: {}", result_part1);
-    assert_eq!(*result_part1, 74_711);
+impl ElveCalories {
+    fn new(keep: usize) -> Self {
+        Self {
+            max_calories: Vec::new(),
+            calories: None,
+            keep,
+        }
+    }
+    // a blank line ends the calories of an elve, so blank lines may not follow each other
+    fn add_line(&mut self, line: &str) -> Result<(), ParseError> {
+        if !line.is_empty() {
+            *self.calories.get_or_insert(0) += number::<u64>(line, line)?;
+            return Ok(());
+        }
+        let calories = self
+            .calories
+            .take()
+            .ok_or_else(|| ParseError::new(line, line, "number"))?;
+        self.add_elve(calories);
+        Ok(())
+    }
+    fn add_elve(&mut self, calories: u64) {
+        let index = self.max_calories.partition_point(|c| *c < calories);
+        self.max_calories.insert(index, calories);
+        if self.max_calories.len() > self.keep {
+            self.max_calories.remove(0);
+        }
+    }
+    fn finish(mut self) -> Vec<u64> {
+        if let Some(calories) = self.calories.take() {
+            self.add_elve(calories);
+        }
+        self.max_calories
+    }
+}
 
-    let result_part2: u64 = max_calories[max_calories.len() - 3..].iter().sum();
-    println!("result day 01 part 2: {}", result_part2);
-    assert_eq!(result_part2, 209_481);
+pub struct Day01;
 
-    Ok(())
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const STREAMS: bool = true;
+    type Input = Vec<u64>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut elve_calories = ElveCalories::new(usize::MAX);
+        for (index, line) in input.lines().enumerate() {
+            elve_calories
+                .add_line(line)
+                .map_err(|err| err.at_line(index + 1))?;
+        }
+        Ok(elve_calories.finish())
+    }
+    fn part_one(max_calories: &Self::Input) -> Result<u64> {
+        let result_part1 = *max_calories
+            .last()
+            .ok_or_else(|| anyhow!("need at least 1 elve"))?;
+        Ok(result_part1)
+    }
+    fn part_two(max_calories: &Self::Input) -> Result<u64> {
+        let top_three = max_calories
+            .len()
+            .checked_sub(3)
+            .ok_or_else(|| anyhow!("need at least 3 elves"))?;
+        let result_part2: u64 = max_calories[top_three..].iter().sum();
+        Ok(result_part2)
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        // lines are checked like by parse(), but only the 3 elves needed by part 2 are kept
+        let mut elve_calories = ElveCalories::new(3);
+        read_lines(reader, |line| elve_calories.add_line(line))?;
+        let max_calories = elve_calories.finish();
+        Ok(vec![
+            Self::part_one(&max_calories).map(Into::into),
+            Self::part_two(&max_calories).map(Into::into),
+        ])
+    }
 }
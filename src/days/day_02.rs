@@ -1,75 +1,119 @@
 //!day_02.rs
+//!
+//! Rock paper scissors tournament, see [`Rps`] for scores of shapes and rounds.
 
+use crate::answer::Answer;
+use crate::parse::{next_token, parse_lines, parse_token, read_lines, ParseError};
+use crate::solution::Solution;
 use anyhow::Result;
+use std::io::BufRead;
 
-#[derive(Clone, Copy)]
-enum Rps {
+/// Shape of a round, codes A and X are rock, B and Y paper, C and Z scissor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rps {
     Rock,
     Paper,
     Scissor,
 }
 
-impl From<&str> for Rps {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for Rps {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         match value {
-            "A" | "X" => Rps::Rock,
-            "B" | "Y" => Rps::Paper,
-            "C" | "Z" => Rps::Scissor,
-            _ => panic!("bad input"),
+            "A" | "X" => Ok(Rps::Rock),
+            "B" | "Y" => Ok(Rps::Paper),
+            "C" | "Z" => Ok(Rps::Scissor),
+            _ => Err(ParseError::new(value, value, "A, B, C, X, Y or Z")),
         }
     }
 }
 
 impl Rps {
-    fn score_shape(&self) -> u64 {
+    /// Score of playing this shape, 1 for rock, 2 for paper and 3 for scissor.
+    pub fn score_shape(&self) -> u64 {
         match self {
             Rps::Rock => 1,
             Rps::Paper => 2,
             Rps::Scissor => 3,
         }
     }
-    fn play(&self, other: &Self) -> u64 {
+    /// Score of outcome against other shape, 6 for a win, 3 for a draw and 0 for a loss.
+    pub fn play(&self, other: &Self) -> u64 {
         match (self, other) {
             (Rps::Rock, Rps::Scissor) | (Rps::Paper, Rps::Rock) | (Rps::Scissor, Rps::Paper) => 6,
             (Rps::Rock, Rps::Paper) | (Rps::Paper, Rps::Scissor) | (Rps::Scissor, Rps::Rock) => 0,
             _ => 3,
         }
     }
-    fn from_cheating(code: &str, other: &Self) -> Self {
+    /// Shape, which gives outcome of code against other shape; code X, Y or Z is parsed as
+    /// rock, paper or scissor and means loss, draw or win.
+    pub fn from_cheating(code: &Self, other: &Self) -> Self {
         match code {
-            "X" => match other {
+            Rps::Rock => match other {
                 Rps::Rock => Rps::Scissor,
                 Rps::Paper => Rps::Rock,
                 Rps::Scissor => Rps::Paper,
             },
-            "Y" => *other,
-            "Z" => match other {
+            Rps::Paper => *other,
+            Rps::Scissor => match other {
                 Rps::Rock => Rps::Paper,
                 Rps::Paper => Rps::Scissor,
                 Rps::Scissor => Rps::Rock,
             },
-            _ => panic!("bad input"),
         }
     }
 }
 
-pub fn day_02() -> Result<()> {
-    let input = include_str!("../../assets/day_02.txt");
-    let mut result_part1 = 0;
-    let mut result_part2: u64 = 0;
-    for (opp, me) in input.lines().map(|l| l.split_once(' ').unwrap()) {
-        let opp = Rps::from(opp);
-        let me_task1 = Rps::from(me);
-        result_part1 += me_task1.play(&opp) + me_task1.score_shape();
-        let me_task2 = Rps::from_cheating(me, &opp);
-        result_part2 += me_task2.play(&opp) + me_task2.score_shape();
-    }
+// round of strategy guide, e.g. `A Y`
+fn parse_round(line: &str) -> Result<(Rps, Rps), ParseError> {
+    let mut tokens = line.split(' ');
+    let opp = next_token(line, &mut tokens, "shape of opponent")?;
+    let opp = parse_token(line, opp)?;
+    let me = next_token(line, &mut tokens, "shape or outcome")?;
+    let me = parse_token(line, me)?;
+    Ok((opp, me))
+}
 
-    println!("result day 02 part 1: {}", result_part1);
-    assert_eq!(result_part1, 14_375);
+// score of my shape in round against opponent
+fn score(opp: &Rps, me: &Rps) -> u64 {
+    me.play(opp) + me.score_shape()
+}
+
+pub struct Day02;
 
-    println!("result day 02 part 2: {}", result_part2);
-    assert_eq!(result_part2, 10_274);
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const STREAMS: bool = true;
+    type Input = Vec<(Rps, Rps)>;
+    type PartOne = u64;
+    type PartTwo = u64;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, parse_round)?)
+    }
+    fn part_one(strategy_guide: &Self::Input) -> Result<u64> {
+        let mut result_part1 = 0;
+        for (opp, me) in strategy_guide.iter() {
+            result_part1 += score(opp, me);
+        }
+        Ok(result_part1)
+    }
+    fn part_two(strategy_guide: &Self::Input) -> Result<u64> {
+        let mut result_part2 = 0;
+        for (opp, me) in strategy_guide.iter() {
+            result_part2 += score(opp, &Rps::from_cheating(me, opp));
+        }
+        Ok(result_part2)
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let (mut result_part1, mut result_part2) = (0, 0);
+        read_lines(reader, |line| {
+            let (opp, me) = parse_round(line)?;
+            result_part1 += score(&opp, &me);
+            result_part2 += score(&opp, &Rps::from_cheating(&me, &opp));
+            Ok(())
+        })?;
+        Ok(vec![Ok(result_part1.into()), Ok(result_part2.into())])
+    }
 }
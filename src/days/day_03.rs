@@ -1,6 +1,12 @@
 //!day_03.rs
+//!
+//! Rucksack reorganization, see [`calc_priority`] for priorities of items.
 
+use crate::answer::Answer;
+use crate::parse::{parse_lines, read_lines, ParseError};
+use crate::solution::Solution;
 use anyhow::Result;
+use std::io::BufRead;
 
 enum LineState {
     First(String),
@@ -8,34 +14,10 @@ enum LineState {
     Last,
 }
 
-fn calc_priority(c: char) -> u32 {
-    if c.is_ascii_lowercase() {
-        // a as u32 is 97 -> -96 == 1
-        return (c as u32) - 96;
-    }
-    if c.is_ascii_uppercase() {
-        // A as u32 is 65 -> -38 == 27
-        return (c as u32) - 38;
-    }
-    panic!("non ascii char");
-}
-
-pub fn day_03() -> Result<()> {
-    let input = include_str!("../../assets/day_03.txt");
-    let mut result_part1 = 0;
-    let mut result_part2 = 0;
-    let mut line_state = LineState::Last;
-    for rucksack in input.lines() {
-        let num_items = rucksack.chars().count();
-        let compartment_1 = &rucksack[..num_items / 2];
-        let compartment_2 = &rucksack[num_items / 2..];
-        for item in compartment_1.chars() {
-            if compartment_2.contains(item) {
-                result_part1 += calc_priority(item);
-                break;
-            }
-        }
-        line_state = match line_state {
+impl LineState {
+    // state after next rucksack of group, priority of badge is added when group is complete
+    fn next(self, rucksack: &str, result: &mut u32) -> Self {
+        match self {
             LineState::Last => LineState::First(rucksack.to_string()),
             LineState::First(first_rucksack) => {
                 let mut merge_first_second = String::with_capacity(first_rucksack.len());
@@ -49,7 +31,7 @@ pub fn day_03() -> Result<()> {
             LineState::Second(merge_first_second) => {
                 for item in merge_first_second.chars() {
                     if rucksack.contains(item) {
-                        result_part2 += calc_priority(item);
+                        *result += calc_priority(item);
                         break;
                     }
                 }
@@ -57,30 +39,79 @@ pub fn day_03() -> Result<()> {
             }
         }
     }
-    println!("result day 03 part 1: {}", result_part1);
-    assert_eq!(result_part1, 8_088);
+}
+
+fn parse_rucksack(l: &str) -> Result<String, ParseError> {
+    match l.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        Some((i, c)) => Err(ParseError::new(l, &l[i..i + c.len_utf8()], "item letter")),
+        None => Ok(l.to_string()),
+    }
+}
 
-    println!("result day 03 part 2: {}", result_part2);
-    assert_eq!(result_part2, 2_522);
+// priority of item in both compartments of rucksack
+fn compartment_priority(rucksack: &str) -> u32 {
+    let num_items = rucksack.chars().count();
+    let compartment_1 = &rucksack[..num_items / 2];
+    let compartment_2 = &rucksack[num_items / 2..];
+    for item in compartment_1.chars() {
+        if compartment_2.contains(item) {
+            return calc_priority(item);
+        }
+    }
+    0
+}
 
-    Ok(())
+/// Priority of item, 1 to 26 for a to z and 27 to 52 for A to Z.
+///
+/// # Panics
+///
+/// Panics if item is not an ascii letter.
+pub fn calc_priority(c: char) -> u32 {
+    if c.is_ascii_lowercase() {
+        // a as u32 is 97 -> -96 == 1
+        return (c as u32) - 96;
+    }
+    if c.is_ascii_uppercase() {
+        // A as u32 is 65 -> -38 == 27
+        return (c as u32) - 38;
+    }
+    panic!("non ascii char");
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day03;
 
-    use super::*;
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const STREAMS: bool = true;
+    type Input = Vec<String>;
+    type PartOne = u32;
+    type PartTwo = u32;
 
-    #[test]
-    fn test_example_part_1() -> Result<()> {
-        let input = include_str!("../../assets/day_03.txt");
-        for line in input.lines() {
-            assert_eq!(line.chars().count(), line[..].len());
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, parse_rucksack)?)
+    }
+    fn part_one(rucksacks: &Self::Input) -> Result<u32> {
+        let result_part1 = rucksacks.iter().map(|r| compartment_priority(r)).sum();
+        Ok(result_part1)
+    }
+    fn part_two(rucksacks: &Self::Input) -> Result<u32> {
+        let mut result_part2 = 0;
+        let mut line_state = LineState::Last;
+        for rucksack in rucksacks.iter() {
+            line_state = line_state.next(rucksack, &mut result_part2);
         }
-        eprintln!("a: {}", 'a' as u32);
-        eprintln!("z: {}", 'z' as u32);
-        eprintln!("A: {}", 'A' as u32);
-        eprintln!("Z: {}", 'Z' as u32);
-        Ok(())
+        Ok(result_part2)
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let (mut result_part1, mut result_part2) = (0, 0);
+        let mut line_state = LineState::Last;
+        read_lines(reader, |line| {
+            let rucksack = parse_rucksack(line)?;
+            result_part1 += compartment_priority(&rucksack);
+            line_state = std::mem::replace(&mut line_state, LineState::Last)
+                .next(&rucksack, &mut result_part2);
+            Ok(())
+        })?;
+        Ok(vec![Ok(result_part1.into()), Ok(result_part2.into())])
     }
 }
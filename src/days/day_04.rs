@@ -1,33 +1,40 @@
 //!day_04.rs
+//!
+//! Camp cleanup, see [`SectionRange`] for overlaps of section assignments.
 
+use crate::answer::Answer;
+use crate::parse::{number, parse_lines, parse_token, read_lines, split_once, ParseError};
+use crate::solution::Solution;
 use anyhow::Result;
+use std::io::BufRead;
 
-struct SectionRange {
+/// Assigned sections of an elf, parsed from e.g. `2-4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionRange {
     start: u32,
     // end is not included in range as in any proper range definition
     end: u32,
 }
 
-impl From<&str> for SectionRange {
-    fn from(value: &str) -> Self {
-        let (start, end) = value
-            .split_once('-')
-            .map(|(s, e)| {
-                (
-                    s.parse::<u32>().expect("bad input"),
-                    e.parse::<u32>().expect("bad input") + 1,
-                )
-            })
-            .unwrap();
-        Self { start, end }
+impl TryFrom<&str> for SectionRange {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (start, end) = split_once(value, value, "-")?;
+        Ok(Self {
+            start: number(value, start)?,
+            end: number::<u32>(value, end)? + 1,
+        })
     }
 }
 
 impl SectionRange {
-    fn size(&self) -> u32 {
+    /// Number of assigned sections.
+    pub fn size(&self) -> u32 {
         self.end - self.start
     }
-    fn get_overlap(&self, other: &Self) -> Option<Self> {
+    /// Sections assigned to both elves, if there are any.
+    pub fn get_overlap(&self, other: &Self) -> Option<Self> {
         let overlap_start = self.start.max(other.start);
         let overlap_end = self.end.min(other.end);
         if overlap_end > overlap_start {
@@ -39,66 +46,53 @@ impl SectionRange {
             None
         }
     }
-}
-
-pub fn day_04() -> Result<()> {
-    let input = include_str!("../../assets/day_04.txt");
-    let mut result_part1 = 0;
-    let mut result_part2 = 0;
-    for line in input.lines() {
-        let (left, right) = line
-            .split_once(',')
-            .map(|(l, r)| (SectionRange::from(l), SectionRange::from(r)))
-            .unwrap();
-        if let Some(ol) = left.get_overlap(&right) {
-            result_part2 += 1;
-            if ol.size() == left.size() || ol.size() == right.size() {
-                result_part1 += 1;
-            }
-        }
+    /// Whether one of both assignments contains the other one.
+    pub fn fully_overlaps(&self, other: &Self) -> bool {
+        self.get_overlap(other)
+            .is_some_and(|ol| ol.size() == self.size() || ol.size() == other.size())
     }
+}
 
-    println!("result day 04 part 1: {}", result_part1);
-    assert_eq!(result_part1, 582);
-
-    println!("result day 04 part 2: {}", result_part2);
-    assert_eq!(result_part2, 893);
-
-    Ok(())
+fn parse_pair(line: &str) -> Result<(SectionRange, SectionRange), ParseError> {
+    let (l, r) = split_once(line, line, ",")?;
+    Ok((parse_token(line, l)?, parse_token(line, r)?))
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day04;
 
-    use super::*;
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const STREAMS: bool = true;
+    type Input = Vec<(SectionRange, SectionRange)>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    #[test]
-    fn test_example_part_1() -> Result<()> {
-        let input = "2-4,6-8\n\
-                           2-3,4-5\n\
-                           5-7,7-9\n\
-                           2-8,3-7\n\
-                           6-6,4-6\n\
-                           2-6,4-8";
-        // add your test here
-        let mut result_part1 = 0;
-        let mut result_part2 = 0;
-        for line in input.lines() {
-            let (left, right) = line
-                .split_once(',')
-                .map(|(l, r)| (SectionRange::from(l), SectionRange::from(r)))
-                .unwrap();
-            if let Some(ol) = left.get_overlap(&right) {
-                result_part2 += 1;
-                if ol.size() == left.size() || ol.size() == right.size() {
-                    result_part1 += 1;
-                }
-            }
-        }
-        println!("result example day 04 part 1: {}", result_part1);
-        assert_eq!(result_part1, 2);
-        println!("result example day 04 part 2: {}", result_part2);
-        assert_eq!(result_part2, 4);
-        Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, parse_pair)?)
+    }
+    fn part_one(section_pairs: &Self::Input) -> Result<usize> {
+        let result_part1 = section_pairs
+            .iter()
+            .filter(|(left, right)| left.fully_overlaps(right))
+            .count();
+        Ok(result_part1)
+    }
+    fn part_two(section_pairs: &Self::Input) -> Result<usize> {
+        let result_part2 = section_pairs
+            .iter()
+            .filter(|(left, right)| left.get_overlap(right).is_some())
+            .count();
+        Ok(result_part2)
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let (mut result_part1, mut result_part2) = (0_usize, 0_usize);
+        read_lines(reader, |line| {
+            let (left, right) = parse_pair(line)?;
+            result_part1 += left.fully_overlaps(&right) as usize;
+            result_part2 += left.get_overlap(&right).is_some() as usize;
+            Ok(())
+        })?;
+        Ok(vec![Ok(result_part1.into()), Ok(result_part2.into())])
     }
 }
+
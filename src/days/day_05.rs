@@ -1,22 +1,31 @@
 //!day_05.rs
+//!
+//! Supply stacks, see [`CrateStacks`] for rearrangements by [`CraneCommand`].
 
-use anyhow::Result;
+use crate::parse::{keyword, next_number, parse_lines, split_once, ParseError};
+use crate::repl::{argument, Command, Explore};
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
 use std::collections::{HashMap, VecDeque};
 
-#[derive(Debug)]
-struct CrateStacks {
+/// Stacks of crates, which are numbered from 1, parsed from drawing of stacks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateStacks {
     stacks: HashMap<u8, VecDeque<char>>,
 }
 
-impl From<&str> for CrateStacks {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for CrateStacks {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut cs = CrateStacks {
             stacks: HashMap::new(),
         };
-        for stack in value
+        let stack_numbers = value
             .lines()
             .last()
-            .unwrap()
+            .ok_or(ParseError::end_of(value, "stack numbers"))?;
+        for stack in stack_numbers
             .split_whitespace()
             .filter_map(|c| c.parse::<u8>().ok())
         {
@@ -24,21 +33,27 @@ impl From<&str> for CrateStacks {
         }
         for line in value.lines() {
             for (i, c) in line
-                .chars()
-                .enumerate()
+                .char_indices()
                 .filter(|(_, c)| c.is_ascii_alphabetic())
             {
-                assert_eq!(i % 4, 1);
                 let stack = (1 + i / 4) as u8;
-                cs.stacks.get_mut(&stack).unwrap().push_back(c);
+                match cs.stacks.get_mut(&stack) {
+                    Some(crates) if i % 4 == 1 => crates.push_back(c),
+                    _ => {
+                        let crate_str = &line[i..i + 1];
+                        return Err(ParseError::new(line, crate_str, "crate of numbered stack")
+                            .within(value, line));
+                    }
+                }
             }
         }
-        cs
+        Ok(cs)
     }
 }
 
 impl CrateStacks {
-    fn apply_crane_command(&mut self, crane_command: &CraneCommand) {
+    /// Moves crates of command one at a time like CrateMover 9000.
+    pub fn apply_crane_command(&mut self, crane_command: &CraneCommand) {
         for _i in 0..crane_command.count {
             if let Some(cr) = self
                 .stacks
@@ -53,7 +68,8 @@ impl CrateStacks {
             }
         }
     }
-    fn apply_crane_9001_command(&mut self, crane_command: &CraneCommand) {
+    /// Moves crates of command at once like CrateMover 9001, which keeps their order.
+    pub fn apply_crane_9001_command(&mut self, crane_command: &CraneCommand) {
         let mut intermediate: VecDeque<char> =
             VecDeque::with_capacity(crane_command.count as usize);
         for _i in 0..crane_command.count {
@@ -73,7 +89,8 @@ impl CrateStacks {
                 .push_front(*cr);
         }
     }
-    fn get_top_crates(&self) -> String {
+    /// Top crate of each stack in order of stack numbers.
+    pub fn get_top_crates(&self) -> String {
         let mut top_crates = String::new();
         for stack in 1..=self.stacks.len() as u8 {
             top_crates.push(self.stacks.get(&stack).unwrap()[0]);
@@ -82,89 +99,166 @@ impl CrateStacks {
     }
 }
 
-#[derive(Debug)]
-struct CraneCommand {
+/// Rearrangement step, parsed from e.g. `move 1 from 2 to 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CraneCommand {
     count: u8,
     source: u8,
     target: u8,
 }
 
-impl From<&str> for CraneCommand {
-    fn from(value: &str) -> Self {
-        let mut value_iter = value
-            .split_whitespace()
-            .filter_map(|v| v.parse::<u8>().ok());
-        Self {
-            count: value_iter.next().unwrap(),
-            source: value_iter.next().unwrap(),
-            target: value_iter.next().unwrap(),
-        }
+impl TryFrom<&str> for CraneCommand {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // move 1 from 2 to 1
+        let mut tokens = value.split_whitespace();
+        keyword(value, &mut tokens, "move")?;
+        let count = next_number(value, &mut tokens)?;
+        keyword(value, &mut tokens, "from")?;
+        let source = next_number(value, &mut tokens)?;
+        keyword(value, &mut tokens, "to")?;
+        let target = next_number(value, &mut tokens)?;
+        Ok(Self {
+            count,
+            source,
+            target,
+        })
     }
 }
 
-pub fn day_05() -> Result<()> {
-    let input = include_str!("../../assets/day_05.txt");
-    let (crate_stack_str, crane_commands) = input.split_once("\n\n").unwrap();
-    let mut crate_stack = CrateStacks::from(crate_stack_str);
-    let crane_commands: Vec<CraneCommand> =
-        crane_commands.lines().map(CraneCommand::from).collect();
-    for cr in crane_commands.iter() {
-        crate_stack.apply_crane_command(cr);
-    }
+// crane of REPL, which applies crane commands one by one
+struct CraneExplorer {
+    crate_stacks: CrateStacks,
+    crane_commands: Vec<CraneCommand>,
+    next_command: usize,
+    crane_9001: bool,
+}
 
-    let result_part1 = crate_stack.get_top_crates();
-    println!("result day 05 part 1: {}", result_part1);
-    assert_eq!(result_part1, String::from("QNHWJVJZW"));
+const CRANE_COMMANDS: &[Command] = &[
+    ("step [N]", "applies next N crane commands, default 1"),
+    ("stack [N]", "lists crates of stack N or of all stacks"),
+    ("top", "lists top crate of each stack"),
+    ("crane 9000|9001", "selects crane of following steps"),
+];
 
-    crate_stack = CrateStacks::from(crate_stack_str);
-    for cr in crane_commands.iter() {
-        crate_stack.apply_crane_9001_command(cr);
+impl Explore for CraneExplorer {
+    fn commands(&self) -> &'static [Command] {
+        CRANE_COMMANDS
     }
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "step" => {
+                let steps: usize = argument(args, 0, Some(1))?;
+                let mut applied: Vec<String> = Vec::new();
+                for cr in self
+                    .crane_commands
+                    .iter()
+                    .skip(self.next_command)
+                    .take(steps)
+                {
+                    if self.crane_9001 {
+                        self.crate_stacks.apply_crane_9001_command(cr);
+                    } else {
+                        self.crate_stacks.apply_crane_command(cr);
+                    }
+                    self.next_command += 1;
+                    applied.push(format!(
+                        "{}: move {} from {} to {}",
+                        self.next_command, cr.count, cr.source, cr.target
+                    ));
+                }
+                if applied.is_empty() {
+                    return Ok(String::from("all crane commands applied"));
+                }
+                Ok(applied.join("\n"))
+            }
+            "stack" => {
+                let stacks: Vec<u8> = match args.first() {
+                    Some(_) => vec![argument(args, 0, None)?],
+                    None => (1..=self.crate_stacks.stacks.len() as u8).collect(),
+                };
+                let mut lines: Vec<String> = Vec::new();
+                for stack in stacks {
+                    let crates = self
+                        .crate_stacks
+                        .stacks
+                        .get(&stack)
+                        .ok_or(anyhow!("stack {} does not exist", stack))?;
+                    lines.push(format!("{}: {}", stack, crates.iter().collect::<String>()));
+                }
+                Ok(lines.join("\n"))
+            }
+            "top" => {
+                // empty stacks are shown as blank
+                let top_crates = (1..=self.crate_stacks.stacks.len() as u8)
+                    .map(|s| self.crate_stacks.stacks[&s].front().copied().unwrap_or(' '))
+                    .collect();
+                Ok(top_crates)
+            }
+            "crane" => {
+                self.crane_9001 = match args.first() {
+                    Some(&"9000") => false,
+                    Some(&"9001") => true,
+                    _ => return Err(anyhow!("crane 9000 or 9001 expected")),
+                };
+                Ok(format!("CrateMover {} selected", args[0]))
+            }
+            _ => Err(anyhow!("unknown command '{}'", command)),
+        }
+    }
+}
 
-    let result_part2 = crate_stack.get_top_crates();
-    println!("result day 05 part 2: {}", result_part2);
-    assert_eq!(result_part2, String::from("BPCZJLFJW"));
+pub struct Day05;
 
-    Ok(())
-}
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    type Input = (CrateStacks, Vec<CraneCommand>);
+    type PartOne = String;
+    type PartTwo = String;
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_example_part_1() -> Result<()> {
-        let input = "    [D]    \n\
-                           [N] [C]    \n\
-                           [Z] [M] [P]\n\
-                           1   2   3 \n\
-                           \n\
-                           move 1 from 2 to 1\n\
-                           move 3 from 1 to 3\n\
-                           move 2 from 2 to 1\n\
-                           move 1 from 1 to 2";
-        // add your test here
-        let (crate_stack_str, crane_commands) = input.split_once("\n\n").unwrap();
-        let mut crate_stack = CrateStacks::from(crate_stack_str);
-        let crane_commands: Vec<CraneCommand> =
-            crane_commands.lines().map(CraneCommand::from).collect();
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (crate_stack_str, crane_commands) = split_once(input, input, "\n\n")?;
+        let crate_stack = CrateStacks::try_from(crate_stack_str)?;
+        let crane_commands = parse_lines(crane_commands, |line| {
+            let crane_command = CraneCommand::try_from(line)?;
+            // crane only moves between existing stacks
+            if [crane_command.source, crane_command.target]
+                .iter()
+                .any(|stack| !crate_stack.stacks.contains_key(stack))
+            {
+                return Err(ParseError::new(line, line, "move between numbered stacks"));
+            }
+            Ok(crane_command)
+        })
+        .map_err(|err| err.within(input, crane_commands))?;
+        Ok((crate_stack, crane_commands))
+    }
+    fn part_one((crate_stack, crane_commands): &Self::Input) -> Result<String> {
+        let mut crate_stack = crate_stack.clone();
         for cr in crane_commands.iter() {
             crate_stack.apply_crane_command(cr);
         }
 
         let result_part1 = crate_stack.get_top_crates();
-        println!("result example day 05 part 1: {}", result_part1);
-        assert_eq!(result_part1, String::from("CMZ"));
-
-        crate_stack = CrateStacks::from(crate_stack_str);
+        Ok(result_part1)
+    }
+    fn part_two((crate_stack, crane_commands): &Self::Input) -> Result<String> {
+        let mut crate_stack = crate_stack.clone();
         for cr in crane_commands.iter() {
             crate_stack.apply_crane_9001_command(cr);
         }
 
         let result_part2 = crate_stack.get_top_crates();
-        println!("result example day 05 part 2: {}", result_part2);
-        assert_eq!(result_part2, String::from("MCD"));
-        Ok(())
+        Ok(result_part2)
+    }
+    fn explore((crate_stack, crane_commands): &Self::Input) -> Result<Box<dyn Explore>> {
+        Ok(Box::new(CraneExplorer {
+            crate_stacks: crate_stack.clone(),
+            crane_commands: crane_commands.clone(),
+            next_command: 0,
+            crane_9001: false,
+        }))
     }
 }
+
@@ -1,7 +1,13 @@
 //!day_06.rs
+//!
+//! Tuning trouble, see [`find_marker`] for markers of datastream.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::solution::Solution;
+use anyhow::{Context, Result};
 use std::collections::VecDeque;
+use std::io::BufRead;
 
 #[derive(Default)]
 struct MarkerState {
@@ -28,75 +34,113 @@ impl MarkerState {
     }
 }
 
-pub fn day_06() -> Result<()> {
-    let input = include_str!("../../assets/day_06.txt");
-
-    let mut result_part1 = 0;
-    let mut result_part2 = 0;
-    let mut start_of_paket_marker_state = MarkerState::default();
-    let mut check_start_of_paket_marker = true;
-    let mut start_of_message_marker_state = MarkerState::default();
-    for c in input.chars() {
-        if check_start_of_paket_marker {
-            if let Some(marker_pos) = start_of_paket_marker_state.check_marker(c, 4) {
-                result_part1 = marker_pos;
-                check_start_of_paket_marker = false;
-            }
-        }
-        if let Some(marker_pos) = start_of_message_marker_state.check_marker(c, 14) {
-            result_part2 = marker_pos;
-            break;
+/// Number of chars processed, when the last marker_size chars are all different for the
+/// first time, or 0 if there is no marker.
+pub fn find_marker(signal: &str, marker_size: usize) -> usize {
+    let mut marker_state = MarkerState::default();
+    for c in signal.chars() {
+        if let Some(marker_pos) = marker_state.check_marker(c, marker_size) {
+            return marker_pos;
         }
     }
-    println!("result day 06 part 1: {}", result_part1);
-    assert_eq!(result_part1, 1_920);
+    0
+}
 
-    println!("result day 06 part 2: {}", result_part2);
-    assert_eq!(result_part2, 2_334);
+const LETTER: &str = "ASCII letter of signal";
 
-    Ok(())
+// signal is a single line of ASCII letters, surrounded by white space
+fn parse_signal(input: &str) -> Result<&str, ParseError> {
+    let signal = input.trim_matches(|c: char| c.is_ascii_whitespace());
+    match signal
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_alphabetic())
+    {
+        Some((i, c)) => Err(ParseError::new(input, &signal[i..i + c.len_utf8()], LETTER)),
+        None => Ok(signal),
+    }
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day06;
 
-    use super::*;
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const STREAMS: bool = true;
+    type Input = String;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    #[test]
-    fn test_example_part() -> Result<()> {
-        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n\
-                           bvwbjplbgvbhsrlpgdmjqwftvncz\n\
-                           nppdvjthqldpwncqszvftbrmjlhg\n\
-                           nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg\n\
-                           zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
-        // add your test here
-        let test_vector_1 = [7, 5, 6, 10, 11];
-        let test_vector_2 = [19, 23, 23, 29, 26];
-        for (i, line) in input.lines().enumerate() {
-            let mut start_of_paket_marker_state = MarkerState::default();
-            let mut check_start_of_paket_marker = true;
-            let mut start_of_message_marker_state = MarkerState::default();
-            for c in line.chars() {
-                if check_start_of_paket_marker {
-                    if let Some(marker_pos) = start_of_paket_marker_state.check_marker(c, 4) {
-                        let result_part1 = marker_pos;
-                        check_start_of_paket_marker = false;
-                        println!("result example {} day 06 part 1: {}", i + 1, result_part1);
-                        assert_eq!(result_part1, test_vector_1[i]);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_signal(input)?.to_string())
+    }
+    fn part_one(signal: &Self::Input) -> Result<usize> {
+        let result_part1 = find_marker(signal, 4);
+        Ok(result_part1)
+    }
+    fn part_two(signal: &Self::Input) -> Result<usize> {
+        let result_part2 = find_marker(signal, 14);
+        Ok(result_part2)
+    }
+    // signal may be too long to hold it as line, it is checked to its end like by parse()
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let mut markers = [
+            (4, MarkerState::default(), 0),
+            (14, MarkerState::default(), 0),
+        ];
+        let mut started = false;
+        // first white space after signal, which must be trailing white space
+        let mut white_space: Option<(usize, usize, u8)> = None;
+        let (mut line, mut column) = (1, 0);
+        loop {
+            let buffer = reader.fill_buf().context("failed to read input")?;
+            if buffer.is_empty() {
+                break;
+            }
+            for b in buffer.iter() {
+                column += 1;
+                if b.is_ascii_whitespace() {
+                    if started && white_space.is_none() {
+                        white_space = Some((line, column, *b));
+                    }
+                    if *b == b'\n' {
+                        (line, column) = (line + 1, 0);
                     }
+                    continue;
                 }
-                if let Some(marker_pos) = start_of_message_marker_state.check_marker(c, 14) {
-                    let result_part2 = marker_pos;
-                    println!("result example {} day 06 part 2: {}", i + 1, result_part2);
-                    assert_eq!(result_part2, test_vector_2[i]);
-                    break;
+                let (line, column, found) = match white_space {
+                    Some(invalid) => invalid,
+                    None if !b.is_ascii_alphabetic() => (line, column, *b),
+                    None => {
+                        started = true;
+                        for (marker_size, marker_state, marker_pos) in markers.iter_mut() {
+                            if *marker_pos == 0 {
+                                *marker_pos = marker_state
+                                    .check_marker(*b as char, *marker_size)
+                                    .unwrap_or(0);
+                            }
+                        }
+                        continue;
+                    }
+                };
+                return Err(ParseError {
+                    day: None,
+                    line,
+                    column,
+                    expected: LETTER.to_string(),
+                    // same as parse(), but non-ASCII chars are cut to their first byte
+                    found: match found {
+                        b'\n' => String::new(),
+                        _ => String::from_utf8_lossy(&[found]).into_owned(),
+                    },
                 }
+                .into());
             }
+            let consumed = buffer.len();
+            reader.consume(consumed);
         }
-
-        //let result_part2 = 0;
-        //println!("result example day 06 part 2: {}", result_part2);
-        //assert_eq!(result_part2, 1);
-        Ok(())
+        Ok(markers
+            .iter()
+            .map(|(_, _, marker_pos)| Ok((*marker_pos).into()))
+            .collect())
     }
 }
+
@@ -1,6 +1,11 @@
 //!day_07.rs
+//!
+//! No space left on device, see [`FileTree`] for sizes of directories.
 
-use anyhow::Result;
+use crate::parse::{number, parse_token, split_once, ParseError};
+use crate::repl::{argument, Command, Explore};
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
 use my_lib::my_tree::TreeNode;
 use std::rc::Rc;
 
@@ -10,13 +15,15 @@ struct File {
     size: usize,
 }
 
-impl From<&str> for File {
-    fn from(value: &str) -> Self {
-        let (size, name) = value
-            .split_once(' ')
-            .map(|(s, n)| (s.parse::<usize>().expect("bad input"), n.to_string()))
-            .unwrap();
-        Self { name, size }
+impl TryFrom<&str> for File {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (size, name) = split_once(value, value, " ")?;
+        Ok(Self {
+            name: name.to_string(),
+            size: number(value, size)?,
+        })
     }
 }
 
@@ -42,12 +49,25 @@ impl Directrory {
     }
 }
 
-struct FileTree {
+/// Directories and files of a terminal output of `cd` and `ls` commands in a filesystem
+/// of 70000000 size, parsed with `FileTree::try_from()`.
+pub struct FileTree {
     tree_root: Rc<TreeNode<Directrory>>,
     children_capacity: usize,
     filesystem_size: usize,
 }
 
+impl TryFrom<&str> for FileTree {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let file_tree = FileTree::new(5, 70_000_000);
+        file_tree.parse(value)?;
+        file_tree.calc_dir_sizes();
+        Ok(file_tree)
+    }
+}
+
 impl FileTree {
     fn new(children_capacity: usize, filesystem_size: usize) -> Self {
         let root = Directrory::new("/".into());
@@ -57,26 +77,29 @@ impl FileTree {
             filesystem_size,
         }
     }
-    fn parse(&self, input: &str) {
+    fn parse(&self, input: &str) -> Result<(), ParseError> {
         let mut current_dir = self.tree_root.clone();
         for line in input.lines().filter(|l| !(*l == "$ cd /" || *l == "$ ls")) {
-            if line.starts_with("dir") {
-                let dir_name = line.split_once(' ').unwrap().1.to_string();
-                let new_dir = Directrory::new(dir_name);
+            if let Some(dir_name) = line.strip_prefix("dir ") {
+                let new_dir = Directrory::new(dir_name.to_string());
                 current_dir.add_child(new_dir, self.children_capacity);
             } else if line == "$ cd .." {
-                current_dir = current_dir.get_parent().unwrap();
-            } else if line.starts_with("$ cd ") {
-                let dir_name = line.split_once("$ cd ").unwrap().1.to_string();
+                current_dir = current_dir.get_parent().ok_or(ParseError::new(
+                    input,
+                    line,
+                    "cd into sub directory of /",
+                ))?;
+            } else if let Some(dir_name) = line.strip_prefix("$ cd ") {
                 current_dir = current_dir
                     .iter_children()
                     .find(|c| c.get_value().name == dir_name)
-                    .unwrap();
+                    .ok_or(ParseError::new(input, dir_name, "listed directory"))?;
             } else {
-                let file = File::from(line);
+                let file: File = parse_token(input, line)?;
                 current_dir.get_mut_value().add_file(file);
             }
         }
+        Ok(())
     }
     fn calc_dir_sizes(&self) {
         for node in self.tree_root.iter_post_order_traversal() {
@@ -87,80 +110,142 @@ impl FileTree {
             node.get_mut_value().calc_total_size(size_of_sub_dirs);
         }
     }
-    fn sum_dir_size_bounded(&self, upper_bound: usize) -> usize {
+    /// Total size of root directory, which includes files of all sub directories.
+    pub fn total_size(&self) -> usize {
+        self.tree_root.get_value().total_size
+    }
+    /// Sum of total sizes of all directories with at most upper_bound total size.
+    pub fn sum_dir_size_bounded(&self, upper_bound: usize) -> usize {
         self.tree_root
             .iter_post_order_traversal()
             .filter(|c| c.get_value().total_size <= upper_bound)
             .map(|c| c.get_value().total_size)
             .sum::<usize>()
     }
-    fn find_smallest_dir_to_delete(&self, min_required_free_size: usize) -> usize {
-        assert!(self.filesystem_size >= self.tree_root.get_value().total_size);
-        let current_free_size = self.filesystem_size - self.tree_root.get_value().total_size;
-        assert!(current_free_size < min_required_free_size);
+    /// Total size of smallest directory, which frees enough space when deleted.
+    ///
+    /// # Errors
+    ///
+    /// Fails if files exceed filesystem, it already has enough free space or no directory
+    /// frees enough space.
+    pub fn find_smallest_dir_to_delete(&self, min_required_free_size: usize) -> Result<usize> {
+        let current_free_size = self
+            .filesystem_size
+            .checked_sub(self.total_size())
+            .ok_or_else(|| anyhow!("files exceed filesystem of {}", self.filesystem_size))?;
+        if current_free_size >= min_required_free_size {
+            return Err(anyhow!("enough free space already: {}", current_free_size));
+        }
         let min_delete_size = min_required_free_size - current_free_size;
         self.tree_root
             .iter_post_order_traversal()
             .filter(|d| d.get_value().total_size >= min_delete_size)
             .map(|d| d.get_value().total_size)
             .min()
-            .unwrap()
+            .ok_or_else(|| anyhow!("no directory frees {}", min_delete_size))
     }
 }
 
-pub fn day_07() -> Result<()> {
-    let input = include_str!("../../assets/day_07.txt");
-    let file_tree = FileTree::new(5, 70_000_000);
-    file_tree.parse(input);
-    file_tree.calc_dir_sizes();
-    let result_part1 = file_tree.sum_dir_size_bounded(100_000);
-    println!("result day 07 part 1: {}", result_part1);
-    assert_eq!(result_part1, 1_644_735);
-    let result_part2 = file_tree.find_smallest_dir_to_delete(30_000_000);
-    println!("result day 07 part 2: {}", result_part2);
-    assert_eq!(result_part2, 1_300_850);
-    Ok(())
+// shell of REPL, which walks the directories of file tree
+struct FileTreeExplorer {
+    tree_root: Rc<TreeNode<Directrory>>,
+    current_dir: Rc<TreeNode<Directrory>>,
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_example_part() -> Result<()> {
-        let input = "$ cd /\n\
-                           $ ls\n\
-                           dir a\n\
-                           14848514 b.txt\n\
-                           8504156 c.dat\n\
-                           dir d\n\
-                           $ cd a\n\
-                           $ ls\n\
-                           dir e\n\
-                           29116 f\n\
-                           2557 g\n\
-                           62596 h.lst\n\
-                           $ cd e\n\
-                           $ ls\n\
-                           584 i\n\
-                           $ cd ..\n\
-                           $ cd ..\n\
-                           $ cd d\n\
-                           $ ls\n\
-                           4060174 j\n\
-                           8033020 d.log\n\
-                           5626152 d.ext\n\
-                           7214296 k";
-        let file_tree = FileTree::new(5, 70_000_000);
-        file_tree.parse(input);
-        file_tree.calc_dir_sizes();
+const FILE_TREE_COMMANDS: &[Command] = &[
+    ("cd DIR", "changes into sub directory DIR, .. or /"),
+    ("ls", "lists directories and files of current directory"),
+    ("du [DIR]", "shows total size of current directory or DIR"),
+    ("pwd", "shows path of current directory"),
+];
+
+impl FileTreeExplorer {
+    fn sub_dir(&self, name: &str) -> Result<Rc<TreeNode<Directrory>>> {
+        self.current_dir
+            .iter_children()
+            .find(|c| c.get_value().name == name)
+            .ok_or(anyhow!("directory {} does not exist", name))
+    }
+}
+
+impl Explore for FileTreeExplorer {
+    fn commands(&self) -> &'static [Command] {
+        FILE_TREE_COMMANDS
+    }
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "cd" => {
+                let dir_name: String = argument(args, 0, None)?;
+                self.current_dir = match dir_name.as_str() {
+                    "/" => self.tree_root.clone(),
+                    ".." => self
+                        .current_dir
+                        .get_parent()
+                        .unwrap_or(self.tree_root.clone()),
+                    name => self.sub_dir(name)?,
+                };
+                Ok(String::new())
+            }
+            "ls" => {
+                let mut entries: Vec<String> = self
+                    .current_dir
+                    .iter_children()
+                    .map(|c| format!("dir {}", c.get_value().name))
+                    .collect();
+                for file in self.current_dir.get_value().files.iter() {
+                    entries.push(format!("{} {}", file.size, file.name));
+                }
+                Ok(entries.join("\n"))
+            }
+            "du" => {
+                let dir = match args.first() {
+                    Some(name) => self.sub_dir(name)?,
+                    None => self.current_dir.clone(),
+                };
+                let total_size = dir.get_value().total_size;
+                Ok(total_size.to_string())
+            }
+            "pwd" => {
+                let mut path: Vec<String> = Vec::new();
+                let mut dir = Some(self.current_dir.clone());
+                while let Some(node) = dir {
+                    path.push(node.get_value().name.clone());
+                    dir = node.get_parent();
+                }
+                // root is named /
+                path.pop();
+                path.reverse();
+                Ok(format!("/{}", path.join("/")))
+            }
+            _ => Err(anyhow!("unknown command '{}'", command)),
+        }
+    }
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    type Input = FileTree;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(FileTree::try_from(input)?)
+    }
+    fn part_one(file_tree: &Self::Input) -> Result<usize> {
         let result_part1 = file_tree.sum_dir_size_bounded(100_000);
-        println!("result example day 07 part 1: {}", result_part1);
-        assert_eq!(result_part1, 95_437);
-        let result_part2 = file_tree.find_smallest_dir_to_delete(30_000_000);
-        println!("result example day 07 part 2: {}", result_part2);
-        assert_eq!(result_part2, 24_933_642);
-        Ok(())
+        Ok(result_part1)
+    }
+    fn part_two(file_tree: &Self::Input) -> Result<usize> {
+        let result_part2 = file_tree.find_smallest_dir_to_delete(30_000_000)?;
+        Ok(result_part2)
+    }
+    fn explore(file_tree: &Self::Input) -> Result<Box<dyn Explore>> {
+        Ok(Box::new(FileTreeExplorer {
+            tree_root: file_tree.tree_root.clone(),
+            current_dir: file_tree.tree_root.clone(),
+        }))
     }
 }
+
@@ -1,121 +1,114 @@
 //!day_08.rs
+//!
+//! Treetop tree house, see [`Forest`] for visible trees and scenic scores.
 
+use crate::grid::{Grid, CARDINALS, EAST, NORTH, SOUTH, WEST};
+use crate::parse::ParseError;
+use crate::solution::Solution;
 use anyhow::Result;
-use my_lib::{my_map_point::MapPoint, my_map_two_dim::MyMap2D};
 
-// taken from ../../assets/day_08.txt
-const X: usize = 99;
-const Y: usize = 99;
-
-#[derive(Default)]
-struct Forest<const X: usize, const Y: usize> {
-    trees: MyMap2D<u32, X, Y>,
-    visible: MyMap2D<bool, X, Y>,
-    scenic_score: MyMap2D<u32, X, Y>,
+/// Grid of tree heights, sized by input and parsed with `Forest::try_from()`.
+#[derive(Debug, Clone)]
+pub struct Forest {
+    trees: Grid<u32>,
 }
 
-impl<const X: usize, const Y: usize> From<&str> for Forest<X, Y> {
-    fn from(value: &str) -> Self {
-        let mut f = Self::default();
-        for (y, line) in value.lines().enumerate() {
-            for (x, t) in line.chars().map(|c| c.to_digit(10).unwrap()).enumerate() {
-                let position = MapPoint::<X, Y>::new(x, y);
-                f.trees.set(position, t);
-                // set visibility of edge
-                if !position.map_position().is_center() {
-                    f.visible.set(position, true);
-                }
-            }
-        }
-        f
+impl TryFrom<&str> for Forest {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let trees = Grid::parse(value, |c, token| {
+            c.to_digit(10)
+                .ok_or_else(|| ParseError::new(value, token, "tree height digit"))
+        })?;
+        Ok(Self { trees })
     }
 }
 
-impl<const X: usize, const Y: usize> Forest<X, Y> {
-    fn check_visbility(&mut self) {
-        for (edge_point, edge_size) in self.trees.iter_edge((0, 0).into(), false) {
-            if edge_point.map_position().is_cardinal() {
+impl Forest {
+    /// Number of trees, which are visible from outside of grid.
+    pub fn count_visible_trees(&self) -> usize {
+        self.visibility().iter().filter(|(_, v)| **v).count()
+    }
+    /// Highest scenic score of all trees.
+    pub fn highest_scenic_score(&self) -> u32 {
+        self.trees
+            .iter()
+            .map(|(position, _)| self.scenic_score(position))
+            .max()
+            .unwrap()
+    }
+    fn visibility(&self) -> Grid<bool> {
+        let (width, height) = (self.trees.width(), self.trees.height());
+        let mut visible = Grid::new(width, height, false);
+        // look into forest from each tree of edge
+        for (edge_point, edge_size) in self.trees.iter() {
+            let (x, y) = edge_point;
+            let inwards = [
+                (x == 0, EAST),
+                (x + 1 == width, WEST),
+                (y == 0, SOUTH),
+                (y + 1 == height, NORTH),
+            ];
+            for (_, orientation) in inwards.iter().filter(|(is_edge, _)| *is_edge) {
+                visible[edge_point] = true;
                 let mut max_size = *edge_size;
-                for (position, size) in self
-                    .trees
-                    .iter_orientation(edge_point, edge_point.map_position().flip())
-                    .skip(1)
-                {
-                    *self.visible.get_mut(position) |= *size > max_size;
-                    max_size = max_size.max(*size);
+                for position in self.trees.ray(edge_point, *orientation) {
+                    let size = self.trees[position];
+                    visible[position] |= size > max_size;
+                    max_size = max_size.max(size);
                 }
             }
         }
+        visible
     }
-    fn num_visible_trees(&self) -> usize {
-        self.visible.iter().filter(|(_, v)| **v).count()
-    }
-    fn calc_scenic_score(&mut self) {
-        for (position, pos_size) in self
-            .trees
+    fn scenic_score(&self, position: (usize, usize)) -> u32 {
+        let pos_size = self.trees[position];
+        CARDINALS
             .iter()
-            .filter(|(p, _)| p.map_position().is_center())
-        {
-            let mut scenic_scores: Vec<u32> = Vec::with_capacity(4);
-            for orientation in position.available_cardinal_directions().iter() {
+            .map(|orientation| {
                 let mut scenic_score = 0;
-                for (_, size) in self.trees.iter_orientation(position, *orientation).skip(1) {
+                for next in self.trees.ray(position, *orientation) {
                     scenic_score += 1;
-                    if size >= pos_size {
+                    if self.trees[next] >= pos_size {
                         break;
                     }
                 }
-                scenic_scores.push(scenic_score);
-            }
-            self.scenic_score
-                .set(position, scenic_scores.iter().product());
-        }
-    }
-    fn max_scenic_score(&self) -> u32 {
-        self.scenic_score.iter().map(|(_, v)| *v).max().unwrap()
+                scenic_score
+            })
+            .product()
     }
 }
 
-pub fn day_08() -> Result<()> {
-    let input = include_str!("../../assets/day_08.txt");
-    let mut forest = Forest::<X, Y>::from(input);
-    forest.check_visbility();
-    let result_part1 = forest.num_visible_trees();
-    println!("result day 08 part 1: {}", result_part1);
-    assert_eq!(result_part1, 1_669);
-    forest.calc_scenic_score();
-    let result_part2 = forest.max_scenic_score();
-    println!("result day 08 part 2: {}", result_part2);
-    assert_eq!(result_part2, 331_344);
-    Ok(())
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    type Input = Forest;
+    type PartOne = usize;
+    type PartTwo = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Forest::try_from(input)?)
+    }
+    fn part_one(forest: &Self::Input) -> Result<usize> {
+        let result_part1 = forest.count_visible_trees();
+        Ok(result_part1)
+    }
+    fn part_two(forest: &Self::Input) -> Result<u32> {
+        let result_part2 = forest.highest_scenic_score();
+        Ok(result_part2)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    const XT: usize = 5;
-    const YT: usize = 5;
 
     #[test]
-    fn test_example_part() -> Result<()> {
-        let input = include_str!("../../assets/day_08.txt");
-        eprintln!("X: {}", input.lines().next().unwrap().chars().count());
-        eprintln!("Y: {}", input.lines().count());
-        let input = "30373\n\
-                           25512\n\
-                           65332\n\
-                           33549\n\
-                           35390";
-        let mut forest = Forest::<XT, YT>::from(input);
-        forest.check_visbility();
-        let result_part1 = forest.num_visible_trees();
-        println!("result example day 08 part 1: {}", result_part1);
-        assert_eq!(result_part1, 21);
-        forest.calc_scenic_score();
-        let result_part2 = forest.max_scenic_score();
-        println!("result example day 08 part 2: {}", result_part2);
-        assert_eq!(result_part2, 8);
-        Ok(())
+    fn test_parse_errors() {
+        let err = Forest::try_from("303\n2551\n653").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
     }
 }
@@ -1,129 +1,190 @@
 //!day_09.rs
+//!
+//! Rope bridge, see [`Rope`] for positions visited by tail of a rope moved by [`RopeAction`].
 
+use crate::answer::Answer;
+use crate::parse::{number, parse_lines, read_lines, split_once, ParseError};
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::visualize::{FrameSink, Render};
 use anyhow::Result;
 use my_lib::my_geometry::my_point::Point;
+use std::collections::HashSet;
+use std::io::BufRead;
 
-struct RopeAction {
+/// Motion of head of rope, parsed from e.g. `R 4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RopeAction {
     direction: Point,
     steps: u32,
 }
 
-impl From<&str> for RopeAction {
-    fn from(value: &str) -> Self {
-        let (c, steps) = value
-            .split_once(' ')
-            .map(|(c, s)| (c, s.parse::<u32>().expect("bad input")))
-            .unwrap();
+impl TryFrom<&str> for RopeAction {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (c, steps) = split_once(value, value, " ")?;
         let direction = match c {
             "U" => Point::new(0, -1),
             "D" => Point::new(0, 1),
             "L" => Point::new(-1, 0),
             "R" => Point::new(1, 0),
-            _ => panic!("bad input"),
+            _ => return Err(ParseError::new(value, c, "U, D, L or R")),
         };
-        Self { direction, steps }
+        Ok(Self {
+            direction,
+            steps: number(value, steps)?,
+        })
     }
 }
 
-struct Rope {
+/// Rope of knots, which all start at the same position.
+#[derive(Debug, Clone)]
+pub struct Rope {
     knots: Vec<Point>,
-    tail_seen: Vec<Point>,
+    tail_seen: HashSet<Point>,
 }
 
 impl Rope {
-    fn new(size: usize) -> Self {
+    /// Rope with size knots, including head and tail.
+    ///
+    /// # Panics
+    ///
+    /// Panics if size is less than 2.
+    pub fn new(size: usize) -> Self {
         assert!(size > 1);
         Rope {
             knots: vec![Point::default(); size],
-            tail_seen: vec![Point::default()],
+            tail_seen: HashSet::from([Point::default()]),
         }
     }
-    fn apply(&mut self, action: &RopeAction) {
-        let index_tail = self.knots.len() - 1;
+    /// Moves head by all steps of action, all other knots follow.
+    pub fn apply(&mut self, action: &RopeAction) {
         for _i in 0..action.steps {
-            let mut previous_knot: Option<Point> = None;
-            for (knot_index, knot) in self.knots.iter_mut().enumerate() {
-                match previous_knot {
-                    Some(pk) => {
-                        let mut delta = pk.subtract(*knot);
-                        if delta.x.abs() > 1 || delta.y.abs() > 1 {
-                            delta.x = delta.x.max(-1).min(1);
-                            delta.y = delta.y.max(-1).min(1);
-                            *knot = knot.add(delta);
-                            if knot_index == index_tail && !self.tail_seen.contains(knot) {
-                                self.tail_seen.push(*knot);
-                            }
+            self.step(action.direction);
+        }
+    }
+    /// Number of different positions visited by tail, including start position.
+    pub fn count_tail_positions(&self) -> usize {
+        self.tail_seen.len()
+    }
+    // moves head one step in direction, all other knots follow
+    fn step(&mut self, direction: Point) {
+        let index_tail = self.knots.len() - 1;
+        let mut previous_knot: Option<Point> = None;
+        for (knot_index, knot) in self.knots.iter_mut().enumerate() {
+            match previous_knot {
+                Some(pk) => {
+                    let mut delta = pk.subtract(*knot);
+                    if delta.x.abs() > 1 || delta.y.abs() > 1 {
+                        delta.x = delta.x.clamp(-1, 1);
+                        delta.y = delta.y.clamp(-1, 1);
+                        *knot = knot.add(delta);
+                        if knot_index == index_tail {
+                            self.tail_seen.insert(*knot);
                         }
                     }
-                    None => {
-                        *knot = knot.add(action.direction);
-                    }
                 }
-                previous_knot = Some(*knot);
+                None => {
+                    *knot = knot.add(direction);
+                }
             }
+            previous_knot = Some(*knot);
         }
     }
 }
 
-pub fn day_09() -> Result<()> {
-    let input = include_str!("../../assets/day_09.txt");
-    let rope_actions: Vec<RopeAction> = input.lines().map(RopeAction::from).collect();
-    let mut rope = Rope::new(2);
-    let mut long_rope = Rope::new(10);
-    for ra in rope_actions.iter() {
-        rope.apply(ra);
-        long_rope.apply(ra);
-    }
-    let result_part1 = rope.tail_seen.len();
-    println!("result day 09 part 1: {}", result_part1);
-    assert_eq!(result_part1, 6_311);
+// size of window around head, which is drawn
+const WINDOW_WIDTH: i64 = 41;
+const WINDOW_HEIGHT: i64 = 21;
 
-    let result_part2 = long_rope.tail_seen.len();
-    println!("result day 09 part 2: {}", result_part2);
-    assert_eq!(result_part2, 2_482);
-    Ok(())
+impl Render for Rope {
+    fn render(&self) -> String {
+        let head = self.knots[0];
+        let left = head.x - WINDOW_WIDTH / 2;
+        let top = head.y - WINDOW_HEIGHT / 2;
+        let mut window = vec![vec!['.'; WINDOW_WIDTH as usize]; WINDOW_HEIGHT as usize];
+        let mut draw = |pos: Point, c: char| {
+            let (x, y) = (pos.x - left, pos.y - top);
+            if (0..WINDOW_WIDTH).contains(&x) && (0..WINDOW_HEIGHT).contains(&y) {
+                window[y as usize][x as usize] = c;
+            }
+        };
+        for seen in self.tail_seen.iter() {
+            draw(*seen, '#');
+        }
+        draw(Point::default(), 's');
+        // draw knots from tail to head, so that knots in front hide knots behind
+        let index_tail = self.knots.len() - 1;
+        for (knot_index, knot) in self.knots.iter().enumerate().rev() {
+            let c = match knot_index {
+                0 => 'H',
+                _ if index_tail == 1 => 'T',
+                _ => char::from_digit(knot_index as u32, 10).unwrap_or('?'),
+            };
+            draw(*knot, c);
+        }
+        let rows: Vec<String> = window.into_iter().map(String::from_iter).collect();
+        rows.join("\n")
+    }
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day09;
 
-    use super::*;
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const STREAMS: bool = true;
+    type Input = Vec<RopeAction>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    #[test]
-    fn test_example_part() -> Result<()> {
-        let input = "R 4\n\
-                           U 4\n\
-                           L 3\n\
-                           D 1\n\
-                           R 4\n\
-                           D 1\n\
-                           L 5\n\
-                           R 2";
-        let rope_actions: Vec<RopeAction> = input.lines().map(RopeAction::from).collect();
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, RopeAction::try_from)?)
+    }
+    fn part_one(rope_actions: &Self::Input) -> Result<usize> {
         let mut rope = Rope::new(2);
         for ra in rope_actions.iter() {
             rope.apply(ra);
         }
-        let result_part1 = rope.tail_seen.len();
-        println!("result example day 09 part 1: {}", result_part1);
-        assert_eq!(result_part1, 13);
-
-        let input = "R 5\n\
-                           U 8\n\
-                           L 8\n\
-                           D 3\n\
-                           R 17\n\
-                           D 10\n\
-                           L 25\n\
-                           U 20";
-        let rope_actions: Vec<RopeAction> = input.lines().map(RopeAction::from).collect();
-        let mut rope = Rope::new(10);
+        let result_part1 = rope.count_tail_positions();
+        Ok(result_part1)
+    }
+    fn part_two(rope_actions: &Self::Input) -> Result<usize> {
+        let mut long_rope = Rope::new(10);
         for ra in rope_actions.iter() {
-            rope.apply(ra);
+            long_rope.apply(ra);
+        }
+        let result_part2 = long_rope.count_tail_positions();
+        Ok(result_part2)
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let (mut rope, mut long_rope) = (Rope::new(2), Rope::new(10));
+        read_lines(reader, |line| {
+            let ra = RopeAction::try_from(line)?;
+            rope.apply(&ra);
+            long_rope.apply(&ra);
+            Ok(())
+        })?;
+        Ok(vec![
+            Ok(rope.count_tail_positions().into()),
+            Ok(long_rope.count_tail_positions().into()),
+        ])
+    }
+    fn visualize(rope_actions: &Self::Input, part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        // one frame per step of head
+        let mut rope = Rope::new(if part == Part::One { 2 } else { 10 });
+        if !sink.frame(&rope)? {
+            return Ok(());
+        }
+        for ra in rope_actions.iter() {
+            for _i in 0..ra.steps {
+                rope.step(ra.direction);
+                if !sink.frame(&rope)? {
+                    return Ok(());
+                }
+            }
         }
-        let result_part2 = rope.tail_seen.len();
-        println!("result example day 09 part 2: {}", result_part2);
-        assert_eq!(result_part2, 36);
         Ok(())
     }
 }
+
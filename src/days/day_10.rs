@@ -1,31 +1,56 @@
 //!day_10.rs
+//!
+//! Cathode-ray tube, see [`run_register`] for signal strength and CRT letters of a program.
 
-use anyhow::Result;
-use my_lib::my_map_two_dim::MyMap2D;
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::ocr;
+use crate::parse::{next_number, next_token, parse_lines, read_lines, ParseError};
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
+use std::io::BufRead;
 
-// size of CRT
-const X: usize = 40;
-const Y: usize = 6;
-
-struct SignalRegister<const X: usize, const Y: usize> {
+/// CPU register and CRT with one row of pixels per `delta_cycle` cycles, which draws a pixel
+/// each cycle.
+pub struct SignalRegister {
     x: i32,
     current_cycle: i32,
     check_cycle: i32,
     delta_cycle: i32,
     sum_check_cycle_x: i32,
-    crt: MyMap2D<char, X, Y>,
+    crt: Grid<char>,
 }
 
-impl<const X: usize, const Y: usize> SignalRegister<X, Y> {
-    fn new(initial_check_cycle: i32, delta_cycle: i32) -> Self {
+impl SignalRegister {
+    fn new(initial_check_cycle: i32, delta_cycle: i32, rows: usize) -> Self {
         SignalRegister {
             x: 1,
             current_cycle: 0,
             check_cycle: initial_check_cycle,
             delta_cycle,
             sum_check_cycle_x: 0,
-            crt: MyMap2D::<char, X, Y>::default(),
+            crt: Grid::new(delta_cycle as usize, rows, '.'),
+        }
+    }
+    /// Sum of signal strengths of checked cycles, e.g. 20th, 60th, 100th and so on.
+    pub fn signal_strength(&self) -> i32 {
+        self.sum_check_cycle_x
+    }
+    /// Lines of drawn pixels, `#` for lit and `.` for dark pixels.
+    pub fn crt_image(&self) -> String {
+        format!("{}", self.crt).trim().to_string()
+    }
+    /// Letters drawn on CRT. Fails with the pixels of the first glyph, which is no letter.
+    pub fn crt_letters(&self) -> Result<String> {
+        let rows = (self.current_cycle as usize).div_ceil(self.delta_cycle as usize);
+        if rows != ocr::GLYPH_HEIGHT {
+            return Err(anyhow!(
+                "letters are {} pixels high, program draws {} rows",
+                ocr::GLYPH_HEIGHT,
+                rows
+            ));
         }
+        ocr::recognize(&self.crt)
     }
     fn apply(&mut self, register_command: &RegisterCommand) {
         let next_cycle = self.current_cycle + register_command.cycle_time;
@@ -37,8 +62,10 @@ impl<const X: usize, const Y: usize> SignalRegister<X, Y> {
             } else {
                 '.'
             };
-            self.crt
-                .set((pixel_x as usize, pixel_y as usize).into(), pixel);
+            // pixels below the CRT are not drawn
+            if self.crt.contains((pixel_x as usize, pixel_y as usize)) {
+                self.crt[(pixel_x as usize, pixel_y as usize)] = pixel;
+            }
         }
         self.current_cycle = next_cycle;
         if self.current_cycle >= self.check_cycle {
@@ -49,63 +76,76 @@ impl<const X: usize, const Y: usize> SignalRegister<X, Y> {
     }
 }
 
-#[derive(Debug)]
-struct RegisterCommand {
+/// Instruction of CPU, parsed from `noop` or e.g. `addx -5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegisterCommand {
     cycle_time: i32,
     delta_x: i32,
 }
 
-impl From<&str> for RegisterCommand {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for RegisterCommand {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut split_value = value.split_whitespace();
-        if let Some(c) = split_value.next() {
-            match c {
-                "noop" => {
-                    return RegisterCommand {
-                        cycle_time: 1,
-                        delta_x: 0,
-                    }
-                }
-                "addx" => {
-                    if let Some(x) = split_value.next() {
-                        let delta_x = x.parse::<i32>().expect("bad input");
-                        return RegisterCommand {
-                            cycle_time: 2,
-                            delta_x,
-                        };
-                    }
-                }
-                _ => (),
-            }
+        match next_token(value, &mut split_value, "noop or addx")? {
+            "noop" => Ok(RegisterCommand {
+                cycle_time: 1,
+                delta_x: 0,
+            }),
+            "addx" => Ok(RegisterCommand {
+                cycle_time: 2,
+                delta_x: next_number(value, &mut split_value)?,
+            }),
+            c => Err(ParseError::new(value, c, "noop or addx")),
         }
-        panic!("bad input");
     }
 }
 
-pub fn day_10() -> Result<()> {
-    let input = include_str!("../../assets/day_10.txt");
-    let register_commands: Vec<RegisterCommand> =
-        input.lines().map(RegisterCommand::from).collect();
-    let mut signal_register = SignalRegister::<X, Y>::new(20, 40);
+/// Runs program on CPU with CRT of 40 columns and as many rows as the program needs, e.g. 6 rows
+/// for 240 cycles. Signal strength is checked from 20th cycle every 40 cycles.
+pub fn run_register(register_commands: &[RegisterCommand]) -> SignalRegister {
+    let cycles: i32 = register_commands.iter().map(|rc| rc.cycle_time).sum();
+    let rows = (cycles as usize).div_ceil(40);
+    let mut signal_register = SignalRegister::new(20, 40, rows);
     for rc in register_commands.iter() {
         signal_register.apply(rc);
     }
+    signal_register
+}
 
-    let result_part1 = signal_register.sum_check_cycle_x;
-    println!("result day 10 part 1: {}", result_part1);
-    assert_eq!(result_part1, 14_860);
+pub struct Day10;
 
-    let result_part2 = format!("{}", signal_register.crt);
-    // result in letters: RGZEHURK
-    let test_part2 = "###...##..####.####.#..#.#..#.###..#..#.\n\
-                            #..#.#..#....#.#....#..#.#..#.#..#.#.#..\n\
-                            #..#.#......#..###..####.#..#.#..#.##...\n\
-                            ###..#.##..#...#....#..#.#..#.###..#.#..\n\
-                            #.#..#..#.#....#....#..#.#..#.#.#..#.#..\n\
-                            #..#..###.####.####.#..#..##..#..#.#..#.";
-    println!("result day 10 part 2:\n{}", result_part2.trim());
-    assert_eq!(result_part2.trim(), test_part2);
-    Ok(())
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const STREAMS: bool = true;
+    type Input = Vec<RegisterCommand>;
+    type PartOne = i32;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, RegisterCommand::try_from)?)
+    }
+    fn part_one(register_commands: &Self::Input) -> Result<i32> {
+        let result_part1 = run_register(register_commands).signal_strength();
+        Ok(result_part1)
+    }
+    fn part_two(register_commands: &Self::Input) -> Result<String> {
+        let result_part2 = run_register(register_commands).crt_letters()?;
+        Ok(result_part2)
+    }
+    // CRT of streamed program has rows of letters, letters of more rows are not recognized anyway
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let mut signal_register = SignalRegister::new(20, 40, ocr::GLYPH_HEIGHT);
+        read_lines(reader, |line| {
+            signal_register.apply(&RegisterCommand::try_from(line)?);
+            Ok(())
+        })?;
+        Ok(vec![
+            Ok(signal_register.signal_strength().into()),
+            signal_register.crt_letters().map(Into::into),
+        ])
+    }
 }
 
 #[cfg(test)]
@@ -114,29 +154,22 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_example_part() -> Result<()> {
+    fn test_crt_image() -> Result<()> {
         let input = include_str!("../../assets/day_10_example.txt");
-        let register_commands: Vec<RegisterCommand> =
-            input.lines().map(RegisterCommand::from).collect();
-        //eprintln!("{:?}", &register_commands[..10]);
-        let mut signal_register = SignalRegister::<X, Y>::new(20, 40);
-        for rc in register_commands.iter() {
-            signal_register.apply(rc);
-        }
-
-        let result_part1 = signal_register.sum_check_cycle_x;
-        println!("result example day 10 part 1: {}", result_part1);
-        assert_eq!(result_part1, 13_140);
-
-        let result_part2 = format!("{}", signal_register.crt);
-        let test_part2 = "##..##..##..##..##..##..##..##..##..##..\n\
-                                ###...###...###...###...###...###...###.\n\
-                                ####....####....####....####....####....\n\
-                                #####.....#####.....#####.....#####.....\n\
-                                ######......######......######......####\n\
-                                #######.......#######.......#######.....";
-        println!("result example day 10 part 2:\n{}", result_part2.trim());
-        assert_eq!(result_part2.trim(), test_part2);
+        let signal_register = run_register(&Day10::parse(input)?);
+        assert_eq!(signal_register.signal_strength(), 13_140);
+        // example draws a test pattern instead of letters
+        assert_eq!(
+            signal_register.crt_image(),
+            "##..##..##..##..##..##..##..##..##..##..\n\
+             ###...###...###...###...###...###...###.\n\
+             ####....####....####....####....####....\n\
+             #####.....#####.....#####.....#####.....\n\
+             ######......######......######......####\n\
+             #######.......#######.......#######....."
+        );
+        let err = signal_register.crt_letters().unwrap_err();
+        assert!(err.to_string().starts_with("unknown glyph at column 0"));
         Ok(())
     }
 }
@@ -1,18 +1,28 @@
 //!day_11.rs
+//!
+//! Monkey in the middle, see [`play_n_rounds`] for monkey business of [`Monkey`]s.
 
-use anyhow::Result;
+use crate::budget;
+use crate::parse::{keyword, next_token, number, strip_prefix, ParseError};
+use crate::progress;
+use crate::selection::Part;
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
 use evalexpr::eval_int;
 use std::collections::VecDeque;
 
-#[derive(Clone, Copy)]
-enum InspectionMethod {
-    Devision(i64),
-    #[cfg(feature = "long-run-time")]
+/// Relief after each inspection, which keeps worry levels of items small.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectionMethod {
+    /// Worry level is divided, e.g. by 3.
+    Division(i64),
+    /// Worry level is reduced modulo, e.g. product of all test divisors.
     Modulo(i64),
 }
 
-#[derive(Debug)]
-struct Monkey {
+/// Items and throwing rules of a monkey, parsed from its note of six lines.
+#[derive(Debug, Clone)]
+pub struct Monkey {
     items: VecDeque<i64>,
     operation: String,
     test_divisor: i64,
@@ -21,98 +31,122 @@ struct Monkey {
     inspected_items_count: i64,
 }
 
-impl From<&str> for Monkey {
-    fn from(value: &str) -> Self {
-        let mut line_iter = value.lines().skip(1);
-        let items: VecDeque<i64> = line_iter
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
-            .1
+// next line of monkey description without prefix
+fn monkey_field<'a>(
+    text: &str,
+    lines: &mut impl Iterator<Item = &'a str>,
+    prefix: &str,
+) -> Result<&'a str, ParseError> {
+    let line = next_token(text, lines, &format!("'{}'", prefix))?;
+    strip_prefix(text, line.trim_start(), prefix)
+}
+
+// operation "new = A + B" or "new = A * B" of operands old or numbers
+fn check_operation(text: &str, operation: &str) -> Result<(), ParseError> {
+    let mut tokens = operation.split_whitespace();
+    keyword(text, &mut tokens, "new")?;
+    keyword(text, &mut tokens, "=")?;
+    for expected in ["old or number", "operator + or *", "old or number"] {
+        let token = next_token(text, &mut tokens, expected)?;
+        let valid = match expected {
+            "operator + or *" => token == "+" || token == "*",
+            _ => token == "old" || token.parse::<i64>().is_ok(),
+        };
+        if !valid {
+            return Err(ParseError::new(text, token, expected));
+        }
+    }
+    match tokens.next() {
+        Some(token) => Err(ParseError::new(text, token, "end of operation")),
+        None => Ok(()),
+    }
+}
+
+impl TryFrom<&str> for Monkey {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut line_iter = value.lines();
+        monkey_field(value, &mut line_iter, "Monkey ")?;
+        let items = monkey_field(value, &mut line_iter, "Starting items: ")?
             .split(", ")
-            .map(|i| i.parse::<i64>().unwrap())
-            .collect();
-        let operation = line_iter
-            .next()
-            .unwrap()
-            .split_once(": ")
-            .unwrap()
-            .1
-            .to_string();
-        let test_divisor = line_iter
-            .next()
-            .unwrap()
-            .split_once("by ")
-            .unwrap()
-            .1
-            .parse::<i64>()
-            .unwrap();
-        let test_true = line_iter
-            .next()
-            .unwrap()
-            .split_once("monkey ")
-            .unwrap()
-            .1
-            .parse::<usize>()
-            .unwrap();
-        let test_false = line_iter
-            .next()
-            .unwrap()
-            .split_once("monkey ")
-            .unwrap()
-            .1
-            .parse::<usize>()
-            .unwrap();
-        Self {
+            .map(|i| number::<i64>(value, i))
+            .collect::<Result<VecDeque<i64>, ParseError>>()?;
+        let operation = monkey_field(value, &mut line_iter, "Operation: ")?;
+        check_operation(value, operation)?;
+        let divisor_token = monkey_field(value, &mut line_iter, "Test: divisible by ")?;
+        let test_divisor = number::<i64>(value, divisor_token)?;
+        if test_divisor <= 0 {
+            return Err(ParseError::new(value, divisor_token, "divisor greater zero"));
+        }
+        let test_true = monkey_field(value, &mut line_iter, "If true: throw to monkey ")?;
+        let test_false = monkey_field(value, &mut line_iter, "If false: throw to monkey ")?;
+        Ok(Self {
             items,
-            operation,
+            operation: operation.to_string(),
             test_divisor,
-            test_true,
-            test_false,
+            test_true: number(value, test_true)?,
+            test_false: number(value, test_false)?,
             inspected_items_count: 0,
-        }
+        })
     }
 }
 
 impl Monkey {
+    /// Divisor of test, which decides target monkey of a thrown item.
+    pub fn test_divisor(&self) -> i64 {
+        self.test_divisor
+    }
     fn catch_item(&mut self, item: i64) {
         self.items.push_back(item);
     }
-    fn throw_item(&mut self, inspection_method: InspectionMethod) -> Option<(usize, i64)> {
+    // target monkey and worry level of next item, fails if operation overflows
+    fn throw_item(&mut self, inspection_method: InspectionMethod) -> Result<Option<(usize, i64)>> {
         match self.items.pop_front() {
             Some(item) => {
                 self.inspected_items_count += 1;
                 let expression = format!("old = {}; {}; new", item, self.operation);
-                let new = eval_int(expression.as_str()).expect("bad expression");
+                let new = eval_int(expression.as_str()).map_err(|err| {
+                    anyhow!(
+                        "operation '{}' failed for old = {}: {}",
+                        self.operation,
+                        item,
+                        err
+                    )
+                })?;
                 let new = match inspection_method {
-                    InspectionMethod::Devision(divisor) => new / divisor,
-                    #[cfg(feature = "long-run-time")]
+                    InspectionMethod::Division(divisor) => new / divisor,
                     InspectionMethod::Modulo(divisor) => new % divisor,
                 };
                 if new % self.test_divisor == 0 {
-                    Some((self.test_true, new))
+                    Ok(Some((self.test_true, new)))
                 } else {
-                    Some((self.test_false, new))
+                    Ok(Some((self.test_false, new)))
                 }
             }
-            None => None,
+            None => Ok(None),
         }
     }
 }
 
-fn play_n_rounds(
-    monkeys: &mut Vec<Monkey>,
+/// Monkey business after n_rounds, which is the product of inspected items of the two most
+/// active monkeys. Fails if the time budget of the running thread is exceeded.
+pub fn play_n_rounds(
+    monkeys: &mut [Monkey],
     inspection_method: InspectionMethod,
     n_rounds: usize,
-) -> i64 {
+) -> Result<i64> {
     let mut max_inspections = 0;
     let mut second_max_inspections = 0;
+    let _search = progress::start("day 11 rounds");
+    progress::total(n_rounds as u64);
     for round in 0..n_rounds {
+        budget::check()?;
+        progress::state();
         let mut monkey_index = 0;
         while monkey_index < monkeys.len() {
             while let Some((catch_index, item)) =
-                monkeys[monkey_index].throw_item(inspection_method)
+                monkeys[monkey_index].throw_item(inspection_method)?
             {
                 monkeys[catch_index].catch_item(item);
             }
@@ -127,54 +161,44 @@ fn play_n_rounds(
             monkey_index += 1;
         }
     }
-    max_inspections * second_max_inspections
+    Ok(max_inspections * second_max_inspections)
 }
 
-pub fn day_11() -> Result<()> {
-    let input = include_str!("../../assets/day_11.txt");
-    let mut monkeys: Vec<Monkey> = input.split("\n\n").map(Monkey::from).collect();
-    let inspection_method = InspectionMethod::Devision(3);
-    let result_part1 = play_n_rounds(&mut monkeys, inspection_method, 20);
-    println!("result day 11 part 1: {}", result_part1);
-    assert_eq!(result_part1, 67_830);
+pub struct Day11;
 
-    #[cfg(feature = "long-run-time")]
-    {
-        let mut monkeys: Vec<Monkey> = input.split("\n\n").map(Monkey::from).collect();
-        let super_divisor: i64 = monkeys.iter().map(|m| m.test_divisor).product();
-        let inspection_method = InspectionMethod::Modulo(super_divisor);
-        let result_part2 = play_n_rounds(&mut monkeys, inspection_method, 10_000);
-        println!("result day 11 part 2: {}", result_part2);
-        assert_eq!(result_part2, 15_305_381_442);
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const SLOW_PARTS: &'static [Part] = &[Part::Two];
+    type Input = Vec<Monkey>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut monkeys: Vec<Monkey> = Vec::new();
+        for monkey in input.split("\n\n") {
+            monkeys.push(Monkey::try_from(monkey).map_err(|err| err.within(input, monkey))?);
+        }
+        // monkeys only throw to existing monkeys
+        for (monkey, block) in monkeys.iter().zip(input.split("\n\n")) {
+            if monkey.test_true.max(monkey.test_false) >= monkeys.len() {
+                let target_line = block.lines().last().unwrap_or(block);
+                return Err(ParseError::new(input, target_line, "existing target monkey").into());
+            }
+        }
+        Ok(monkeys)
     }
-    #[cfg(not(feature = "long-run-time"))]
-    {
-        println!("day 11 part 2 skipped because of long run time")
+    fn part_one(monkeys: &Self::Input) -> Result<i64> {
+        let mut monkeys = monkeys.clone();
+        let inspection_method = InspectionMethod::Division(3);
+        let result_part1 = play_n_rounds(&mut monkeys, inspection_method, 20)?;
+        Ok(result_part1)
     }
-
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_example_part() -> Result<()> {
-        let input = include_str!("../../assets/day_11_example.txt");
-        let mut monkeys: Vec<Monkey> = input.split("\n\n").map(Monkey::from).collect();
-        let inspection_method = InspectionMethod::Devision(3);
-        let result_part1 = play_n_rounds(&mut monkeys, inspection_method, 20);
-        println!("result example day 11 part 1: {}", result_part1);
-        assert_eq!(result_part1, 10_605);
-
-        let mut monkeys: Vec<Monkey> = input.split("\n\n").map(Monkey::from).collect();
-        let super_divisor: i64 = monkeys.iter().map(|m| m.test_divisor).product();
+    fn part_two(monkeys: &Self::Input) -> Result<i64> {
+        let mut monkeys = monkeys.clone();
+        let super_divisor: i64 = monkeys.iter().map(|m| m.test_divisor()).product();
         let inspection_method = InspectionMethod::Modulo(super_divisor);
-        let result_part2 = play_n_rounds(&mut monkeys, inspection_method, 10_000);
-        println!("result example day 11 part 2: {}", result_part2);
-        assert_eq!(result_part2, 2_713_310_158);
-        Ok(())
+        let result_part2 = play_n_rounds(&mut monkeys, inspection_method, 10_000)?;
+        Ok(result_part2)
     }
 }
+
@@ -1,108 +1,124 @@
 //!day_12.rs
+//!
+//! Hill climbing algorithm, see [`Heightmap`] for shortest paths to best signal.
 
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 use anyhow::Result;
-use my_lib::{my_compass::Compass, my_map_point::MapPoint, my_map_two_dim::MyMap2D};
+use std::collections::VecDeque;
 
-// taken from ../../assets/day_12.txt
-const X: usize = 113;
-const Y: usize = 41;
-
-struct Heightmap<const X: usize, const Y: usize> {
-    map: MyMap2D<char, X, Y>,
+/// Grid of elevations a to z with start S and end E, sized by input and parsed with
+/// `Heightmap::try_from()`.
+#[derive(Debug)]
+pub struct Heightmap {
+    map: Grid<char>,
 }
 
-impl<const X: usize, const Y: usize> From<&str> for Heightmap<X, Y> {
-    fn from(value: &str) -> Self {
-        Heightmap {
-            map: MyMap2D::from(value),
+impl TryFrom<&str> for Heightmap {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (mut start, mut end) = (0, 0);
+        let map = Grid::parse(value, |c, token| {
+            match c {
+                'a'..='z' => (),
+                'S' => start += 1,
+                'E' => end += 1,
+                _ => return Err(ParseError::new(value, token, "elevation a-z, S or E")),
+            }
+            Ok(c)
+        })?;
+        if start != 1 || end != 1 {
+            return Err(ParseError::end_of(value, "exactly one S and one E"));
         }
+        Ok(Heightmap { map })
+    }
+}
+
+// S has elevation a, E has elevation z
+fn elevation(c: char) -> u32 {
+    match c {
+        'S' => 'a' as u32,
+        'E' => 'z' as u32,
+        _ => c as u32,
     }
 }
 
-impl<const X: usize, const Y: usize> Heightmap<X, Y> {
-    fn travel_shortest_path_from_startpoint(&self) -> usize {
+impl Heightmap {
+    /// Fewest steps from S to E, each step climbs at most one elevation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if E is not reachable from S.
+    pub fn travel_shortest_path_from_startpoint(&self) -> usize {
         let (start_point, _) = self.map.iter().find(|(_, c)| **c == 'S').unwrap();
-        self.calc_destance_map_from_endpoint()
-            .get(start_point)
-            .unwrap()
+        self.calc_destance_map_from_endpoint()[start_point].unwrap()
     }
-    fn travel_shortest_path_from_lowest_elevation(&self) -> usize {
+    /// Fewest steps from any square of elevation a to E.
+    ///
+    /// # Panics
+    ///
+    /// Panics if E is not reachable from any square of elevation a.
+    pub fn travel_shortest_path_from_lowest_elevation(&self) -> usize {
         let distance_map = self.calc_destance_map_from_endpoint();
         self.map
             .iter()
-            .filter(|(_, v)| **v == 'a' || **v == 'S')
-            .filter_map(|(p, _)| *distance_map.get(p))
+            .filter(|(_, v)| elevation(**v) == 'a' as u32)
+            .filter_map(|(p, _)| distance_map[p])
             .min()
             .unwrap()
     }
-    fn calc_destance_map_from_endpoint(&self) -> MyMap2D<Option<usize>, X, Y> {
-        let mut distance_map: MyMap2D<Option<usize>, X, Y> = MyMap2D::default();
+    // breadth first search from E, which steps down at most one elevation
+    fn calc_destance_map_from_endpoint(&self) -> Grid<Option<usize>> {
+        let mut distance_map = Grid::new(self.map.width(), self.map.height(), None);
         let (end_point, _) = self.map.iter().find(|(_, c)| **c == 'E').unwrap();
-        let filter_fn = Box::new(
-            |_: MapPoint<X, Y>,
-             value_of_next_cell: &char,
-             _: Compass,
-             _: MapPoint<X, Y>,
-             value_of_current_cell: &char,
-             _: usize| {
-                match value_of_current_cell {
-                    'E' => *value_of_next_cell as u32 >= 'z' as u32 - 1,
-                    _ => match value_of_next_cell {
-                        'S' => 'a' as u32 >= *value_of_current_cell as u32 - 1,
-                        _ => *value_of_next_cell as u32 >= *value_of_current_cell as u32 - 1,
-                    },
+        distance_map[end_point] = Some(0);
+        let mut queue = VecDeque::from([(end_point, 0)]);
+        while let Some((point, distance)) = queue.pop_front() {
+            let current_elevation = elevation(self.map[point]);
+            for next in self.map.neighbors(point) {
+                if distance_map[next].is_none()
+                    && elevation(self.map[next]) + 1 >= current_elevation
+                {
+                    distance_map[next] = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
                 }
-            },
-        );
-        for (point, _, distance) in self.map.iter_distance(end_point, filter_fn) {
-            distance_map.set(point, Some(distance));
+            }
         }
         distance_map
     }
 }
 
-pub fn day_12() -> Result<()> {
-    let input = include_str!("../../assets/day_12.txt");
-    let height_map = Heightmap::<X, Y>::from(input);
-    let result_part1 = height_map.travel_shortest_path_from_startpoint();
-    println!("result day 12 part 1: {}", result_part1);
-    assert_eq!(result_part1, 380);
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    type Input = Heightmap;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    let result_part2 = height_map.travel_shortest_path_from_lowest_elevation();
-    println!("result day 12 part 2: {}", result_part2);
-    assert_eq!(result_part2, 375);
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Heightmap::try_from(input)?)
+    }
+    fn part_one(height_map: &Self::Input) -> Result<usize> {
+        let result_part1 = height_map.travel_shortest_path_from_startpoint();
+        Ok(result_part1)
+    }
+    fn part_two(height_map: &Self::Input) -> Result<usize> {
+        let result_part2 = height_map.travel_shortest_path_from_lowest_elevation();
+        Ok(result_part2)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    const XT: usize = 8;
-    const YT: usize = 5;
 
     #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_12.txt");
-        let x_input = input.lines().next().unwrap().chars().count();
-        let y_input = input.lines().count();
-        eprintln!("X: {}, Y: {}", x_input, y_input);
-
-        let input = "Sabqponm\n\
-                           abcryxxl\n\
-                           accszExk\n\
-                           acctuvwj\n\
-                           abdefghi";
-        let height_map = Heightmap::<XT, YT>::from(input);
-        eprint!("{}", height_map.map);
-
-        let result_part1 = height_map.travel_shortest_path_from_startpoint();
-        println!("result example day 12 part 1: {}", result_part1);
-        assert_eq!(result_part1, 31);
-
-        let result_part2 = height_map.travel_shortest_path_from_lowest_elevation();
-        println!("result example day 12 part 2: {}", result_part2);
-        assert_eq!(result_part2, 29);
-        Ok(())
+    fn test_parse_errors() {
+        let err = Heightmap::try_from("SabE\nabc").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
     }
 }
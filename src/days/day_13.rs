@@ -1,16 +1,43 @@
 //!day_13.rs
+//!
+//! Distress signal, see [`Packet`] for ordering of packets.
 
+use crate::parse::{number, parse_token, split_once, ParseError};
+use crate::solution::Solution;
 use anyhow::Result;
 use std::cmp::Ordering;
+use std::fmt::Display;
 
+/// Packet of distress signal, parsed from e.g. `[1,[2,3]]`. Packets are ordered by
+/// comparing integers and lists item by item, an integer compares like a list of itself.
 #[derive(Debug, PartialEq, Eq, Clone)]
-enum Packet {
+pub enum Packet {
     Integer(u8),
     List(Vec<Packet>),
 }
 
-impl From<&str> for Packet {
-    fn from(value: &str) -> Self {
+impl Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Integer(integer) => write!(f, "{}", integer),
+            Packet::List(list) => {
+                write!(f, "[")?;
+                for (i, packet) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Packet {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         if value.starts_with('[') {
             let mut list: Vec<Packet> = Vec::new();
             let mut brackets_count = 1;
@@ -19,30 +46,29 @@ impl From<&str> for Packet {
 
             while brackets_count > 0 {
                 index += 1;
-                match &value[index..index + 1] {
-                    "[" => brackets_count += 1,
-                    "]" => brackets_count -= 1,
-                    "," => {
-                        if brackets_count == 1 {
-                            seperator_indices.push(index);
-                        }
-                    }
-                    _ => (),
+                match value.as_bytes().get(index) {
+                    Some(b'[') => brackets_count += 1,
+                    Some(b']') => brackets_count -= 1,
+                    Some(b',') if brackets_count == 1 => seperator_indices.push(index),
+                    Some(_) => (),
+                    None => return Err(ParseError::end_of(value, "']'")),
                 }
             }
-            assert_eq!(index + 1, value.len());
+            if index + 1 != value.len() {
+                return Err(ParseError::new(value, &value[index + 1..], "end of packet"));
+            }
             if index > 1 {
                 let mut prev_sep_i = 1;
                 // append index to include last respectivly single item
                 seperator_indices.push(index);
                 for sep_i in seperator_indices.into_iter() {
-                    list.push(Packet::from(&value[prev_sep_i..sep_i]));
+                    list.push(parse_token(value, &value[prev_sep_i..sep_i])?);
                     prev_sep_i = sep_i + 1;
                 }
             }
-            return Packet::List(list);
+            return Ok(Packet::List(list));
         }
-        Packet::Integer(value.parse::<u8>().expect("bad input"))
+        Ok(Packet::Integer(number::<u8>(value, value)?))
     }
 }
 
@@ -76,7 +102,12 @@ impl Ord for Packet {
     }
 }
 
-fn compare_packet_pairs(packet_pairs: &[(Packet, Packet)]) -> usize {
+/// Sum of indices, starting with 1, of pairs in right order.
+///
+/// # Panics
+///
+/// Panics if packets of a pair are equal.
+pub fn compare_packet_pairs(packet_pairs: &[(Packet, Packet)]) -> usize {
     let mut sum_index = 0;
     for (i, (left, right)) in packet_pairs.iter().enumerate() {
         match left.cmp(right) {
@@ -88,7 +119,9 @@ fn compare_packet_pairs(packet_pairs: &[(Packet, Packet)]) -> usize {
     sum_index
 }
 
-fn sort_packets(packets: &mut Vec<Packet>) -> usize {
+/// Sorts packets together with divider packets `[[2]]` and `[[6]]` and returns product of
+/// indices, starting with 1, of both divider packets.
+pub fn sort_packets(packets: &mut Vec<Packet>) -> usize {
     let seperator_1 = Packet::List(vec![Packet::List(vec![Packet::Integer(2)])]);
     let seperator_2 = Packet::List(vec![Packet::List(vec![Packet::Integer(6)])]);
     packets.push(seperator_1.clone());
@@ -99,62 +132,41 @@ fn sort_packets(packets: &mut Vec<Packet>) -> usize {
     seperator_1_index * seperator_2_index
 }
 
-pub fn day_13() -> Result<()> {
-    let input = include_str!("../../assets/day_13.txt");
-    let packet_pairs: Vec<(Packet, Packet)> = input
-        .split("\n\n")
-        .map(|p| {
-            p.split_once('\n')
-                .map(|(l, r)| (Packet::from(l), Packet::from(r)))
-                .unwrap()
-        })
-        .collect();
-    let result_part1 = compare_packet_pairs(&packet_pairs);
-    println!("result day 13 part 1: {}", result_part1);
-    assert_eq!(result_part1, 4_734);
+pub struct Day13;
 
-    let mut packets: Vec<Packet> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(Packet::from)
-        .collect();
-    let result_part2 = sort_packets(&mut packets);
-    println!("result day 13 part 2: {}", result_part2);
-    assert_eq!(result_part2, 21_836);
-    Ok(())
-}
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    type Input = Vec<(Packet, Packet)>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_13.txt");
-        eprintln!("{:?}", Packet::from(input.lines().next().unwrap()));
-
-        let input = include_str!("../../assets/day_13_example.txt");
-        let packet_pairs: Vec<(Packet, Packet)> = input
-            .split("\n\n")
-            .map(|p| {
-                p.split_once('\n')
-                    .map(|(l, r)| (Packet::from(l), Packet::from(r)))
-                    .unwrap()
-            })
-            .collect();
-        let result_part1 = compare_packet_pairs(&packet_pairs);
-        println!("result example day 13 part 1: {}", result_part1);
-        assert_eq!(result_part1, 13);
-
-        let mut packets: Vec<Packet> = input
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(Packet::from)
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut packet_pairs: Vec<(Packet, Packet)> = Vec::new();
+        for pair in input.split("\n\n") {
+            let (left, right) = split_once(input, pair.trim(), "\n")?;
+            let left_packet: Packet = parse_token(input, left)?;
+            let right_packet: Packet = parse_token(input, right)?;
+            // equal packets, e.g. [[1]] and [1], have no order, see compare_packet_pairs()
+            if left_packet.cmp(&right_packet) == Ordering::Equal {
+                return Err(
+                    ParseError::new(input, right, "packet different from left packet").into(),
+                );
+            }
+            packet_pairs.push((left_packet, right_packet));
+        }
+        Ok(packet_pairs)
+    }
+    fn part_one(packet_pairs: &Self::Input) -> Result<usize> {
+        let result_part1 = compare_packet_pairs(packet_pairs);
+        Ok(result_part1)
+    }
+    fn part_two(packet_pairs: &Self::Input) -> Result<usize> {
+        let mut packets: Vec<Packet> = packet_pairs
+            .iter()
+            .flat_map(|(l, r)| [l.clone(), r.clone()])
             .collect();
         let result_part2 = sort_packets(&mut packets);
-        println!("result example day 13 part 2: {}", result_part2);
-        assert_eq!(result_part2, 140);
-        Ok(())
+        Ok(result_part2)
     }
 }
+
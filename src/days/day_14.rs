@@ -1,31 +1,41 @@
 //!day_14.rs
+//!
+//! Regolith reservoir, see [`RockAndSand`] for units of sand coming to rest.
 
+use crate::parse::{number, split_once, ParseError};
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::visualize::{FrameSink, Render};
 use anyhow::Result;
 use my_lib::my_geometry::my_point::Point;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
-struct RockAndSand {
+/// Cave of rock paths, parsed from lines of e.g. `498,4 -> 498,6 -> 496,6`, into which sand
+/// is poured from 500,0.
+#[derive(Debug, Clone)]
+pub struct RockAndSand {
     rocks: HashSet<Point>,
     sand: HashSet<Point>,
     start_of_sand: Point,
 }
 
-impl From<&str> for RockAndSand {
-    fn from(value: &str) -> Self {
+fn rock_corner(text: &str, token: &str) -> Result<Point, ParseError> {
+    let (x, y) = split_once(text, token, ",")?;
+    Ok(Point::from((number(text, x)?, number(text, y)?)))
+}
+
+impl TryFrom<&str> for RockAndSand {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut rocks: HashSet<Point> = HashSet::new();
         for line in value.lines() {
-            let mut rock_corners_iter = line.split(" -> ").filter_map(|rc| {
-                rc.split_once(',').map(|(x, y)| {
-                    Point::from((
-                        x.parse::<i64>().expect("bad input"),
-                        y.parse::<i64>().expect("bad input"),
-                    ))
-                })
-            });
-            let mut current_corner = rock_corners_iter.next().unwrap();
+            let mut rock_corners_iter = line.split(" -> ");
+            let mut current_corner = rock_corner(value, rock_corners_iter.next().unwrap_or(line))?;
             rocks.insert(current_corner);
-            for rock_corner in rock_corners_iter {
+            for rock_corner_token in rock_corners_iter {
+                let rock_corner = rock_corner(value, rock_corner_token)?;
                 let direction = match (
                     current_corner.x.cmp(&rock_corner.x),
                     current_corner.y.cmp(&rock_corner.y),
@@ -34,7 +44,13 @@ impl From<&str> for RockAndSand {
                     (Ordering::Equal, Ordering::Less) => Point::new(0, 1),
                     (Ordering::Greater, Ordering::Equal) => Point::new(-1, 0),
                     (Ordering::Less, Ordering::Equal) => Point::new(1, 0),
-                    _ => panic!("bad rock corners"),
+                    _ => {
+                        return Err(ParseError::new(
+                            value,
+                            rock_corner_token,
+                            "corner in line with previous corner",
+                        ))
+                    }
                 };
                 loop {
                     current_corner = current_corner.add(direction);
@@ -48,11 +64,14 @@ impl From<&str> for RockAndSand {
             }
         }
 
-        Self {
+        if rocks.is_empty() {
+            return Err(ParseError::end_of(value, "rock path"));
+        }
+        Ok(Self {
             rocks,
             sand: HashSet::new(),
             start_of_sand: Point::new(500, 0),
-        }
+        })
     }
 }
 
@@ -113,52 +132,89 @@ impl<'a> Iterator for FallingSand<'a> {
 }
 
 impl RockAndSand {
-    fn pouring_sand(&mut self, bottom: bool) -> usize {
-        loop {
-            let (new_sand, into_the_abyss) = FallingSand::new(self, bottom).last().unwrap();
-            if into_the_abyss {
-                break;
-            }
-            self.sand.insert(new_sand);
-            if new_sand == self.start_of_sand {
-                break;
-            }
+    // false, if unit falls into the abyss or blocks start of sand
+    fn pour_sand_unit(&mut self, bottom: bool) -> bool {
+        let (new_sand, into_the_abyss) = FallingSand::new(self, bottom).last().unwrap();
+        if into_the_abyss {
+            return false;
         }
+        self.sand.insert(new_sand);
+        new_sand != self.start_of_sand
+    }
+    /// Pours sand until it falls into the abyss or, with a floor two below the lowest rock,
+    /// until it blocks source of sand; returns number of resting units of sand.
+    pub fn pouring_sand(&mut self, bottom: bool) -> usize {
+        while self.pour_sand_unit(bottom) {}
         self.sand.len()
     }
 }
 
-pub fn day_14() -> Result<()> {
-    let input = include_str!("../../assets/day_14.txt");
-    let mut rock_and_sand = RockAndSand::from(input);
-    let result_part1 = rock_and_sand.pouring_sand(false);
-    println!("result day 14 part 1: {}", result_part1);
-    assert_eq!(result_part1, 1_068);
-
-    let result_part2 = rock_and_sand.pouring_sand(true);
-    println!("result day 14 part 2: {}", result_part2);
-    assert_eq!(result_part2, 27_936);
-    
-    Ok(())
+impl Render for RockAndSand {
+    fn render(&self) -> String {
+        let points = || {
+            self.rocks
+                .iter()
+                .chain(self.sand.iter())
+                .chain([&self.start_of_sand])
+        };
+        let min_x = points().map(|p| p.x).min().unwrap();
+        let max_x = points().map(|p| p.x).max().unwrap();
+        let max_y = points().map(|p| p.y).max().unwrap();
+        let mut rows: Vec<String> = Vec::new();
+        for y in self.start_of_sand.y..=max_y {
+            let row: String = (min_x..=max_x)
+                .map(|x| {
+                    let point = Point::new(x, y);
+                    if point == self.start_of_sand && !self.sand.contains(&point) {
+                        '+'
+                    } else if self.rocks.contains(&point) {
+                        '#'
+                    } else if self.sand.contains(&point) {
+                        'o'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            rows.push(row);
+        }
+        rows.join("\n")
+    }
 }
 
-#[cfg(test)]
-mod tests {
-
-    use super::*;
+pub struct Day14;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "498,4 -> 498,6 -> 496,6\n\
-                           503,4 -> 502,4 -> 502,9 -> 494,9";
-        let mut rock_and_sand = RockAndSand::from(input);
-        let result_part1 = rock_and_sand.pouring_sand(false);
-        println!("result example day 14 part 1: {}", result_part1);
-        assert_eq!(result_part1, 24);
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    type Input = RockAndSand;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-        let result_part2 = rock_and_sand.pouring_sand(true);
-        println!("result example day 14 part 2: {}", result_part2);
-        assert_eq!(result_part2, 93);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(RockAndSand::try_from(input)?)
+    }
+    fn part_one(rock_and_sand: &Self::Input) -> Result<usize> {
+        let result_part1 = rock_and_sand.clone().pouring_sand(false);
+        Ok(result_part1)
+    }
+    fn part_two(rock_and_sand: &Self::Input) -> Result<usize> {
+        let result_part2 = rock_and_sand.clone().pouring_sand(true);
+        Ok(result_part2)
+    }
+    fn visualize(rock_and_sand: &Self::Input, part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        // one frame per unit of sand coming to rest
+        let mut rock_and_sand = rock_and_sand.clone();
+        let bottom = part == Part::Two;
+        let mut pouring = sink.frame(&rock_and_sand)?;
+        while pouring {
+            let sand_units = rock_and_sand.sand.len();
+            pouring = rock_and_sand.pour_sand_unit(bottom);
+            // last unit of part 1 falls into the abyss, last unit of part 2 blocks start of sand
+            if rock_and_sand.sand.len() > sand_units {
+                pouring &= sink.frame(&rock_and_sand)?;
+            }
+        }
         Ok(())
     }
 }
+
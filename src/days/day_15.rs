@@ -1,47 +1,63 @@
 //!day_15.rs
+//!
+//! Beacon exclusion zone, see [`SensorReport`] for positions covered by sensors.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::parse::{number, parse_lines, split_once, strip_prefix, ParseError};
+use crate::selection::Part;
+use crate::solution::{Params, Solution};
+use anyhow::{anyhow, Result};
 use my_lib::my_geometry::{my_diamond::Diamond, my_line::Line, my_point::Point};
 
+/// Sensor and its closest beacon, parsed from e.g.
+/// `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`.
 #[derive(Debug, Clone, Copy)]
-struct SensorBeacon {
+pub struct SensorBeacon {
     sensor: Diamond,
     beacon: Point,
 }
 
-impl From<&str> for SensorBeacon {
-    fn from(value: &str) -> Self {
-        let (sensor, beacon) = value
-            .strip_prefix("Sensor at x=")
-            .unwrap()
-            .split_once(": closest beacon is at x=")
-            .unwrap();
-        let sensor = sensor
-            .split_once(", y=")
-            .map(|(x, y)| {
-                Point::from((
-                    x.parse::<i64>().expect("bad input"),
-                    y.parse::<i64>().expect("bad input"),
-                ))
-            })
-            .unwrap();
-        let beacon = beacon
-            .split_once(", y=")
-            .map(|(x, y)| {
-                Point::from((
-                    x.parse::<i64>().expect("bad input"),
-                    y.parse::<i64>().expect("bad input"),
-                ))
-            })
-            .unwrap();
-        Self {
+fn position(text: &str, token: &str) -> Result<Point, ParseError> {
+    let (x, y) = split_once(text, token, ", y=")?;
+    Ok(Point::from((number(text, x)?, number(text, y)?)))
+}
+
+impl TryFrom<&str> for SensorBeacon {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (sensor, beacon) = split_once(
+            value,
+            strip_prefix(value, value, "Sensor at x=")?,
+            ": closest beacon is at x=",
+        )?;
+        let sensor = position(value, sensor)?;
+        let beacon = position(value, beacon)?;
+        Ok(Self {
             sensor: Diamond::new(sensor, sensor.delta(beacon)),
             beacon,
-        }
+        })
+    }
+}
+
+/// Sensors and beacons of report, parsed with `Day15::parse()`, which uses row 2000000 and
+/// search range 0 to 4000000 of puzzle input.
+pub struct SensorReport {
+    sensor_beacons: Vec<SensorBeacon>,
+    // scanned row of part 1 and search range of part 2, both are smaller in example
+    row: i64,
+    max_range: i64,
+}
+
+impl SensorReport {
+    /// Sensors with their closest beacons in order of report.
+    pub fn sensor_beacons(&self) -> &[SensorBeacon] {
+        &self.sensor_beacons
     }
 }
 
-fn calc_scanned_positions_of_row(sensor_beacons: &[SensorBeacon], row: i64) -> i64 {
+/// Number of positions in row, where no beacon can be present.
+pub fn calc_scanned_positions_of_row(sensor_beacons: &[SensorBeacon], row: i64) -> i64 {
     let mut min_x = i64::MAX;
     let mut max_x = i64::MIN;
     let mut sensor_beacons_in_row: Vec<Point> = Vec::with_capacity(sensor_beacons.len());
@@ -76,7 +92,13 @@ fn calc_scanned_positions_of_row(sensor_beacons: &[SensorBeacon], row: i64) -> i
     max_x - min_x + 1 - count_sensor_beacons_in_range
 }
 
-fn find_distress_beacon(sensor_beacons: &[SensorBeacon], max_range: i64, x_factor: i64) -> i64 {
+/// Tuning frequency x * x_factor + y of the only position in search range 0 to max_range,
+/// which is not covered by any sensor.
+///
+/// # Panics
+///
+/// Panics if there is not exactly one such position.
+pub fn find_distress_beacon(sensor_beacons: &[SensorBeacon], max_range: i64, x_factor: i64) -> i64 {
     let mut distress_beacons: Vec<Point> = Vec::new();
     for (i, sb_1) in sensor_beacons.iter().enumerate() {
         for sb_2 in sensor_beacons.iter().skip(i + 1) {
@@ -100,42 +122,117 @@ fn find_distress_beacon(sensor_beacons: &[SensorBeacon], max_range: i64, x_facto
     distress_beacons[0].x * x_factor + distress_beacons[0].y
 }
 
-pub fn day_15() -> Result<()> {
-    let input = include_str!("../../assets/day_15.txt");
-    let sensor_beacons: Vec<SensorBeacon> = input.lines().map(SensorBeacon::from).collect();
-    let row = 2_000_000;
-    let result_part1 = calc_scanned_positions_of_row(&sensor_beacons, row);
-    println!("result day 15 part 1: {}", result_part1);
-    assert_eq!(result_part1, 5_112_034);
-
-    let max_range = 4_000_000;
-    let x_factor = 4_000_000;
-    let result_part2 = find_distress_beacon(&sensor_beacons, max_range, x_factor);
-    println!("result day 15 part 2: {}", result_part2);
-    assert_eq!(result_part2, 13_172_087_230_812);
-
-    Ok(())
+// sensors of reference as x, y and manhattan distance to their beacon
+fn reference_sensors(sensor_beacons: &[SensorBeacon]) -> Vec<(i64, i64, i64)> {
+    sensor_beacons
+        .iter()
+        .map(|sb| {
+            let sensor = sb.sensor.get_center();
+            let distance = (sensor.x - sb.beacon.x).abs() + (sensor.y - sb.beacon.y).abs();
+            (sensor.x, sensor.y, distance)
+        })
+        .collect()
 }
 
-#[cfg(test)]
-mod tests {
+// reference of part 1, which checks each position of row against all sensors
+fn reference_scanned_positions_of_row(sensor_beacons: &[SensorBeacon], row: i64) -> i64 {
+    let sensors = reference_sensors(sensor_beacons);
+    let min_x = sensors.iter().map(|(x, _, d)| x - d).min().unwrap_or(0);
+    let max_x = sensors.iter().map(|(x, _, d)| x + d).max().unwrap_or(-1);
+    (min_x..=max_x)
+        .filter(|x| {
+            let covered = sensors
+                .iter()
+                .any(|(sx, sy, d)| (x - sx).abs() + (row - sy).abs() <= *d);
+            let occupied = sensors.iter().any(|(sx, sy, _)| (*sx, *sy) == (*x, row))
+                || sensor_beacons
+                    .iter()
+                    .any(|sb| (sb.beacon.x, sb.beacon.y) == (*x, row));
+            covered && !occupied
+        })
+        .count() as i64
+}
+
+// reference of part 2, which scans each row for positions not covered by any sensor and
+// jumps to the right end of covering sensors; fails unless there is exactly one position
+fn reference_distress_beacon(
+    sensor_beacons: &[SensorBeacon],
+    max_range: i64,
+    x_factor: i64,
+) -> Result<i64> {
+    let sensors = reference_sensors(sensor_beacons);
+    let mut distress_beacon: Option<(i64, i64)> = None;
+    for y in 0..=max_range {
+        let mut x = 0;
+        while x <= max_range {
+            match sensors
+                .iter()
+                .find(|(sx, sy, d)| (x - sx).abs() + (y - sy).abs() <= *d)
+            {
+                Some((sx, sy, d)) => x = sx + d - (y - sy).abs() + 1,
+                None => {
+                    if let Some((other_x, other_y)) = distress_beacon {
+                        return Err(anyhow!(
+                            "found distress beacons at x={}, y={} and x={}, y={}",
+                            other_x,
+                            other_y,
+                            x,
+                            y
+                        ));
+                    }
+                    distress_beacon = Some((x, y));
+                    x += 1;
+                }
+            }
+        }
+    }
+    let (x, y) = distress_beacon.ok_or(anyhow!("found no distress beacon"))?;
+    Ok(x * x_factor + y)
+}
 
-    use super::*;
+pub struct Day15;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_15_example.txt");
-        let sensor_beacons: Vec<SensorBeacon> = input.lines().map(SensorBeacon::from).collect();
-        let row = 10;
-        let result_part1 = calc_scanned_positions_of_row(&sensor_beacons, row);
-        println!("result example day 15 part 1: {}", result_part1);
-        assert_eq!(result_part1, 26);
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    // references scan positions, which needs small row and max_range parameters
+    const REFERENCE_PARTS: &'static [Part] = &[Part::One, Part::Two];
+    type Input = SensorReport;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-        let max_range = 20;
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(SensorReport {
+            sensor_beacons: parse_lines(input, SensorBeacon::try_from)?,
+            row: 2_000_000,
+            max_range: 4_000_000,
+        })
+    }
+    fn part_one(report: &Self::Input) -> Result<i64> {
+        let result_part1 = calc_scanned_positions_of_row(&report.sensor_beacons, report.row);
+        Ok(result_part1)
+    }
+    fn part_two(report: &Self::Input) -> Result<i64> {
         let x_factor = 4_000_000;
-        let result_part2 = find_distress_beacon(&sensor_beacons, max_range, x_factor);
-        println!("result example day 15 part 2: {}", result_part2);
-        assert_eq!(result_part2, 56_000_011);
+        let result_part2 =
+            find_distress_beacon(&report.sensor_beacons, report.max_range, x_factor);
+        Ok(result_part2)
+    }
+    fn reference(report: &Self::Input, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => {
+                Ok(reference_scanned_positions_of_row(&report.sensor_beacons, report.row).into())
+            }
+            Part::Two => {
+                reference_distress_beacon(&report.sensor_beacons, report.max_range, 4_000_000)
+                    .map(Into::into)
+            }
+        }
+    }
+    fn apply_params(report: &mut Self::Input, params: &Params) -> Result<()> {
+        params.check_known(Self::DAY, &["row", "max_range"])?;
+        report.row = params.get("row").unwrap_or(report.row);
+        report.max_range = params.get("max_range").unwrap_or(report.max_range);
         Ok(())
     }
 }
+
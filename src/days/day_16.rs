@@ -1,53 +1,95 @@
 //!day_16.rs
+//!
+//! Proboscidea volcanium, see [`ValveNetwork`] for most pressure released by opening valves.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::budget;
+use crate::parse::{keyword, next_number, next_token, strip_prefix, ParseError};
+use crate::progress;
+use crate::selection::Part;
+use crate::solution::{Params, Solution};
+use anyhow::{Context, Result};
 use petgraph::algo::floyd_warshall;
 use petgraph::graph::{NodeIndex, UnGraph};
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
 
-struct ValveNetwork {
+/// Valves with flow rates and tunnels between them, parsed from lines of e.g.
+/// `Valve BB has flow rate=13; tunnels lead to valves CC, AA`; start is valve AA.
+pub struct ValveNetwork {
     valves: UnGraph<u32, u32>,
     initial_node_id: NodeIndex<u32>,
     pair_distance: HashMap<(NodeIndex<u32>, NodeIndex<u32>), u32>,
+    // part 1 ignores valves with lower flow rate, which is too strict for example
+    minimum_valve_value: u32,
 }
 
-impl From<&str> for ValveNetwork {
-    fn from(value: &str) -> Self {
+// label, flow rate and labels of tunnel valves of one line
+fn valve_line(line: &str) -> Result<(&str, u32, Vec<&str>), ParseError> {
+    let mut key_word_iter = line.split_whitespace();
+    keyword(line, &mut key_word_iter, "Valve")?;
+    let valve_label = next_token(line, &mut key_word_iter, "valve label")?;
+    keyword(line, &mut key_word_iter, "has")?;
+    keyword(line, &mut key_word_iter, "flow")?;
+    let rate = next_token(line, &mut key_word_iter, "'rate='")?;
+    let rate = strip_prefix(line, rate, "rate=")?;
+    let valve_value = next_number(line, &mut rate.split(';'))?;
+    // "tunnel leads to valve" or "tunnels lead to valves"
+    let edge_valves: Vec<&str> = key_word_iter
+        .skip(4)
+        .map(|e| e.trim_end_matches(','))
+        .collect();
+    if edge_valves.is_empty() {
+        return Err(ParseError::end_of(line, "tunnel valves"));
+    }
+    Ok((valve_label, valve_value, edge_valves))
+}
+
+impl TryFrom<&str> for ValveNetwork {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let base_capacity = value.lines().count();
+        // opened valves are tracked in a u64 bit mask
+        if base_capacity > u64::BITS as usize {
+            return Err(ParseError::end_of(value, "at most 64 valves"));
+        }
         let mut valves: UnGraph<u32, u32> = UnGraph::with_capacity(base_capacity, base_capacity);
         let mut labels: HashMap<&str, NodeIndex<u32>> = HashMap::new();
+        let mut valve_lines: Vec<(&str, Vec<&str>)> = Vec::with_capacity(base_capacity);
         // add nodes
         for line in value.lines() {
-            let mut key_word_iter = line.split_whitespace();
-            let valve_label = key_word_iter.nth(1).unwrap();
-            let valve_value = key_word_iter
-                .nth(2)
-                .map(|v| {
-                    v.strip_prefix("rate=")
-                        .unwrap()
-                        .strip_suffix(';')
-                        .unwrap()
-                        .parse::<u32>()
-                        .expect("bad input")
-                })
-                .unwrap();
+            let (valve_label, valve_value, edge_valves) =
+                valve_line(line).map_err(|err| err.within(value, line))?;
             let node_id = valves.add_node(valve_value);
             labels.insert(valve_label, node_id);
+            valve_lines.push((valve_label, edge_valves));
         }
-        let initial_node_id = *labels.get("AA").unwrap();
+        let initial_node_id = *labels
+            .get("AA")
+            .ok_or_else(|| ParseError::end_of(value, "valve AA"))?;
         // add edges
-        let mut count_edge_update = 0;
-        for line in value.lines() {
-            let mut key_word_iter = line.split_whitespace();
-            let valve_label = key_word_iter.nth(1).unwrap();
-            let valve_id: &NodeIndex<u32> = labels.get(valve_label).unwrap();
-            for edge_valve in key_word_iter.skip(7).map(|e| &e[0..2]) {
-                let edge_valve_id: &NodeIndex<u32> = labels.get(edge_valve).unwrap();
+        for (valve_label, edge_valves) in valve_lines.iter() {
+            let valve_id: &NodeIndex<u32> = &labels[valve_label];
+            for edge_valve in edge_valves.iter() {
+                let edge_valve_id: &NodeIndex<u32> = labels
+                    .get(edge_valve)
+                    .ok_or_else(|| ParseError::new(value, edge_valve, "label of valve"))?;
                 valves.update_edge(*valve_id, *edge_valve_id, 1);
-                count_edge_update += 1;
             }
         }
-        assert_eq!(valves.edge_count(), count_edge_update / 2);
+        // tunnels lead both ways, so both valves of a tunnel list it
+        let tunnels: HashMap<&str, &Vec<&str>> = valve_lines.iter().map(|(l, e)| (*l, e)).collect();
+        for (valve_label, edge_valves) in valve_lines.iter() {
+            for edge_valve in edge_valves.iter() {
+                if !tunnels[edge_valve].contains(valve_label) {
+                    return Err(ParseError::new(
+                        value,
+                        edge_valve,
+                        format!("valve with tunnel back to {}", valve_label),
+                    ));
+                }
+            }
+        }
         let pair_distance: HashMap<(NodeIndex<u32>, NodeIndex<u32>), u32> =
             floyd_warshall(&valves, |e| *e.weight())
                 .expect("bad floyd warshall")
@@ -59,15 +101,33 @@ impl From<&str> for ValveNetwork {
                 })
                 .map(|((n1, n2), d)| ((*n1, *n2), *d))
                 .collect();
-        ValveNetwork {
+        Ok(ValveNetwork {
             valves,
             initial_node_id,
             pair_distance,
-        }
+            minimum_valve_value: 3,
+        })
     }
 }
 
 impl ValveNetwork {
+    /// Most pressure released alone within minutes, only valves with at least
+    /// minimum_valve_value flow rate are opened. Fails if the time budget of the running thread
+    /// is exceeded.
+    pub fn most_pressure_released(&self, minutes: u32, minimum_valve_value: u32) -> Result<u32> {
+        Ok(self
+            .best_pressure_release(minutes, minimum_valve_value)?
+            .values()
+            .max()
+            .copied()
+            .unwrap_or(0))
+    }
+    /// Most pressure released together with an elephant within 26 minutes, only valves with at
+    /// least minimum_valve_value flow rate are opened. Fails if the time budget of the running
+    /// thread is exceeded.
+    pub fn most_pressure_released_in_pair(&self, minimum_valve_value: u32) -> Result<u32> {
+        self.best_pressure_release_pair_working(minimum_valve_value)
+    }
     fn iter_pair_distance(
         &self,
         node: NodeIndex<u32>,
@@ -83,7 +143,12 @@ impl ValveNetwork {
             })
             .map(|((_, n2), d)| (*n2, *d))
     }
-    fn best_pressure_release(&self, minutes: u32, minimum_valve_value: u32) -> HashMap<u64, u32> {
+    fn best_pressure_release(
+        &self,
+        minutes: u32,
+        minimum_valve_value: u32,
+    ) -> Result<HashMap<u64, u32>> {
+        let _search = progress::start("day 16 valves");
         let mut pressure_hash: HashMap<u64, u32> = HashMap::new();
         for (next_node, distance) in
             self.iter_pair_distance(self.initial_node_id, minutes, minimum_valve_value)
@@ -95,9 +160,9 @@ impl ValveNetwork {
                 0,
                 &mut pressure_hash,
                 minimum_valve_value,
-            );
+            )?;
         }
-        pressure_hash
+        Ok(pressure_hash)
     }
     fn pressure_release_recursive(
         &self,
@@ -107,7 +172,8 @@ impl ValveNetwork {
         mut pressure: u32,
         pressure_hash: &mut HashMap<u64, u32>,
         minimum_valve_value: u32,
-    ) {
+    ) -> Result<()> {
+        budget::check()?;
         remaining_minutes -= 1;
         pressure += self.valves.node_weight(current_node).unwrap() * remaining_minutes;
         bit_mask += 2_u64.pow(current_node.index() as u32);
@@ -116,8 +182,11 @@ impl ValveNetwork {
         } else {
             pressure_hash.insert(bit_mask, pressure);
         }
+        progress::state();
+        progress::cache_size(pressure_hash.len());
+        progress::best(pressure as i64);
         if remaining_minutes <= 1 {
-            return;
+            return Ok(());
         }
         for (next_node, distance) in
             self.iter_pair_distance(current_node, remaining_minutes, minimum_valve_value)
@@ -130,44 +199,166 @@ impl ValveNetwork {
                     pressure,
                     pressure_hash,
                     minimum_valve_value,
-                );
+                )?;
             }
         }
+        Ok(())
     }
-    fn best_pressure_release_pair_working(&self, minimum_valve_value: u32) -> u32 {
+    fn best_pressure_release_pair_working(&self, minimum_valve_value: u32) -> Result<u32> {
         let minutes: u32 = 26;
-        let pressure_hash = self.best_pressure_release(minutes, minimum_valve_value);
+        let pressure_hash = self.best_pressure_release(minutes, minimum_valve_value)?;
         let mut max_pressure = 0;
         for (i, (bm_1, p1)) in pressure_hash.iter().enumerate() {
+            budget::check()?;
             for (bm_2, p2) in pressure_hash.iter().skip(i + 1) {
                 if bm_1 & bm_2 == 0 {
                     max_pressure = max_pressure.max(*p1 + *p2);
                 }
             }
         }
+        Ok(max_pressure)
+    }
+    // valves with flow rate, the reference does not filter them by minimum_valve_value
+    fn reference_valves(&self) -> Vec<NodeIndex<u32>> {
+        self.valves
+            .node_indices()
+            .filter(|node| *self.valves.node_weight(*node).unwrap() > 0)
+            .collect()
+    }
+    // reference of pressure release, which tries all orders of opening closed valves;
+    // distances are searched breadth first in tunnels instead of using pair_distance
+    fn reference_pressure_release(&self, closed_valves: &[NodeIndex<u32>], minutes: u32) -> u32 {
+        let distances = self.reference_distances();
+        self.reference_pressure_release_recursive(
+            self.initial_node_id,
+            closed_valves,
+            minutes,
+            &distances,
+        )
+    }
+    fn reference_distances(&self) -> HashMap<NodeIndex<u32>, HashMap<NodeIndex<u32>, u32>> {
+        self.valves
+            .node_indices()
+            .map(|node| (node, self.reference_distances_from(node)))
+            .collect()
+    }
+    fn reference_distances_from(&self, start_node: NodeIndex<u32>) -> HashMap<NodeIndex<u32>, u32> {
+        let mut distances: HashMap<NodeIndex<u32>, u32> = HashMap::from([(start_node, 0)]);
+        let mut queue: VecDeque<NodeIndex<u32>> = VecDeque::from([start_node]);
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[&node];
+            for next_node in self.valves.neighbors(node) {
+                if let Entry::Vacant(e) = distances.entry(next_node) {
+                    e.insert(distance + 1);
+                    queue.push_back(next_node);
+                }
+            }
+        }
+        distances
+    }
+    fn reference_pressure_release_recursive(
+        &self,
+        current_node: NodeIndex<u32>,
+        closed_valves: &[NodeIndex<u32>],
+        minutes: u32,
+        distances: &HashMap<NodeIndex<u32>, HashMap<NodeIndex<u32>, u32>>,
+    ) -> u32 {
+        let mut max_pressure = 0;
+        for (i, next_node) in closed_valves.iter().enumerate() {
+            // moving to valve and opening it takes distance + 1 minutes
+            let Some(distance) = distances[&current_node].get(next_node) else {
+                continue;
+            };
+            if distance + 1 >= minutes {
+                continue;
+            }
+            let remaining_minutes = minutes - distance - 1;
+            let mut still_closed = closed_valves.to_vec();
+            still_closed.remove(i);
+            let pressure = self.valves.node_weight(*next_node).unwrap() * remaining_minutes
+                + self.reference_pressure_release_recursive(
+                    *next_node,
+                    &still_closed,
+                    remaining_minutes,
+                    distances,
+                );
+            max_pressure = max_pressure.max(pressure);
+        }
+        max_pressure
+    }
+    // reference of working in pair, which splits valves with flow rate in all possible ways
+    fn reference_pressure_release_pair_working(&self) -> u32 {
+        let minutes = 26;
+        let valves = self.reference_valves();
+        let distances = self.reference_distances();
+        let mut max_pressure = 0;
+        for split in 0..2_u64.pow(valves.len() as u32) {
+            let mut mine: Vec<NodeIndex<u32>> = Vec::new();
+            let mut others: Vec<NodeIndex<u32>> = Vec::new();
+            for (i, valve) in valves.iter().enumerate() {
+                if split & (1 << i) > 0 {
+                    mine.push(*valve);
+                } else {
+                    others.push(*valve);
+                }
+            }
+            let pressure = [mine, others]
+                .iter()
+                .map(|closed_valves| {
+                    self.reference_pressure_release_recursive(
+                        self.initial_node_id,
+                        closed_valves,
+                        minutes,
+                        &distances,
+                    )
+                })
+                .sum();
+            max_pressure = max_pressure.max(pressure);
+        }
         max_pressure
     }
 }
 
-pub fn day_16() -> Result<()> {
-    let input = include_str!("../../assets/day_16.txt");
-    let valve_network = ValveNetwork::from(input);
-    let minutes = 30;
-    let minimum_valve_value = 3;
-    let result_part1 = *valve_network
-        .best_pressure_release(minutes, minimum_valve_value)
-        .values()
-        .max()
-        .unwrap();
-    println!("result day 16 part 1: {}", result_part1);
-    assert_eq!(result_part1, 2_077);
+pub struct Day16;
 
-    let minimum_valve_value = 1;
-    let result_part2 = valve_network.best_pressure_release_pair_working(minimum_valve_value);
-    println!("result day 16 part 2: {}", result_part2);
-    assert_eq!(result_part2, 2_741);
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    const REFERENCE_PARTS: &'static [Part] = &[Part::One, Part::Two];
+    type Input = ValveNetwork;
+    type PartOne = u32;
+    type PartTwo = u32;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(ValveNetwork::try_from(input)?)
+    }
+    fn part_one(valve_network: &Self::Input) -> Result<u32> {
+        let minutes = 30;
+        let result_part1 =
+            valve_network.most_pressure_released(minutes, valve_network.minimum_valve_value)?;
+        Ok(result_part1)
+    }
+    fn part_two(valve_network: &Self::Input) -> Result<u32> {
+        let minimum_valve_value = 1;
+        let result_part2 = valve_network.most_pressure_released_in_pair(minimum_valve_value)?;
+        Ok(result_part2)
+    }
+    fn reference(valve_network: &Self::Input, part: Part) -> Result<Answer> {
+        let result = match part {
+            Part::One => {
+                valve_network.reference_pressure_release(&valve_network.reference_valves(), 30)
+            }
+            Part::Two => valve_network.reference_pressure_release_pair_working(),
+        };
+        Ok(result.into())
+    }
+    fn apply_params(valve_network: &mut Self::Input, params: &Params) -> Result<()> {
+        params.check_known(Self::DAY, &["minimum_valve_value"])?;
+        if let Some(value) = params.get("minimum_valve_value") {
+            valve_network.minimum_valve_value =
+                u32::try_from(value).context("bad parameter minimum_valve_value of day 16")?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -176,9 +367,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn text_bitmask() {
+    fn text_bitmask() -> Result<()> {
         let input = include_str!("../../assets/day_16_example.txt");
-        let valve_network = ValveNetwork::from(input);
+        let valve_network = ValveNetwork::try_from(input)?;
         let node_count = valve_network.valves.node_count();
         let mut bit_mask: u64 = 0;
         for node in valve_network.valves.node_indices() {
@@ -187,25 +378,6 @@ mod tests {
         assert_eq!(bit_mask, 2_u64.pow(node_count as u32) - 1);
         assert!(bit_mask & 2_u64.pow(valve_network.initial_node_id.index() as u32) > 0);
         assert!(bit_mask & 2_u64.pow(node_count as u32) == 0);
-    }
-
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_16_example.txt");
-        let valve_network = ValveNetwork::from(input);
-        let minutes = 30;
-        let minimum_valve_value = 2;
-        let result_part1 = *valve_network
-            .best_pressure_release(minutes, minimum_valve_value)
-            .values()
-            .max()
-            .unwrap();
-        println!("result example day 16 part 1: {}", result_part1);
-        assert_eq!(result_part1, 1_651);
-
-        let result_part2 = valve_network.best_pressure_release_pair_working(1);
-        println!("result example day 16 part 2: {}", result_part2);
-        assert_eq!(result_part2, 1_707);
         Ok(())
     }
 }
@@ -1,7 +1,15 @@
 //!day_17.rs
+//!
+//! Pyroclastic flow, see [`Chamber`] for height of tower of falling blocks.
 
-use anyhow::Result;
-use std::collections::{hash_map::Entry, HashMap};
+use crate::answer::Answer;
+use crate::budget;
+use crate::parse::ParseError;
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::visualize::{FrameSink, Render};
+use anyhow::{anyhow, Result};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 struct Point {
@@ -159,7 +167,8 @@ impl Block {
     }
 }
 
-struct Chamber {
+/// Chamber of seven units width, into which blocks fall pushed by jets of hot gas.
+pub struct Chamber {
     rocks: Vec<Point>,
     highest_block: isize,
     top_rocks: [isize; 7],
@@ -167,8 +176,15 @@ struct Chamber {
     offset: isize,
 }
 
+impl Default for Chamber {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Chamber {
-    fn new() -> Self {
+    /// Empty chamber with floor at height 0.
+    pub fn new() -> Self {
         let mut rocks: Vec<Point> = Vec::with_capacity(2_022);
         for x in 1..8 {
             rocks.push(Point::new(x, 0));
@@ -181,6 +197,17 @@ impl Chamber {
             offset: 0,
         }
     }
+    /// Height of tower after num_blocks blocks have fallen, pushed by jets of pattern, which
+    /// is repeated; `>` pushes right, all other chars push left. Repeated sequences of blocks
+    /// are skipped, which allows huge num_blocks. Fails if the time budget of the running thread
+    /// is exceeded, e.g. by huge num_blocks without repeated sequences.
+    ///
+    /// # Panics
+    ///
+    /// Panics if num_blocks is not positive or jet pattern is empty.
+    pub fn tower_height(jet_streams: &str, num_blocks: isize) -> Result<isize> {
+        Chamber::new().falling_blocks(num_blocks, jet_streams)
+    }
     fn check_block(&self, block: &Block) -> bool {
         if block.left_rock() == 0 || block.right_rock() == 8 || block.bottom_rock() == 0 {
             return false;
@@ -206,82 +233,188 @@ impl Chamber {
             self.normalized_top_rocks[i] = tr - self.highest_block;
         }
     }
-    fn falling_blocks(&mut self, num_blocks: isize, jet_streams: &str) -> isize {
+    // drops next block of block_source until it rests, returns index of block and of last jet
+    fn drop_block(
+        &mut self,
+        block_source: &mut Block,
+        jets: &[bool],
+        jet_index: &mut usize,
+    ) -> (u8, usize) {
+        let (block_index, mut block) = block_source.spawn_new_block(self.highest_block);
+        loop {
+            let current_jet_index = *jet_index;
+            *jet_index = (*jet_index + 1) % jets.len();
+            let jet_block = block.apply_jet(jets[current_jet_index]);
+            if self.check_block(&jet_block) {
+                block = jet_block;
+            }
+            let falling_block = block.move_down();
+            if self.check_block(&falling_block) {
+                block = falling_block;
+            } else {
+                self.add_block(&block);
+                return (block_index, current_jet_index);
+            }
+        }
+    }
+    fn falling_blocks(&mut self, num_blocks: isize, jet_streams: &str) -> Result<isize> {
         assert!(num_blocks > 0);
         let mut block_source = Block::init();
         let mut block_counter = 0;
         let mut seen_sequences: HashMap<(u8, usize, [isize; 7]), (isize, isize)> =
             HashMap::with_capacity(2_022);
-        let mut jet_iter = jet_streams.chars().map(|c| c == '>').enumerate().cycle();
+        let jets: Vec<bool> = jet_streams.chars().map(|c| c == '>').collect();
+        let mut jet_index = 0;
         while block_counter < num_blocks {
-            let (block_index, mut block) = block_source.spawn_new_block(self.highest_block);
-            for (jet_index, jet) in &mut jet_iter {
-                let jet_block = block.apply_jet(jet);
-                if self.check_block(&jet_block) {
-                    block = jet_block;
-                }
-                let falling_block = block.move_down();
-                if self.check_block(&falling_block) {
-                    block = falling_block;
-                } else {
-                    self.add_block(&block);
-                    block_counter += 1;
-                    if self.offset == 0 {
-                        let key = (block_index, jet_index, self.normalized_top_rocks);
-                        if let Entry::Vacant(e) = seen_sequences.entry(key) {
-                            e.insert((block_counter, self.highest_block));
-                        } else if let Some((last_block_counter, last_highest_block)) =
-                            seen_sequences.get(&key)
-                        {
-                            // calc offset and increment block_counter with number of sequence blocks
-                            let num_sequences =
-                                (num_blocks - block_counter) / (block_counter - last_block_counter);
-                            self.offset = num_sequences * (self.highest_block - last_highest_block);
-                            block_counter += num_sequences * (block_counter - last_block_counter);
-                        }
-                    }
-                    break;
+            budget::check()?;
+            let (block_index, last_jet_index) =
+                self.drop_block(&mut block_source, &jets, &mut jet_index);
+            block_counter += 1;
+            if self.offset == 0 {
+                let key = (block_index, last_jet_index, self.normalized_top_rocks);
+                if let Entry::Vacant(e) = seen_sequences.entry(key) {
+                    e.insert((block_counter, self.highest_block));
+                } else if let Some((last_block_counter, last_highest_block)) =
+                    seen_sequences.get(&key)
+                {
+                    // calc offset and increment block_counter with number of sequence blocks
+                    let num_sequences =
+                        (num_blocks - block_counter) / (block_counter - last_block_counter);
+                    self.offset = num_sequences * (self.highest_block - last_highest_block);
+                    block_counter += num_sequences * (block_counter - last_block_counter);
                 }
             }
         }
-        self.highest_block + self.offset
+        Ok(self.highest_block + self.offset)
     }
 }
 
-pub fn day_17() -> Result<()> {
-    let input = include_str!("../../assets/day_17.txt");
-    let num_rocks = 2_022;
-    let mut chamber = Chamber::new();
-    let result_part1 = chamber.falling_blocks(num_rocks, input);
-    println!("result day 17 part 1: {}", result_part1);
-    assert_eq!(result_part1, 3_193);
-    let num_rocks = 1_000_000_000_000;
-    let mut chamber = Chamber::new();
-    let result_part1 = chamber.falling_blocks(num_rocks, input);
-    println!("result day 17 part 2: {}", result_part1);
-    assert_eq!(result_part1, 1_577_650_429_835);
+// rows of chamber shown below highest block
+const VISIBLE_ROWS: isize = 40;
 
-    Ok(())
+impl Render for Chamber {
+    fn render(&self) -> String {
+        let lowest_row = (self.highest_block - VISIBLE_ROWS + 1).max(1);
+        let visible_rocks: HashSet<(isize, isize)> = self
+            .rocks
+            .iter()
+            .filter(|r| r.y >= lowest_row)
+            .map(|r| (r.x, r.y))
+            .collect();
+        let mut rows: Vec<String> = (lowest_row..=self.highest_block)
+            .rev()
+            .map(|y| {
+                let row: String = (1..8)
+                    .map(|x| if visible_rocks.contains(&(x, y)) { '#' } else { '.' })
+                    .collect();
+                format!("|{}|", row)
+            })
+            .collect();
+        rows.push(if lowest_row == 1 {
+            String::from("+-------+")
+        } else {
+            String::from("|~~~~~~~|")
+        });
+        rows.join("\n")
+    }
 }
 
-#[cfg(test)]
-mod tests {
+// rocks of blocks of reference as offsets from their left bottom corner
+const REFERENCE_BLOCKS: [&[(isize, isize)]; 5] = [
+    &[(0, 0), (1, 0), (2, 0), (3, 0)],
+    &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+    &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+    &[(0, 0), (0, 1), (0, 2), (0, 3)],
+    &[(0, 0), (1, 0), (0, 1), (1, 1)],
+];
 
-    use super::*;
+// reference of tower height, which drops every block without skipping repeated sequences
+fn reference_tower_height(jet_streams: &str, num_blocks: usize) -> isize {
+    let jets: Vec<char> = jet_streams.chars().collect();
+    let mut rocks: HashSet<(isize, isize)> = HashSet::new();
+    let mut highest_rock = 0;
+    let mut jet_index = 0;
+    for block in REFERENCE_BLOCKS.iter().cycle().take(num_blocks) {
+        // chamber has columns 0 to 6 above floor at row 0
+        let fits = |x: isize, y: isize| {
+            block.iter().all(|(dx, dy)| {
+                (0..7).contains(&(x + dx)) && y + dy > 0 && !rocks.contains(&(x + dx, y + dy))
+            })
+        };
+        let (mut x, mut y) = (2, highest_rock + 4);
+        loop {
+            let dx = if jets[jet_index] == '>' { 1 } else { -1 };
+            jet_index = (jet_index + 1) % jets.len();
+            if fits(x + dx, y) {
+                x += dx;
+            }
+            if !fits(x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+        for (dx, dy) in block.iter() {
+            rocks.insert((x + dx, y + dy));
+            highest_rock = highest_rock.max(y + dy);
+        }
+    }
+    highest_rock
+}
+
+pub struct Day17;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    // part 2 drops far too many blocks without skipping
+    const REFERENCE_PARTS: &'static [Part] = &[Part::One];
+    type Input = String;
+    type PartOne = isize;
+    type PartTwo = isize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        // jet pattern is a single line
+        let jet_streams = input.trim();
+        if let Some((i, c)) = jet_streams.char_indices().find(|(_, c)| !matches!(c, '<' | '>')) {
+            let token = &jet_streams[i..i + c.len_utf8()];
+            return Err(ParseError::new(input, token, "jet '<' or '>'").into());
+        }
+        if jet_streams.is_empty() {
+            return Err(ParseError::end_of(input, "jet '<' or '>'").into());
+        }
+        Ok(jet_streams.to_string())
+    }
+    fn part_one(jet_streams: &Self::Input) -> Result<isize> {
         let num_rocks = 2_022;
-        let mut chamber = Chamber::new();
-        let result_part1 = chamber.falling_blocks(num_rocks, input);
-        println!("result example day 17 part 1: {}", result_part1);
-        assert_eq!(result_part1, 3_068);
+        let result_part1 = Chamber::tower_height(jet_streams, num_rocks)?;
+        Ok(result_part1)
+    }
+    fn part_two(jet_streams: &Self::Input) -> Result<isize> {
         let num_rocks = 1_000_000_000_000;
+        let result_part2 = Chamber::tower_height(jet_streams, num_rocks)?;
+        Ok(result_part2)
+    }
+    fn reference(jet_streams: &Self::Input, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => Ok(reference_tower_height(jet_streams, 2_022).into()),
+            Part::Two => Err(anyhow!("day 17 has no reference solver of part 2")),
+        }
+    }
+    fn visualize(jet_streams: &Self::Input, _part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        // part 2 extrapolates the tower of part 1, which is shown for both parts
         let mut chamber = Chamber::new();
-        let result_part1 = chamber.falling_blocks(num_rocks, input);
-        println!("result example day 17 part 2: {}", result_part1);
-        assert_eq!(result_part1, 1_514_285_714_288);
+        let mut block_source = Block::init();
+        let jets: Vec<bool> = jet_streams.chars().map(|c| c == '>').collect();
+        let mut jet_index = 0;
+        if !sink.frame(&chamber)? {
+            return Ok(());
+        }
+        for _ in 0..2_022 {
+            chamber.drop_block(&mut block_source, &jets, &mut jet_index);
+            if !sink.frame(&chamber)? {
+                break;
+            }
+        }
         Ok(())
     }
 }
+
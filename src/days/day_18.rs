@@ -1,29 +1,34 @@
 //!day_18.rs
+//!
+//! Boiling boulders, see [`count_surfaces`] for surface area of lava droplet.
 
+use crate::parse::{next_number, parse_lines, ParseError};
+use crate::solution::Solution;
 use anyhow::Result;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Point3D {
+/// Unit cube of lava droplet, parsed from e.g. `2,2,2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point3D {
     x: i32,
     y: i32,
     z: i32,
 }
 
-impl From<&str> for Point3D {
-    fn from(value: &str) -> Self {
-        let mut v_iter = value
-            .split(',')
-            .map(|c| c.parse::<i32>().expect("bad input"));
-        Self {
-            x: v_iter.next().unwrap(),
-            y: v_iter.next().unwrap(),
-            z: v_iter.next().unwrap(),
-        }
+impl TryFrom<&str> for Point3D {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let mut v_iter = value.split(',');
+        Ok(Self {
+            x: next_number(value, &mut v_iter)?,
+            y: next_number(value, &mut v_iter)?,
+            z: next_number(value, &mut v_iter)?,
+        })
     }
 }
 
 impl Point3D {
-    fn new(x: i32, y: i32, z: i32) -> Self {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
         Self { x, y, z }
     }
     fn add(&self, other: &Self) -> Self {
@@ -57,7 +62,13 @@ impl Point3D {
     }
 }
 
-fn count_surfaces(points: &[Point3D]) -> (usize, usize) {
+/// Number of sides of cubes, which are not connected to another cube, and number of those
+/// sides, which are reachable from outside of droplet.
+///
+/// # Panics
+///
+/// Panics if there are no cubes.
+pub fn count_surfaces(points: &[Point3D]) -> (usize, usize) {
     let mut min = points[0];
     let mut max = points[0];
     let dirs = [
@@ -101,45 +112,24 @@ fn count_surfaces(points: &[Point3D]) -> (usize, usize) {
     (n_surfaces, n_surfaces_outside)
 }
 
-pub fn day_18() -> Result<()> {
-    let input = include_str!("../../assets/day_18.txt");
-    let points: Vec<Point3D> = input.lines().map(Point3D::from).collect();
-    let (result_part1, result_part2) = count_surfaces(&points);
-    println!("result day 18 part 1: {}", result_part1);
-    assert_eq!(result_part1, 4_548);
-    println!("result day 18 part 2: {}", result_part2);
-    assert_eq!(result_part2, 2_588);
-
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
+pub struct Day18;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "2,2,2\n\
-                           1,2,2\n\
-                           3,2,2\n\
-                           2,1,2\n\
-                           2,3,2\n\
-                           2,2,1\n\
-                           2,2,3\n\
-                           2,2,4\n\
-                           2,2,6\n\
-                           1,2,5\n\
-                           3,2,5\n\
-                           2,1,5\n\
-                           2,3,5";
-        let points: Vec<Point3D> = input.lines().map(Point3D::from).collect();
-        let (result_part1, result_part2) = count_surfaces(&points);
-        println!("result example day 18 part 1: {}", result_part1);
-        assert_eq!(result_part1, 64);
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    type Input = Vec<Point3D>;
+    type PartOne = usize;
+    type PartTwo = usize;
 
-        println!("result example day 18 part 2: {}", result_part2);
-        assert_eq!(result_part2, 58);
-        Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, Point3D::try_from)?)
+    }
+    fn part_one(points: &Self::Input) -> Result<usize> {
+        let (result_part1, _) = count_surfaces(points);
+        Ok(result_part1)
+    }
+    fn part_two(points: &Self::Input) -> Result<usize> {
+        let (_, result_part2) = count_surfaces(points);
+        Ok(result_part2)
     }
 }
+
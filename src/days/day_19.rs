@@ -1,6 +1,14 @@
 //!day_19.rs
+//!
+//! Not enough minerals, see [`GeodeCollection`] for most geodes opened with a blueprint.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::budget;
+use crate::parse::{next_number, parse_lines, ParseError};
+use crate::progress;
+use crate::selection::Part;
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
 use std::collections::HashMap;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
@@ -15,34 +23,41 @@ struct BluePrint {
     max_ore_required: u64,
 }
 
-impl From<&str> for BluePrint {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for BluePrint {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        // only numbers of blue print are of interest
         let mut v_iter = value
             .split_ascii_whitespace()
-            .filter_map(|s| s.strip_suffix(':').or(Some(s)))
-            .filter_map(|s| s.parse::<u64>().ok());
+            .map(|s| s.strip_suffix(':').unwrap_or(s))
+            .filter(|s| s.starts_with(|c: char| c.is_ascii_digit()));
         let mut bp = Self {
-            id: v_iter.next().unwrap(),
-            ore_robot_ore: v_iter.next().unwrap(),
-            clay_robot_ore: v_iter.next().unwrap(),
-            obsidian_robot_ore: v_iter.next().unwrap(),
-            obsidian_robot_clay: v_iter.next().unwrap(),
-            geode_robot_ore: v_iter.next().unwrap(),
-            geode_robot_obsidian: v_iter.next().unwrap(),
+            id: next_number(value, &mut v_iter)?,
+            ore_robot_ore: next_number(value, &mut v_iter)?,
+            clay_robot_ore: next_number(value, &mut v_iter)?,
+            obsidian_robot_ore: next_number(value, &mut v_iter)?,
+            obsidian_robot_clay: next_number(value, &mut v_iter)?,
+            geode_robot_ore: next_number(value, &mut v_iter)?,
+            geode_robot_obsidian: next_number(value, &mut v_iter)?,
             max_ore_required: 0,
         };
-        assert!(v_iter.next().is_none());
+        if let Some(token) = v_iter.next() {
+            return Err(ParseError::new(value, token, "end of blue print"));
+        }
         bp.max_ore_required = bp
             .ore_robot_ore
             .max(bp.clay_robot_ore)
             .max(bp.obsidian_robot_ore)
             .max(bp.geode_robot_ore);
-        bp
+        Ok(bp)
     }
 }
 
+/// Robots and collected ressources of a blueprint, which starts with one ore robot; parsed
+/// with `Day19::parse()` from one blueprint per line.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct GeodeCollection {
+pub struct GeodeCollection {
     n_ore_robots: u64,
     ore: u64,
     n_clay_robots: u64,
@@ -62,6 +77,15 @@ impl GeodeCollection {
             ..Default::default()
         }
     }
+    /// Id of blueprint.
+    pub fn blue_print_id(&self) -> u64 {
+        self.blue_print.id
+    }
+    /// Most geodes opened within minutes. Fails if the time budget of the running thread is
+    /// exceeded.
+    pub fn max_geodes(&self, minutes: u64) -> Result<u64> {
+        self.execute(minutes)
+    }
     fn actions(&self, minutes: u64) -> Vec<(Self, u64)> {
         let mut actions: Vec<(Self, u64)> = Vec::with_capacity(4);
         // build geode robot if possible
@@ -189,100 +213,183 @@ impl GeodeCollection {
         geode_robot.clear_excess_ressources(remaining_minutes);
         Some((geode_robot, remaining_minutes))
     }
-    fn execute(&self, minutes: u64) -> u64 {
+    fn execute(&self, minutes: u64) -> Result<u64> {
+        let _search = progress::start(format!("day 19 blueprint {}", self.blue_print.id));
         let mut cache: HashMap<(u64, Self), u64> = HashMap::new();
         self.execute_recursive(&mut cache, minutes)
     }
-    fn execute_recursive(&self, cache: &mut HashMap<(u64, Self), u64>, minutes: u64) -> u64 {
+    fn execute_recursive(
+        &self,
+        cache: &mut HashMap<(u64, Self), u64>,
+        minutes: u64,
+    ) -> Result<u64> {
         if minutes == 0 {
-            return self.geodes;
+            return Ok(self.geodes);
         }
         if let Some(max_geode_collection) = cache.get(&(minutes, *self)) {
-            return *max_geode_collection;
+            return Ok(*max_geode_collection);
         }
+        budget::check()?;
+        progress::state();
 
         let mut max_geode_collection = self.n_geode_robots * minutes + self.geodes;
 
         for (action, remaining_minutes) in self.actions(minutes).iter() {
             max_geode_collection =
-                max_geode_collection.max(action.execute_recursive(cache, *remaining_minutes));
+                max_geode_collection.max(action.execute_recursive(cache, *remaining_minutes)?);
         }
 
         cache.insert((minutes, *self), max_geode_collection);
-        max_geode_collection
+        progress::cache_size(cache.len());
+        progress::best(max_geode_collection as i64);
+        Ok(max_geode_collection)
     }
 }
 
-pub fn day_19() -> Result<()> {
-    let input = include_str!("../../assets/day_19.txt");
-    let geode_collectors: Vec<GeodeCollection> = input
-        .lines()
-        .map(BluePrint::from)
-        .map(GeodeCollection::new)
-        .collect();
-    let minutes = 24;
-    let result_part1: u64 = geode_collectors
-        .iter()
-        .map(|gc| gc.execute(minutes) * gc.blue_print.id)
-        .sum();
-    println!("result day 19 part 1: {}", result_part1);
-    assert_eq!(result_part1, 2_341);
+// reference of geode collection, which decides minute by minute without skipping minutes,
+// limiting robots or clearing ressources; it only cuts branches, which are dominated or
+// cannot beat the best collection found so far
+fn reference_geodes(blue_print: &BluePrint, minutes: u64) -> Result<u64> {
+    let mut max_geodes = 0;
+    reference_geodes_recursive(
+        blue_print,
+        [1, 0, 0, 0],
+        [0; 4],
+        minutes,
+        [false; 4],
+        &mut max_geodes,
+    )?;
+    Ok(max_geodes)
+}
 
-    #[cfg(feature = "long-run-time")]
-    {
-        let minutes = 32;
-        let result_part2: u64 = geode_collectors
-            .iter()
-            .take(3)
-            .map(|gc| gc.execute(minutes))
-            .product();
-        println!("result day 19 part 2: {}", result_part2);
-        assert_eq!(result_part2, 3_689);
+// robots and ressources are ordered ore, clay, obsidian and geode;
+// skipped robots were affordable in the minute before, but the collection waited
+fn reference_geodes_recursive(
+    blue_print: &BluePrint,
+    robots: [u64; 4],
+    ressources: [u64; 4],
+    minutes: u64,
+    skipped: [bool; 4],
+    max_geodes: &mut u64,
+) -> Result<()> {
+    if minutes == 0 {
+        *max_geodes = (*max_geodes).max(ressources[3]);
+        return Ok(());
+    }
+    if reference_upper_bound(blue_print, robots, ressources, minutes) <= *max_geodes {
+        return Ok(());
+    }
+    budget::check()?;
+    let costs = [
+        [blue_print.ore_robot_ore, 0, 0],
+        [blue_print.clay_robot_ore, 0, 0],
+        [blue_print.obsidian_robot_ore, blue_print.obsidian_robot_clay, 0],
+        [blue_print.geode_robot_ore, 0, blue_print.geode_robot_obsidian],
+    ];
+    let affordable = costs.map(|cost| ressources.iter().zip(cost.iter()).all(|(r, c)| r >= c));
+    let mut collected = ressources;
+    for (c, r) in collected.iter_mut().zip(robots.iter()) {
+        *c += r;
+    }
+    // geode robots first, which raises max_geodes early
+    for robot in (0..4).rev() {
+        // building a skipped robot now is never better than building it before waiting
+        if !affordable[robot] || skipped[robot] {
+            continue;
+        }
+        let mut built_robots = robots;
+        built_robots[robot] += 1;
+        let mut remaining = collected;
+        for (r, c) in remaining.iter_mut().zip(costs[robot].iter()) {
+            *r -= c;
+        }
+        reference_geodes_recursive(
+            blue_print,
+            built_robots,
+            remaining,
+            minutes - 1,
+            [false; 4],
+            max_geodes,
+        )?;
     }
-    #[cfg(not(feature = "long-run-time"))]
-    {
-        println!("day 19 part 2 skipped because of long run time")
+    reference_geodes_recursive(
+        blue_print,
+        robots,
+        collected,
+        minutes - 1,
+        affordable,
+        max_geodes,
+    )
+}
+
+// geodes collected, if ore was unlimited and a robot of each kind could be built every minute
+fn reference_upper_bound(
+    blue_print: &BluePrint,
+    robots: [u64; 4],
+    ressources: [u64; 4],
+    minutes: u64,
+) -> u64 {
+    let [_, mut clay_robots, mut obsidian_robots, mut geode_robots] = robots;
+    let [_, mut clay, mut obsidian, mut geodes] = ressources;
+    for _ in 0..minutes {
+        let build_geode_robot = obsidian >= blue_print.geode_robot_obsidian;
+        let build_obsidian_robot = clay >= blue_print.obsidian_robot_clay;
+        clay += clay_robots;
+        obsidian += obsidian_robots;
+        geodes += geode_robots;
+        if build_geode_robot {
+            obsidian -= blue_print.geode_robot_obsidian;
+            geode_robots += 1;
+        }
+        if build_obsidian_robot {
+            clay -= blue_print.obsidian_robot_clay;
+            obsidian_robots += 1;
+        }
+        clay_robots += 1;
     }
-    Ok(())
+    geodes
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day19;
 
-    use super::*;
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+    const SLOW_PARTS: &'static [Part] = &[Part::Two];
+    // reference of part 2 takes too long for 32 minutes
+    const REFERENCE_PARTS: &'static [Part] = &[Part::One];
+    type Input = Vec<GeodeCollection>;
+    type PartOne = u64;
+    type PartTwo = u64;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "Blueprint 1: \
-            Each ore robot costs 4 ore. \
-            Each clay robot costs 2 ore. \
-            Each obsidian robot costs 3 ore and 14 clay. \
-            Each geode robot costs 2 ore and 7 obsidian.\n\
-        Blueprint 2: \
-            Each ore robot costs 2 ore. \
-            Each clay robot costs 3 ore. \
-            Each obsidian robot costs 3 ore and 8 clay. \
-            Each geode robot costs 3 ore and 12 obsidian.";
-        let geode_collectors: Vec<GeodeCollection> = input
-            .lines()
-            .map(BluePrint::from)
-            .map(GeodeCollection::new)
-            .collect();
+    fn parse(input: &str) -> Result<Self::Input> {
+        let blue_prints = parse_lines(input, BluePrint::try_from)?;
+        Ok(blue_prints.into_iter().map(GeodeCollection::new).collect())
+    }
+    fn part_one(geode_collectors: &Self::Input) -> Result<u64> {
         let minutes = 24;
-        let result_part1: u64 = geode_collectors
-            .iter()
-            .map(|gc| gc.execute(minutes) * gc.blue_print.id)
-            .sum();
-        println!("result example day 19 part 1: {}", result_part1);
-        assert_eq!(result_part1, 33);
-        // part 2
+        let mut result_part1: u64 = 0;
+        for gc in geode_collectors.iter() {
+            result_part1 += gc.max_geodes(minutes)? * gc.blue_print_id();
+        }
+        Ok(result_part1)
+    }
+    fn part_two(geode_collectors: &Self::Input) -> Result<u64> {
         let minutes = 32;
-        let result_part2_0 = geode_collectors[0].execute(minutes);
-        println!("result example day 19 part 2, 0: {}", result_part2_0);
-        assert_eq!(result_part2_0, 56);
-        let result_part2_1 = geode_collectors[1].execute(minutes);
-        println!("result example day 19 part 2, 1: {}", result_part2_1);
-        assert_eq!(result_part2_1, 62);
-        Ok(())
+        let mut result_part2: u64 = 1;
+        for gc in geode_collectors.iter().take(3) {
+            result_part2 *= gc.max_geodes(minutes)?;
+        }
+        Ok(result_part2)
+    }
+    fn reference(geode_collectors: &Self::Input, part: Part) -> Result<Answer> {
+        if part == Part::Two {
+            return Err(anyhow!("day 19 has no reference solver of part 2"));
+        }
+        let mut quality_levels: u64 = 0;
+        for gc in geode_collectors.iter() {
+            quality_levels += reference_geodes(&gc.blue_print, 24)? * gc.blue_print.id;
+        }
+        Ok(quality_levels.into())
     }
 }
+
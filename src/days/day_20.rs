@@ -1,10 +1,18 @@
 //!day_20.rs
+//!
+//! Grove positioning system, see [`rotate_codes`] for mixing of encrypted file.
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::parse::{number, parse_lines, ParseError};
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::trace;
+use anyhow::{anyhow, Result};
 use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Copy)]
-struct Code {
+/// Number of encrypted file and its index in original order, created from `(index, number)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Code {
     code: i64,
     id: usize,
 }
@@ -27,7 +35,9 @@ impl Code {
     }
 }
 
-fn rotate_codes(codes: &[Code], decryption_key: i64, cycles: u64) -> Vec<Code> {
+/// Mixes codes multiplied by decryption_key cycles times; each code moves by its number in
+/// original order, the list is circular.
+pub fn rotate_codes(codes: &[Code], decryption_key: i64, cycles: u64) -> Vec<Code> {
     let len = codes.len() as i64;
     let codes: Vec<Code> = codes
         .iter()
@@ -61,65 +71,87 @@ fn rotate_codes(codes: &[Code], decryption_key: i64, cycles: u64) -> Vec<Code> {
     rotated_codes
 }
 
-fn get_coordinates_sum(codes: &[Code]) -> i64 {
+/// Sum of the 1000th, 2000th and 3000th number after number 0.
+///
+/// # Panics
+///
+/// Panics if there is no code with number 0.
+pub fn get_coordinates_sum(codes: &[Code]) -> i64 {
     let len = codes.len();
     let index_0 = codes.iter().position(|c| c.code == 0).unwrap();
     let mut sum = 0;
     for steps in [1000_usize, 2000, 3000].into_iter() {
         let remaining_steps = steps % len;
         let index_steps = (index_0 + remaining_steps) % len;
-        #[cfg(test)]
-        eprintln!("{}", codes[index_steps].code);
+        trace::event!(
+            Debug,
+            "{}th number after 0: {}",
+            steps,
+            codes[index_steps].code
+        );
         sum += codes[index_steps].code;
     }
     sum
 }
 
-pub fn day_20() -> Result<()> {
-    let input = include_str!("../../assets/day_20.txt");
-    let codes: Vec<Code> = input
-        .lines()
-        .enumerate()
-        .map(|(id, c)| Code::from((id, c.parse::<i64>().expect("bad input"))))
-        .collect();
-    let rotated_codes = rotate_codes(&codes, 1, 1);
-    let result_part1 = get_coordinates_sum(&rotated_codes);
-    println!("result day 20 part 1: {}", result_part1);
-    assert_eq!(result_part1, 4_426);
-
-    let decryption_key = 811_589_153;
-    let cycles = 10;
-    let rotated_codes = rotate_codes(&codes, decryption_key, cycles);
-    let result_part2 = get_coordinates_sum(&rotated_codes);
-    println!("result day 20 part 2: {}", result_part2);
-    assert_eq!(result_part2, 8_119_137_886_612);
-
-    Ok(())
+// reference of part 1, which moves each code by swapping it with its neighbour step by step
+fn reference_mixing(codes: &[Code]) -> i64 {
+    let len = codes.len();
+    let mut mixed: Vec<Code> = codes.to_vec();
+    for c in codes.iter() {
+        let mut index = mixed.iter().position(|m| m.id == c.id).unwrap();
+        for _ in 0..c.code.unsigned_abs() {
+            let next_index = if c.code > 0 {
+                (index + 1) % len
+            } else {
+                (index + len - 1) % len
+            };
+            mixed.swap(index, next_index);
+            index = next_index;
+        }
+    }
+    let index_0 = mixed.iter().position(|m| m.code == 0).unwrap();
+    [1000, 2000, 3000]
+        .iter()
+        .map(|steps| mixed[(index_0 + steps) % len].code)
+        .sum()
 }
 
-#[cfg(test)]
-mod tests {
+pub struct Day20;
 
-    use super::*;
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+    // part 2 would need billions of steps per code
+    const REFERENCE_PARTS: &'static [Part] = &[Part::One];
+    type Input = Vec<Code>;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let a: i64 = -9;
-        eprintln!("mod test: {}", a.rem_euclid(7));
-        let codes = [1, 2, -3, 3, -2, 0, 4];
-        let codes: Vec<Code> = codes.into_iter().enumerate().map(Code::from).collect();
-        let rotated_codes = rotate_codes(&codes, 1, 1);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let codes = parse_lines(input, |c| number::<i64>(c, c))?;
+        // rotation needs at least two codes and coordinates are counted from code 0
+        if codes.len() < 2 || !codes.contains(&0) {
+            return Err(ParseError::end_of(input, "at least two codes including 0").into());
+        }
+        Ok(codes.into_iter().enumerate().map(Code::from).collect())
+    }
+    fn part_one(codes: &Self::Input) -> Result<i64> {
+        let rotated_codes = rotate_codes(codes, 1, 1);
         let result_part1 = get_coordinates_sum(&rotated_codes);
-        println!("result example day 20 part 1: {}", result_part1);
-        assert_eq!(result_part1, 3);
-
+        Ok(result_part1)
+    }
+    fn part_two(codes: &Self::Input) -> Result<i64> {
         let decryption_key = 811_589_153;
         let cycles = 10;
-        let rotated_codes = rotate_codes(&codes, decryption_key, cycles);
+        let rotated_codes = rotate_codes(codes, decryption_key, cycles);
         let result_part2 = get_coordinates_sum(&rotated_codes);
-        println!("result example day 20 part 2: {}", result_part2);
-        assert_eq!(result_part2, 1_623_178_306);
-
-        Ok(())
+        Ok(result_part2)
+    }
+    fn reference(codes: &Self::Input, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => Ok(reference_mixing(codes).into()),
+            Part::Two => Err(anyhow!("day 20 has no reference solver of part 2")),
+        }
     }
 }
+
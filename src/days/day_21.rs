@@ -1,10 +1,16 @@
 //!day_21.rs
+//!
+//! Monkey math, see [`eval_monkeys`] and [`eval_human`] for numbers yelled by monkeys.
 
+use crate::parse::{next_token, parse_lines, split_once, ParseError};
+use crate::repl::{argument, Command, Explore};
+use crate::solution::Solution;
 use anyhow::{anyhow, Result};
 use evalexpr::eval_int;
 use std::collections::HashMap;
 
-fn eval_monkeys(key: String, monkeys: &HashMap<String, String>) -> Result<i64> {
+/// Number yelled by monkey key. Fails if a monkey is missing.
+pub fn eval_monkeys(key: String, monkeys: &HashMap<String, String>) -> Result<i64> {
     let value = monkeys.get(&key).ok_or(anyhow!("key not found"))?;
     match value.parse::<i64>() {
         Ok(v) => Ok(v),
@@ -39,7 +45,9 @@ fn eval_non_humn_expr<'a>(
     }
 }
 
-fn eval_human(monkeys: &HashMap<String, String>) -> Result<i64> {
+/// Number humn has to yell, so that both monkeys of root yell the same number. Fails if job
+/// of root does not depend on humn.
+pub fn eval_human(monkeys: &HashMap<String, String>) -> Result<i64> {
     // 1. go from first call of humn up to root and collect keys in path to humn
     let mut current_key = &String::from("humn");
     let mut humn_keys: Vec<&String> = vec![current_key];
@@ -47,7 +55,7 @@ fn eval_human(monkeys: &HashMap<String, String>) -> Result<i64> {
         let (key, _) = monkeys
             .iter()
             .find(|(_, v)| v.contains(current_key))
-            .unwrap();
+            .ok_or(anyhow!("job of root does not depend on humn"))?;
         current_key = key;
         if key == "root" {
             break;
@@ -76,52 +84,118 @@ fn eval_human(monkeys: &HashMap<String, String>) -> Result<i64> {
     Ok(eval_value)
 }
 
-pub fn day_21() -> Result<()> {
-    let input = include_str!("../../assets/day_21.txt");
-    let monkeys: HashMap<String, String> = input
-        .lines()
-        .map(|l| {
-            l.split_once(": ")
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .unwrap()
-        })
-        .collect();
-
-    let result_part1 = eval_monkeys(String::from("root"), &monkeys)?;
-    println!("result day 21 part 1: {}", result_part1);
-    assert_eq!(result_part1, 232_974_643_455_000);
-
-    let result_part2 = eval_human(&monkeys)?;
-    println!("result day 21 part 2: {}", result_part2);
-    assert_eq!(result_part2, 3_740_214_169_961);
+// monkey name and job, which is either a number or an operation of two monkeys
+fn monkey_job(line: &str) -> Result<(String, String), ParseError> {
+    let (key, job) = split_once(line, line, ": ")?;
+    if job.parse::<i64>().is_err() {
+        let mut expression_iter = job.split_whitespace();
+        next_token(line, &mut expression_iter, "monkey")?;
+        let symbol = next_token(line, &mut expression_iter, "operation")?;
+        if !["+", "-", "*", "/"].contains(&symbol) {
+            return Err(ParseError::new(line, symbol, "operation +, -, * or /"));
+        }
+        next_token(line, &mut expression_iter, "monkey")?;
+        if let Some(token) = expression_iter.next() {
+            return Err(ParseError::new(line, token, "end of job"));
+        }
+    }
+    Ok((key.to_string(), job.to_string()))
+}
 
+// monkeys in operations of jobs must have a job themselves
+fn check_monkeys(input: &str, monkeys: &HashMap<String, String>) -> Result<(), ParseError> {
+    for line in input.lines() {
+        let Some((_, job)) = line.split_once(": ") else {
+            continue;
+        };
+        if job.parse::<i64>().is_ok() {
+            continue;
+        }
+        for monkey in job.split_whitespace().step_by(2) {
+            if !monkeys.contains_key(monkey) {
+                return Err(ParseError::new(input, monkey, "monkey with a job"));
+            }
+        }
+    }
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
+// monkeys of REPL, whose numbers can be changed to test their effect on root
+struct MonkeyExplorer {
+    monkeys: HashMap<String, String>,
+}
+
+const MONKEY_COMMANDS: &[Command] = &[
+    ("eval MONKEY", "evaluates number yelled by MONKEY"),
+    ("job MONKEY", "shows job of MONKEY"),
+    ("set MONKEY N", "changes job of MONKEY to yelling N"),
+    ("humn", "solves number humn has to yell"),
+];
 
-    use super::*;
+impl MonkeyExplorer {
+    fn job(&self, args: &[&str]) -> Result<(String, &String)> {
+        let monkey: String = argument(args, 0, None)?;
+        let job = self
+            .monkeys
+            .get(&monkey)
+            .ok_or(anyhow!("monkey {} does not exist", monkey))?;
+        Ok((monkey, job))
+    }
+}
+
+impl Explore for MonkeyExplorer {
+    fn commands(&self) -> &'static [Command] {
+        MONKEY_COMMANDS
+    }
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "eval" => {
+                let (monkey, _) = self.job(args)?;
+                Ok(eval_monkeys(monkey, &self.monkeys)?.to_string())
+            }
+            "job" => Ok(self.job(args)?.1.clone()),
+            "set" => {
+                // only numbers, jobs of other monkeys could create cycles
+                let (monkey, _) = self.job(args)?;
+                let number: i64 = argument(args, 1, None)?;
+                self.monkeys.insert(monkey.clone(), number.to_string());
+                Ok(format!("{}: {}", monkey, number))
+            }
+            "humn" => Ok(eval_human(&self.monkeys)?.to_string()),
+            _ => Err(anyhow!("unknown command '{}'", command)),
+        }
+    }
+}
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_21_example.txt");
-        let monkeys: HashMap<String, String> = input
-            .lines()
-            .map(|l| {
-                l.split_once(": ")
-                    .map(|(k, v)| (k.to_string(), v.to_string()))
-                    .unwrap()
-            })
-            .collect();
+pub struct Day21;
 
-        let result_part1 = eval_monkeys(String::from("root"), &monkeys)?;
-        println!("result example day 21 part 1: {}", result_part1);
-        assert_eq!(result_part1, 152);
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+    type Input = HashMap<String, String>;
+    type PartOne = i64;
+    type PartTwo = i64;
 
-        let result_part2 = eval_human(&monkeys)?;
-        println!("result example day 21 part 2: {}", result_part2);
-        assert_eq!(result_part2, 301);
-        Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        let monkeys: HashMap<String, String> =
+            parse_lines(input, monkey_job)?.into_iter().collect();
+        if !monkeys.contains_key("root") || !monkeys.contains_key("humn") {
+            return Err(ParseError::end_of(input, "monkeys root and humn").into());
+        }
+        check_monkeys(input, &monkeys)?;
+        Ok(monkeys)
+    }
+    fn part_one(monkeys: &Self::Input) -> Result<i64> {
+        let result_part1 = eval_monkeys(String::from("root"), monkeys)?;
+        Ok(result_part1)
+    }
+    fn part_two(monkeys: &Self::Input) -> Result<i64> {
+        let result_part2 = eval_human(monkeys)?;
+        Ok(result_part2)
+    }
+    fn explore(monkeys: &Self::Input) -> Result<Box<dyn Explore>> {
+        Ok(Box::new(MonkeyExplorer {
+            monkeys: monkeys.clone(),
+        }))
     }
 }
+
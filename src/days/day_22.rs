@@ -1,17 +1,12 @@
 //!day_22.rs
+//!
+//! Monkey map, see [`explore_trail`] for password of [`FlatJungleMap`] and [`CubicJungle`].
 
+use crate::grid::{turn_left, turn_right, Grid, CARDINALS, EAST, SOUTH, WEST};
+use crate::parse::{number, split_once, ParseError};
+use crate::solution::Solution;
+use crate::trace;
 use anyhow::Result;
-use my_lib::{
-    my_compass::Compass,
-    my_cube_map::{CubeMap, CubeMapPoint},
-    my_map_point::MapPoint,
-    my_map_two_dim::MyMap2D,
-};
-
-// values taken from ../../assets/day_22.txt
-const X: usize = 150;
-const Y: usize = 200;
-const N: usize = 50;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 enum Tile {
@@ -27,257 +22,414 @@ impl From<char> for Tile {
             ' ' => Tile::Void,
             '.' => Tile::Free,
             '#' => Tile::Wall,
-            _ => panic!("bad input"),
+            // tiles are checked by parse_map() before
+            _ => unreachable!("bad tile"),
         }
     }
 }
 
-trait JungleTrail {
+// tiles of map, which is as wide as its longest line and has a start tile in its top row
+fn parse_map(text: &str, map: &str) -> Result<Grid<Tile>, ParseError> {
+    let tiles = Grid::parse_padded(map, Tile::Void, |c, token| match c {
+        ' ' | '.' | '#' => Ok(Tile::from(c)),
+        _ => Err(ParseError::new(text, token, "tile ' ', '.' or '#'")),
+    })?;
+    if !tiles.row(0).contains(&Tile::Free) {
+        let top_row = map.lines().next().unwrap_or(map);
+        return Err(ParseError::new(
+            text,
+            top_row,
+            "free tile '.' in top row of map",
+        ));
+    }
+    Ok(tiles)
+}
+
+// steps and following turn, true is turning right
+fn parse_trail(text: &str, trail_str: &str) -> Result<Vec<(usize, Option<bool>)>, ParseError> {
+    let mut trail: Vec<(usize, Option<bool>)> = Vec::new();
+    let mut index = 0;
+    for steps in trail_str.split(['R', 'L']) {
+        index += steps.len();
+        let dir: Option<bool> = match trail_str.as_bytes().get(index) {
+            Some(b'R') => Some(true),
+            Some(b'L') => Some(false),
+            _ => None,
+        };
+        index += 1;
+        trail.push((number(text, steps)?, dir));
+    }
+    Ok(trail)
+}
+
+/// Map of jungle with a trail to follow, either flat or folded to a cube.
+pub trait JungleTrail {
+    /// Moves to leftmost free tile of top row, facing east.
     fn set_start_pos(&mut self);
+    /// Follows trail until its end or until hitting a wall.
     fn follow_trail(&mut self);
+    /// Password of current position and orientation.
     fn calc_trail_end_value(&self) -> usize;
 }
 
-#[derive(Default)]
-struct FlatJungleMap<const X: usize, const Y: usize> {
-    map: MyMap2D<Tile, X, Y>,
+// leftmost free tile of top row
+fn start_position(map: &Grid<Tile>) -> (usize, usize) {
+    // start tile is checked by parse_map()
+    let x = map.row(0).iter().position(|t| *t == Tile::Free).unwrap();
+    (x, 0)
+}
+
+// password of position and facing, which counts clockwise from east
+fn password((x, y): (usize, usize), orientation: (isize, isize)) -> usize {
+    let facing = CARDINALS.iter().position(|o| *o == orientation).unwrap();
+    (x + 1) * 4 + (y + 1) * 1_000 + facing
+}
+
+fn turn(orientation: (isize, isize), turning: &Option<bool>) -> (isize, isize) {
+    match turning {
+        Some(true) => turn_right(orientation),
+        Some(false) => turn_left(orientation),
+        None => orientation,
+    }
+}
+
+/// Flat map of tiles, which wraps around at its edges.
+#[derive(Debug, Clone)]
+pub struct FlatJungleMap {
+    map: Grid<Tile>,
     trail: Vec<(usize, Option<bool>)>,
-    trail_position: MapPoint<X, Y>,
-    trail_orientation: Compass,
+    trail_position: (usize, usize),
+    trail_orientation: (isize, isize),
 }
 
-impl<const X: usize, const Y: usize> From<&str> for FlatJungleMap<X, Y> {
-    fn from(value: &str) -> Self {
-        let (map, trail_str) = value.split_once("\n\n").unwrap();
-        let mut trail: Vec<(usize, Option<bool>)> = Vec::new();
-        let mut index = 0;
-        for steps in trail_str.split(['R', 'L']) {
-            index += steps.len();
-            let dir: Option<bool> = if index < trail_str.len() {
-                match &trail_str[index..=index] {
-                    "R" => Some(true),
-                    "L" => Some(false),
-                    _ => panic!("bad trail input"),
-                }
-            } else {
-                None
-            };
-            index += 1;
-            let steps = steps.parse::<usize>().expect("bad steps input");
-            trail.push((steps, dir));
-        }
-        Self {
-            map: MyMap2D::from(map),
+impl TryFrom<&str> for FlatJungleMap {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (map, trail_str) = split_once(value, value, "\n\n")?;
+        let map = parse_map(value, map)?;
+        let trail = parse_trail(value, trail_str.trim_end())?;
+        Ok(Self {
+            map,
             trail,
-            ..Default::default()
+            trail_position: (0, 0),
+            trail_orientation: EAST,
+        })
+    }
+}
+
+impl FlatJungleMap {
+    // next tile, which is not void, wrapping around at edges of map
+    fn next_tile(&self, position: (usize, usize)) -> (usize, usize) {
+        let mut next = self.map.step_wrap(position, self.trail_orientation);
+        while self.map[next] == Tile::Void {
+            next = self.map.step_wrap(next, self.trail_orientation);
         }
+        next
     }
 }
 
-impl<const X: usize, const Y: usize> JungleTrail for FlatJungleMap<X, Y> {
+impl JungleTrail for FlatJungleMap {
     fn set_start_pos(&mut self) {
-        self.trail_position = self
-            .map
-            .iter_row(0)
-            .find(|(_, t)| **t == Tile::Free)
-            .unwrap()
-            .0;
-        self.trail_orientation = Compass::E;
+        self.trail_position = start_position(&self.map);
+        self.trail_orientation = EAST;
     }
     fn follow_trail(&mut self) {
         for (max_steps, turning) in self.trail.iter() {
-            #[cfg(test)]
-            eprintln!(
-                "trail_position: {}, orientation: {:?}",
-                self.trail_position, self.trail_orientation
+            trace::event!(
+                Trace,
+                "trail_position: {:?}, orientation: {:?}",
+                self.trail_position,
+                self.trail_orientation
             );
-            if let Some(new_pos) = self
-                .trail_position
-                .iter_orientation_wrap_around(self.trail_orientation, Compass::Center)
-                .filter(|p| *self.map.get(*p) != Tile::Void)
-                .skip(1)
-                .take(*max_steps)
-                .take_while(|p| *self.map.get(*p) != Tile::Wall)
-                .last()
-            {
-                self.trail_position = new_pos;
-            }
-            if let Some(turn_direction) = turning {
-                self.trail_orientation = if *turn_direction {
-                    self.trail_orientation.clockwise().clockwise()
-                } else {
-                    self.trail_orientation.counterclockwise().counterclockwise()
-                };
+            for _ in 0..*max_steps {
+                let next = self.next_tile(self.trail_position);
+                if self.map[next] == Tile::Wall {
+                    break;
+                }
+                self.trail_position = next;
             }
+            self.trail_orientation = turn(self.trail_orientation, turning);
         }
-        #[cfg(test)]
-        eprintln!(
-            "trail_position: {}, orientation: {:?}",
-            self.trail_position, self.trail_orientation
+        trace::event!(
+            Trace,
+            "trail_position: {:?}, orientation: {:?}",
+            self.trail_position,
+            self.trail_orientation
         );
     }
     fn calc_trail_end_value(&self) -> usize {
-        (self.trail_position.x() + 1) * 4
-            + (self.trail_position.y() + 1) * 1_000
-            + match self.trail_orientation {
-                Compass::E => 0,
-                Compass::S => 1,
-                Compass::W => 2,
-                Compass::N => 3,
-                _ => panic!("internal error"),
-            }
+        password(self.trail_position, self.trail_orientation)
     }
 }
 
-#[derive(Default)]
-struct CubicJungle<const N: usize> {
-    cube_map: CubeMap<Tile, N>,
-    trail: Vec<(usize, Option<bool>)>,
-    trail_position: CubeMapPoint<N>,
-    trail_orientation: Compass,
+// vector in 3D space of cube
+type Vector = [i64; 3];
+
+fn scale(v: Vector, factor: i64) -> Vector {
+    v.map(|c| c * factor)
 }
 
-impl<const N: usize> From<&str> for CubicJungle<N> {
-    fn from(value: &str) -> Self {
-        let (cube_str, trail_str) = value.split_once("\n\n").unwrap();
-        let cube_map: CubeMap<Tile, N> = CubeMap::from(cube_str);
-        // read in trail
-        let mut trail: Vec<(usize, Option<bool>)> = Vec::new();
-        let mut index = 0;
-        for steps in trail_str.split(['R', 'L']) {
-            index += steps.len();
-            let dir: Option<bool> = if index < trail_str.len() {
-                match &trail_str[index..=index] {
-                    "R" => Some(true),
-                    "L" => Some(false),
-                    _ => panic!("bad trail input"),
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn dot(a: Vector, b: Vector) -> i64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+// face of cube at its place in map, with directions of its normal and of east and south
+// of map in 3D space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Face {
+    origin: (usize, usize),
+    normal: Vector,
+    east: Vector,
+    south: Vector,
+}
+
+impl Face {
+    // face of neighbor in map, which is folded along the edge in orientation
+    fn fold(&self, origin: (usize, usize), orientation: (isize, isize)) -> Face {
+        let (normal, east, south) = match orientation {
+            EAST => (self.east, scale(self.normal, -1), self.south),
+            WEST => (scale(self.east, -1), self.normal, self.south),
+            SOUTH => (self.south, self.east, scale(self.normal, -1)),
+            // north
+            _ => (scale(self.south, -1), self.east, self.normal),
+        };
+        Face {
+            origin,
+            normal,
+            east,
+            south,
+        }
+    }
+    fn direction(&self, (dx, dy): (isize, isize)) -> Vector {
+        add(scale(self.east, dx as i64), scale(self.south, dy as i64))
+    }
+    fn orientation(&self, direction: Vector) -> (isize, isize) {
+        *CARDINALS
+            .iter()
+            .find(|o| self.direction(**o) == direction)
+            .unwrap()
+    }
+    // center of tile in 3D space with cube of side 2 * size around origin
+    fn point(&self, (x, y): (usize, usize), size: usize) -> Vector {
+        let size = size as i64;
+        let (x, y) = (
+            (x - self.origin.0) as i64 * 2 + 1 - size,
+            (y - self.origin.1) as i64 * 2 + 1 - size,
+        );
+        add(
+            scale(self.normal, size),
+            add(scale(self.east, x), scale(self.south, y)),
+        )
+    }
+    fn position(&self, point: Vector, size: usize) -> (usize, usize) {
+        let size = size as i64;
+        (
+            self.origin.0 + ((dot(point, self.east) + size - 1) / 2) as usize,
+            self.origin.1 + ((dot(point, self.south) + size - 1) / 2) as usize,
+        )
+    }
+}
+
+// faces of size x size tiles of map, which is the net of a cube
+fn fold_cube(text: &str, map: &Grid<Tile>) -> Result<(usize, Vec<Face>), ParseError> {
+    // map starts at beginning of text
+    let error = || ParseError::new(text, text, "map of six square faces folding to a cube");
+    let tiles = map.iter().filter(|(_, t)| **t != Tile::Void).count();
+    let size = (1..=map.width().max(map.height()))
+        .find(|size| 6 * size * size >= tiles)
+        .filter(|size| 6 * size * size == tiles)
+        .ok_or_else(error)?;
+    if !map.width().is_multiple_of(size) || !map.height().is_multiple_of(size) {
+        return Err(error());
+    }
+    // faces in map, each face is either void or has no void tile at all
+    let mut net = Grid::new(map.width() / size, map.height() / size, false);
+    for (position, tile) in map.iter() {
+        let face_position = (position.0 / size, position.1 / size);
+        let origin = (face_position.0 * size, face_position.1 * size);
+        let is_face = map[origin] != Tile::Void;
+        if is_face != (*tile != Tile::Void) {
+            return Err(error());
+        }
+        net[face_position] = is_face;
+    }
+    // folds faces starting with first face of top row
+    let (first, _) = net.iter().find(|(_, is_face)| **is_face).unwrap();
+    let mut faces: Vec<Face> = vec![Face {
+        origin: (first.0 * size, first.1 * size),
+        normal: [0, 0, -1],
+        east: [1, 0, 0],
+        south: [0, 1, 0],
+    }];
+    let mut folded = Grid::new(net.width(), net.height(), false);
+    folded[first] = true;
+    let mut index = 0;
+    while index < faces.len() {
+        let face = faces[index];
+        let face_position = (face.origin.0 / size, face.origin.1 / size);
+        for orientation in CARDINALS {
+            if let Some(next) = net.step(face_position, orientation) {
+                if net[next] && !folded[next] {
+                    folded[next] = true;
+                    faces.push(face.fold((next.0 * size, next.1 * size), orientation));
                 }
-            } else {
-                None
-            };
-            index += 1;
-            let steps = steps.parse::<usize>().expect("bad steps input");
-            trail.push((steps, dir));
+            }
         }
-        // return cube jungle
-        CubicJungle {
-            cube_map,
+        index += 1;
+    }
+    // six faces must not overlap
+    let normals: Vec<Vector> = faces.iter().map(|f| f.normal).collect();
+    if faces.len() != 6 || (1..6).any(|i| normals[..i].contains(&normals[i])) {
+        return Err(error());
+    }
+    Ok((size, faces))
+}
+
+/// Map folded to a cube with square faces, which are detected from the map.
+#[derive(Debug, Clone)]
+pub struct CubicJungle {
+    map: Grid<Tile>,
+    size: usize,
+    faces: Vec<Face>,
+    trail: Vec<(usize, Option<bool>)>,
+    trail_position: (usize, usize),
+    trail_orientation: (isize, isize),
+}
+
+impl TryFrom<&str> for CubicJungle {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (cube_str, trail_str) = split_once(value, value, "\n\n")?;
+        let map = parse_map(value, cube_str)?;
+        let (size, faces) = fold_cube(value, &map)?;
+        let trail = parse_trail(value, trail_str.trim_end())?;
+        Ok(CubicJungle {
+            map,
+            size,
+            faces,
             trail,
-            ..Default::default()
+            trail_position: (0, 0),
+            trail_orientation: EAST,
+        })
+    }
+}
+
+impl CubicJungle {
+    // next tile and orientation, which turns when crossing an edge of cube
+    fn next_tile(&self) -> ((usize, usize), (isize, isize)) {
+        let (position, orientation) = (self.trail_position, self.trail_orientation);
+        if let Some(next) = self.map.step(position, orientation) {
+            // neighbors in map are neighbors on cube
+            if self.map[next] != Tile::Void {
+                return (next, orientation);
+            }
         }
+        let origin = (
+            position.0 / self.size * self.size,
+            position.1 / self.size * self.size,
+        );
+        let face = self.faces.iter().find(|f| f.origin == origin).unwrap();
+        let direction = face.direction(orientation);
+        let next_face = self.faces.iter().find(|f| f.normal == direction).unwrap();
+        // one tile ahead around edge of cube
+        let point = add(
+            face.point(position, self.size),
+            add(direction, scale(face.normal, -1)),
+        );
+        (
+            next_face.position(point, self.size),
+            next_face.orientation(scale(face.normal, -1)),
+        )
     }
 }
 
-impl<const N: usize> JungleTrail for CubicJungle<N> {
+impl JungleTrail for CubicJungle {
     fn set_start_pos(&mut self) {
-        self.trail_position.0 = 0;
-        self.trail_position.1 = self
-            .cube_map
-            .get_surface(self.trail_position.0)
-            .iter_row(0)
-            .find(|(_, t)| **t == Tile::Free)
-            .unwrap()
-            .0;
-        self.trail_orientation = Compass::E;
+        self.trail_position = start_position(&self.map);
+        self.trail_orientation = EAST;
     }
     fn follow_trail(&mut self) {
         for (max_steps, turning) in self.trail.iter() {
-            #[cfg(test)]
-            eprintln!(
+            trace::event!(
+                Trace,
                 "trail_position: {:?}, orientation: {:?}",
-                self.cube_map
-                    .cube_map_point_to_flat_map_coordinates(&self.trail_position)
-                    .unwrap(),
+                self.trail_position,
                 self.trail_orientation
             );
-            if let Some((new_pos, new_orientation, _)) = self
-                .cube_map
-                .iter_orientation(self.trail_position, self.trail_orientation)
-                .skip(1)
-                .take(*max_steps)
-                .take_while(|(.., v)| **v != Tile::Wall)
-                .last()
-            {
-                self.trail_position = new_pos;
-                self.trail_orientation = new_orientation;
-            }
-            if let Some(turn_direction) = turning {
-                self.trail_orientation = if *turn_direction {
-                    self.trail_orientation.clockwise().clockwise()
-                } else {
-                    self.trail_orientation.counterclockwise().counterclockwise()
-                };
+            for _ in 0..*max_steps {
+                let (next, next_orientation) = self.next_tile();
+                if self.map[next] == Tile::Wall {
+                    break;
+                }
+                self.trail_position = next;
+                self.trail_orientation = next_orientation;
             }
+            self.trail_orientation = turn(self.trail_orientation, turning);
         }
-        #[cfg(test)]
-        eprintln!(
+        trace::event!(
+            Trace,
             "trail_position: {:?}, orientation: {:?}",
-            self.cube_map
-                .cube_map_point_to_flat_map_coordinates(&self.trail_position)
-                .unwrap(),
+            self.trail_position,
             self.trail_orientation
         );
     }
     fn calc_trail_end_value(&self) -> usize {
-        let flat_map_trail_end = self
-            .cube_map
-            .cube_map_point_to_flat_map_coordinates(&self.trail_position)
-            .unwrap();
-        (flat_map_trail_end.0 + 1) * 4
-            + (flat_map_trail_end.1 + 1) * 1_000
-            + match self.trail_orientation {
-                Compass::E => 0,
-                Compass::S => 1,
-                Compass::W => 2,
-                Compass::N => 3,
-                _ => panic!("internal error"),
-            }
+        password(self.trail_position, self.trail_orientation)
     }
 }
 
-fn explore_trail(mut jungle: impl JungleTrail) -> usize {
+/// Password at end of trail.
+pub fn explore_trail(mut jungle: impl JungleTrail) -> usize {
     jungle.set_start_pos();
     jungle.follow_trail();
     jungle.calc_trail_end_value()
 }
 
-pub fn day_22() -> Result<()> {
-    let input = include_str!("../../assets/day_22.txt");
-    let flat_jungle_trail = FlatJungleMap::<X, Y>::from(input);
-    let result_part1 = explore_trail(flat_jungle_trail);
-    println!("result day 22 part 1: {}", result_part1);
-    assert_eq!(result_part1, 13_566);
+pub struct Day22;
 
-    let cubic_jungle_trail = CubicJungle::<N>::from(input);
-    let result_part2 = explore_trail(cubic_jungle_trail);
-    println!("result day 22 part 2: {}", result_part2);
-    assert_eq!(result_part2, 11_451);
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+    type Input = (FlatJungleMap, CubicJungle);
+    type PartOne = usize;
+    type PartTwo = usize;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok((
+            FlatJungleMap::try_from(input)?,
+            CubicJungle::try_from(input)?,
+        ))
+    }
+    fn part_one((flat_jungle_trail, _): &Self::Input) -> Result<usize> {
+        let result_part1 = explore_trail(flat_jungle_trail.clone());
+        Ok(result_part1)
+    }
+    fn part_two((_, cubic_jungle_trail): &Self::Input) -> Result<usize> {
+        let result_part2 = explore_trail(cubic_jungle_trail.clone());
+        Ok(result_part2)
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    // values taken from ../../assets/day_22_example.txt
-    const XD: usize = 16;
-    const YD: usize = 12;
-    const ND: usize = 4;
 
     #[test]
-    fn test_example() -> Result<()> {
-        let input = include_str!("../../assets/day_22_example.txt");
-        let flat_ungle_trail = FlatJungleMap::<XD, YD>::from(input);
-        let result_part1 = explore_trail(flat_ungle_trail);
-        println!("result example day 22 part 1: {}", result_part1);
-        assert_eq!(result_part1, 6_032);
-
-        let cubic_jungle_trail = CubicJungle::<ND>::from(input);
-        let result_part2 = explore_trail(cubic_jungle_trail);
-        println!("result exa,ple day 22 part 2: {}", result_part2);
-        assert_eq!(result_part2, 5_031);
-
-        Ok(())
+    fn test_parse_errors() {
+        // six faces in a row do not fold to a cube
+        let err = CubicJungle::try_from("......\n\n10R5").unwrap_err();
+        assert_eq!(err.expected, "map of six square faces folding to a cube");
+        assert!(CubicJungle::try_from("..\n.\n\n10").is_err());
+        let err = FlatJungleMap::try_from("  ##\n ...\n\n10").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1 column 1: expected free tile '.' in top row of map, found '  ##'"
+        );
     }
 }
@@ -1,39 +1,52 @@
 //!day_23.rs
+//!
+//! Unstable diffusion, see [`ElveSwarm`] for spreading of elves.
 
-use anyhow::Result;
+use crate::parse::{grid_cells, ParseError};
+use crate::repl::{argument, Command, Explore};
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::visualize::{FrameSink, Render};
+use anyhow::{anyhow, Result};
 use my_lib::{my_compass::Compass, my_geometry::my_point::Point};
 
 use std::collections::{HashMap, HashSet};
 
-struct ElveSwarm {
+/// Elves spreading out on a grid, one movement phase per round.
+#[derive(Clone)]
+pub struct ElveSwarm {
     elves: HashSet<Point>,
     top_left: Point,
     bottom_right: Point,
     directions: [Compass; 4],
 }
 
-impl From<&str> for ElveSwarm {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for ElveSwarm {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let top_left = Point::default();
         let mut bottom_right = Point::default();
         let mut elves: HashSet<Point> = HashSet::new();
-        for (y, line) in value.lines().enumerate().map(|(y, l)| (y as i64, l)) {
-            for x in line
-                .chars()
-                .enumerate()
-                .filter(|(_, e)| *e == '#')
-                .map(|(x, _)| x as i64)
-            {
-                bottom_right = (x, y).into();
-                elves.insert(bottom_right);
+        for ((x, y), c, token) in grid_cells(value) {
+            match c {
+                '#' => {
+                    bottom_right = (x as i64, y as i64).into();
+                    elves.insert(bottom_right);
+                }
+                '.' => (),
+                _ => return Err(ParseError::new(value, token, "elve '#' or ground '.'")),
             }
         }
-        Self {
+        if elves.is_empty() {
+            return Err(ParseError::end_of(value, "elve '#'"));
+        }
+        Ok(Self {
             elves,
             top_left,
             bottom_right,
             directions: [Compass::N, Compass::S, Compass::W, Compass::E],
-        }
+        })
     }
 }
 
@@ -96,60 +109,126 @@ impl ElveSwarm {
         let diagonale = self.bottom_right.subtract(self.top_left);
         ((diagonale.x + 1) * (diagonale.y + 1)) as usize - self.elves.len()
     }
-}
-
-pub fn day_23() -> Result<()> {
-    let input = include_str!("../../assets/day_23.txt");
-    let mut elve_swarm = ElveSwarm::from(input);
-    let num_rounds: usize = 10;
-    for _ in 0..num_rounds {
-        elve_swarm.one_movement_phase();
+    /// Empty tiles of smallest rectangle containing all elves after num_rounds rounds.
+    pub fn empty_tiles_after(&self, num_rounds: usize) -> usize {
+        let mut elve_swarm = self.clone();
+        for _ in 0..num_rounds {
+            elve_swarm.one_movement_phase();
+        }
+        elve_swarm.count_empty_tiles()
     }
-    let result_part1 = elve_swarm.count_empty_tiles();
-    println!("result day 23 part 1: {}", result_part1);
-    assert_eq!(result_part1, 4_034);
-    
-    let mut num_rounds = num_rounds;
-    while elve_swarm.one_movement_phase() > 0 {
-        num_rounds += 1;
+    /// First round, in which no elve moves.
+    pub fn first_round_without_movement(&self) -> usize {
+        let mut elve_swarm = self.clone();
+        let mut num_rounds: usize = 1;
+        while elve_swarm.one_movement_phase() > 0 {
+            num_rounds += 1;
+        }
+        num_rounds
     }
-    num_rounds += 1;
-    println!("result day 23 part 1: {}", num_rounds);
-    assert_eq!(num_rounds, 960);
+}
 
-    Ok(())
+impl Render for ElveSwarm {
+    fn render(&self) -> String {
+        let rows: Vec<String> = (self.top_left.y..=self.bottom_right.y)
+            .map(|y| {
+                (self.top_left.x..=self.bottom_right.x)
+                    .map(|x| {
+                        if self.elves.contains(&Point::new(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        rows.join("\n")
+    }
 }
 
-#[cfg(test)]
-mod tests {
+// elves of REPL, which move round by round
+struct ElveExplorer {
+    elve_swarm: ElveSwarm,
+    num_rounds: usize,
+}
 
-    use super::*;
+const ELVE_COMMANDS: &[Command] = &[
+    ("step [N]", "moves elves N rounds, default 1"),
+    ("print", "draws smallest rectangle containing all elves"),
+    ("empty", "counts empty tiles of this rectangle"),
+    ("round", "shows finished rounds and next direction"),
+];
 
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "....#..\n\
-                           ..###.#\n\
-                           #...#.#\n\
-                           .#...##\n\
-                           #.###..\n\
-                           ##.#.##\n\
-                           .#..#..";
-        let mut elve_swarm = ElveSwarm::from(input);
-        let mut num_rounds: usize = 10;
-        for _ in 0..num_rounds {
-            elve_swarm.one_movement_phase();
+impl Explore for ElveExplorer {
+    fn commands(&self) -> &'static [Command] {
+        ELVE_COMMANDS
+    }
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String> {
+        match command {
+            "step" => {
+                let steps: usize = argument(args, 0, Some(1))?;
+                let mut moved: Vec<String> = Vec::new();
+                for _ in 0..steps {
+                    let num_moves = self.elve_swarm.one_movement_phase();
+                    self.num_rounds += 1;
+                    moved.push(format!(
+                        "round {}: {} elves moved",
+                        self.num_rounds, num_moves
+                    ));
+                    if num_moves == 0 {
+                        break;
+                    }
+                }
+                Ok(moved.join("\n"))
+            }
+            "print" => Ok(self.elve_swarm.render()),
+            "empty" => Ok(self.elve_swarm.count_empty_tiles().to_string()),
+            "round" => Ok(format!(
+                "{} rounds finished, next first direction {:?}",
+                self.num_rounds, self.elve_swarm.directions[0]
+            )),
+            _ => Err(anyhow!("unknown command '{}'", command)),
         }
-        let result_part1 = elve_swarm.count_empty_tiles();
-        println!("result example day 23 part 1: {}", result_part1);
-        assert_eq!(result_part1, 110);
+    }
+}
 
-        while elve_swarm.one_movement_phase() > 0 {
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u8 = 23;
+    type Input = ElveSwarm;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(ElveSwarm::try_from(input)?)
+    }
+    fn part_one(elve_swarm: &Self::Input) -> Result<usize> {
+        let result_part1 = elve_swarm.empty_tiles_after(10);
+        Ok(result_part1)
+    }
+    fn part_two(elve_swarm: &Self::Input) -> Result<usize> {
+        let result_part2 = elve_swarm.first_round_without_movement();
+        Ok(result_part2)
+    }
+    fn explore(elve_swarm: &Self::Input) -> Result<Box<dyn Explore>> {
+        Ok(Box::new(ElveExplorer {
+            elve_swarm: elve_swarm.clone(),
+            num_rounds: 0,
+        }))
+    }
+    fn visualize(elve_swarm: &Self::Input, part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        // one frame per round, part 1 stops after 10 rounds, part 2 if no elve moves
+        let mut elve_swarm = elve_swarm.clone();
+        let mut num_rounds: usize = 0;
+        while sink.frame(&elve_swarm)? && (part == Part::Two || num_rounds < 10) {
             num_rounds += 1;
+            if elve_swarm.one_movement_phase() == 0 {
+                break;
+            }
         }
-        num_rounds += 1;
-        println!("result day 23 part 1: {}", num_rounds);
-        assert_eq!(num_rounds, 20);
-
         Ok(())
     }
 }
+
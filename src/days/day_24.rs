@@ -1,13 +1,22 @@
 //!day_24.rs
+//!
+//! Blizzard basin, see [`BlizzardVale`] for shortest path through blizzards.
 
+use crate::budget;
+use crate::parse::ParseError;
+use crate::progress;
+use crate::selection::Part;
+use crate::solution::Solution;
+use crate::visualize::{FrameSink, Render};
 use anyhow::Result;
 use my_lib::{my_compass::Compass, my_geometry::my_point::Point};
 use num::integer::lcm;
 use std::cmp::Ordering;
 use std::collections::{BTreeSet, HashSet};
 
+/// Vale with blizzards moving in straight lines and wrapping around at its walls.
 #[derive(Default)]
-struct BlizzardVale {
+pub struct BlizzardVale {
     north_blizzards: Vec<Point>,
     east_blizzards: Vec<Point>,
     south_blizzards: Vec<Point>,
@@ -17,8 +26,10 @@ struct BlizzardVale {
     lcm_rc: i64,
 }
 
-impl From<&str> for BlizzardVale {
-    fn from(value: &str) -> Self {
+impl TryFrom<&str> for BlizzardVale {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
         let mut blizzard_vale = BlizzardVale::default();
         for (y, line) in value
             .lines()
@@ -26,24 +37,41 @@ impl From<&str> for BlizzardVale {
             .enumerate()
             .map(|(y, l)| (y as i64, l))
         {
-            for (x, c) in line.chars().skip(1).enumerate().map(|(x, c)| (x as i64, c)) {
+            for (x, (i, c)) in line
+                .char_indices()
+                .skip(1)
+                .enumerate()
+                .map(|(x, c)| (x as i64, c))
+            {
                 match c {
                     '^' => blizzard_vale.north_blizzards.push((x, y).into()),
                     '>' => blizzard_vale.east_blizzards.push((x, y).into()),
                     'v' => blizzard_vale.south_blizzards.push((x, y).into()),
                     '<' => blizzard_vale.west_blizzards.push((x, y).into()),
-                    _ => (),
+                    '#' | '.' => (),
+                    _ => {
+                        let token = &line[i..i + c.len_utf8()];
+                        return Err(ParseError::new(value, token, "wall, ground or blizzard"));
+                    }
                 }
                 blizzard_vale.num_columns = x;
             }
             blizzard_vale.num_rows = y;
         }
+        if blizzard_vale.num_columns < 1 || blizzard_vale.num_rows < 1 {
+            return Err(ParseError::end_of(value, "vale with walls around ground"));
+        }
         blizzard_vale.lcm_rc = lcm(blizzard_vale.num_columns, blizzard_vale.num_rows);
-        blizzard_vale
+        Ok(blizzard_vale)
     }
 }
 
 impl BlizzardVale {
+    /// Minutes to walk from start to end and back again, trips being 1 or 3 in the puzzle.
+    /// Fails if the time budget of the running thread is exceeded.
+    pub fn shortest_expedition(&self, trips: i64) -> Result<i64> {
+        ExpeditionState::new().shortest_path_expedition(self, trips)
+    }
     fn is_start_pos(&self, state: ExpeditionState) -> bool {
         (state.x, state.y) == (0, -1)
     }
@@ -86,6 +114,58 @@ impl BlizzardVale {
     }
 }
 
+// vale at given minute with all positions, which the expedition may have reached
+struct BlizzardFrame<'a> {
+    blizzard_vale: &'a BlizzardVale,
+    minutes: i64,
+    expedition: HashSet<(i64, i64)>,
+}
+
+impl Render for BlizzardFrame<'_> {
+    fn render(&self) -> String {
+        let vale = self.blizzard_vale;
+        let mut ground = vec![vec![0_u8; vale.num_columns as usize]; vale.num_rows as usize];
+        let mut arrows = vec![vec!['.'; vale.num_columns as usize]; vale.num_rows as usize];
+        for (blizzards, dir, arrow) in [
+            (&vale.north_blizzards, Compass::N, '^'),
+            (&vale.east_blizzards, Compass::E, '>'),
+            (&vale.south_blizzards, Compass::S, 'v'),
+            (&vale.west_blizzards, Compass::W, '<'),
+        ] {
+            for blizzard in blizzards.iter() {
+                let pos = blizzard.add(Point::from(dir).scale(self.minutes));
+                let (x, y) = (
+                    pos.x.rem_euclid(vale.num_columns) as usize,
+                    pos.y.rem_euclid(vale.num_rows) as usize,
+                );
+                ground[y][x] += 1;
+                arrows[y][x] = arrow;
+            }
+        }
+        let mut rows: Vec<String> = Vec::new();
+        for y in -1..=vale.num_rows {
+            let row: String = (-1..=vale.num_columns)
+                .map(|x| {
+                    if self.expedition.contains(&(x, y)) {
+                        return 'E';
+                    }
+                    let is_opening = (x, y) == (0, -1) || (x, y) == (vale.num_columns - 1, vale.num_rows);
+                    if x < 0 || y < 0 || x == vale.num_columns || y == vale.num_rows {
+                        return if is_opening { '.' } else { '#' };
+                    }
+                    match ground[y as usize][x as usize] {
+                        0 | 1 => arrows[y as usize][x as usize],
+                        n => char::from_digit(n.min(9) as u32, 10).unwrap_or('9'),
+                    }
+                })
+                .collect();
+            rows.push(row);
+        }
+        rows.push(format!("minute {}", self.minutes));
+        rows.join("\n")
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct ExpeditionState {
     minutes: i64,
@@ -164,7 +244,12 @@ impl ExpeditionState {
             phase: self.phase,
         }
     }
-    fn shortest_path_expedition(&mut self, blizzard_vale: &BlizzardVale, num_phases: i64) -> i64 {
+    fn shortest_path_expedition(
+        &mut self,
+        blizzard_vale: &BlizzardVale,
+        num_phases: i64,
+    ) -> Result<i64> {
+        let _search = progress::start("day 24 expedition");
         let mut queue: BTreeSet<Self> = BTreeSet::new();
         queue.insert(*self);
         let mut seen: HashSet<Self> = HashSet::new();
@@ -173,6 +258,9 @@ impl ExpeditionState {
             if current_state.phase < current_phase {
                 continue;
             }
+            budget::check()?;
+            progress::state();
+            progress::cache_size(seen.len());
             current_state.increment_minutes();
             for new_state in Compass::center_and_cardinals()
                 .into_iter()
@@ -185,7 +273,7 @@ impl ExpeditionState {
                     let phase_end_state = new_state.increment_phase();
                     if phase_end_state.phase == num_phases {
                         *self = phase_end_state;
-                        return new_state.minutes;
+                        return Ok(new_state.minutes);
                     }
                     seen.insert(phase_end_state);
                     queue.insert(phase_end_state);
@@ -209,49 +297,76 @@ impl ExpeditionState {
             }
         }
         // this should never happen
-        -1
+        Ok(-1)
     }
 }
 
 // So inspired (again) by HyperNeutrino
 // https://www.youtube.com/watch?v=R_QWG-cPp_k&list=PLnNm9syGLD3yf-YW-a5XNh1CJN07xr0Kz&index=24
 
-pub fn day_24() -> Result<()> {
-    let input = include_str!("../../assets/day_24.txt");
-    let blizzard_vale = BlizzardVale::from(input);
-    let mut expedition = ExpeditionState::new();
-    let result_part1 = expedition.shortest_path_expedition(&blizzard_vale, 1);
-    println!("result day 24 part 1: {}", result_part1);
-    assert_eq!(result_part1, 274);
-    let result_part2 = expedition.shortest_path_expedition(&blizzard_vale, 3);
-    println!("result day 24 part 2: {}", result_part2);
-    assert_eq!(result_part2, 839);
+pub struct Day24;
 
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "#.######\n\
-                           #>>.<^<#\n\
-                           #.<..<<#\n\
-                           #>v.><>#\n\
-                           #<^v^^>#\n\
-                           ######.#";
-        let blizzard_vale = BlizzardVale::from(input);
-        let mut expedition = ExpeditionState::new();
-        let result_part1 = expedition.shortest_path_expedition(&blizzard_vale, 1);
-        println!("result example day 24 part 1: {}", result_part1);
-        assert_eq!(result_part1, 18);
-        let result_part2 = expedition.shortest_path_expedition(&blizzard_vale, 3);
-        println!("result example day 24 part 2: {}", result_part2);
-        assert_eq!(result_part2, 54);
+impl Solution for Day24 {
+    const DAY: u8 = 24;
+    type Input = BlizzardVale;
+    type PartOne = i64;
+    type PartTwo = i64;
 
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(BlizzardVale::try_from(input)?)
+    }
+    fn part_one(blizzard_vale: &Self::Input) -> Result<i64> {
+        let result_part1 = blizzard_vale.shortest_expedition(1)?;
+        Ok(result_part1)
+    }
+    fn part_two(blizzard_vale: &Self::Input) -> Result<i64> {
+        // there and back again, and there again
+        let result_part2 = blizzard_vale.shortest_expedition(3)?;
+        Ok(result_part2)
+    }
+    fn visualize(blizzard_vale: &Self::Input, part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        // one frame per minute showing all reachable positions of expedition
+        let num_phases = if part == Part::One { 1 } else { 3 };
+        let start = (0, -1);
+        let end = (blizzard_vale.num_columns - 1, blizzard_vale.num_rows);
+        let mut frame = BlizzardFrame {
+            blizzard_vale,
+            minutes: 0,
+            expedition: HashSet::from([start]),
+        };
+        let mut phase = 0;
+        while sink.frame(&frame)? {
+            // expedition waits for ever, if no position can be reached
+            if phase == num_phases || frame.expedition.is_empty() {
+                break;
+            }
+            frame.minutes += 1;
+            let goal = if phase & 1 == 0 { end } else { start };
+            let mut expedition: HashSet<(i64, i64)> = HashSet::new();
+            for (x, y) in frame.expedition.iter() {
+                for c in Compass::center_and_cardinals() {
+                    let pos = Point::new(*x, *y).add(Point::from(c));
+                    let state = ExpeditionState {
+                        minutes: frame.minutes,
+                        x: pos.x,
+                        y: pos.y,
+                        phase,
+                    };
+                    if !blizzard_vale.is_out_of_vale(state)
+                        && !blizzard_vale.check_pos_for_blizzards(state)
+                    {
+                        expedition.insert((pos.x, pos.y));
+                    }
+                }
+            }
+            // continue from goal only, if goal of phase is reached
+            if expedition.contains(&goal) {
+                phase += 1;
+                expedition = HashSet::from([goal]);
+            }
+            frame.expedition = expedition;
+        }
         Ok(())
     }
 }
+
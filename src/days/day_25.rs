@@ -1,10 +1,17 @@
 //!day_25.rs
+//!
+//! Full of hot air, see [`Base5Num`] for conversion of SNAFU numbers.
 
 use std::fmt::Display;
 
-use anyhow::Result;
+use crate::answer::Answer;
+use crate::parse::{parse_lines, read_lines, ParseError};
+use crate::selection::Part;
+use crate::solution::Solution;
+use anyhow::{anyhow, Result};
+use std::io::BufRead;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct Base5Place {
     // 5^value: 0 -> 1, 1 -> 5, 2 -> 25, and so on
     place: u32,
@@ -87,27 +94,47 @@ impl Base5Place {
     }
 }
 
-struct Base5Num {
+/// SNAFU number with digits =, -, 0, 1 and 2 of values -2 to 2 for powers of five.
+///
+/// Parse with `Base5Num::try_from("1=-0-2")`, print with Display and convert to and from decimal
+/// with `i64::from(&num)` and `Base5Num::from(1747)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base5Num {
     // index in vec must be equal to place of Base5Place
     num: Vec<Base5Place>,
 }
 
-impl From<&str> for Base5Num {
-    fn from(value: &str) -> Self {
+// larger numbers do not fit into i64
+const MAX_PLACES: usize = 27;
+
+impl TryFrom<&str> for Base5Num {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if value.is_empty() || value.chars().count() > MAX_PLACES {
+            return Err(ParseError::new(
+                value,
+                value,
+                format!("SNAFU number with 1 to {} digits", MAX_PLACES),
+            ));
+        }
         let mut num: Vec<Base5Place> = Vec::with_capacity(value.chars().count());
         // read chars from right to left!
-        for (place, val) in value.chars().rev().enumerate() {
+        for (place, (i, val)) in value.char_indices().rev().enumerate() {
             let base5_value: i64 = match val {
                 '=' => -2,
                 '-' => -1,
                 '0' => 0,
                 '1' => 1,
                 '2' => 2,
-                _ => panic!("bad input"),
+                _ => {
+                    let token = &value[i..i + val.len_utf8()];
+                    return Err(ParseError::new(value, token, "SNAFU digit =, -, 0, 1 or 2"));
+                }
             };
             num.push(Base5Place::new(place as u32, base5_value));
         }
-        Self { num }
+        Ok(Self { num })
     }
 }
 
@@ -121,6 +148,18 @@ impl Display for Base5Num {
     }
 }
 
+impl From<i64> for Base5Num {
+    fn from(value: i64) -> Self {
+        Self::from_dezimal(value)
+    }
+}
+
+impl From<&Base5Num> for i64 {
+    fn from(value: &Base5Num) -> Self {
+        value.to_dezimal()
+    }
+}
+
 impl Base5Num {
     fn to_dezimal(&self) -> i64 {
         let mut dezimal = 0;
@@ -145,13 +184,36 @@ impl Base5Num {
     }
 }
 
-pub fn day_25() -> Result<()> {
-    let input = include_str!("../../assets/day_25.txt");
-    let result_part1: i64 = input.lines().map(|l| Base5Num::from(l).to_dezimal()).sum();
-    let result_part1 = format!("{}", Base5Num::from_dezimal(result_part1));
-    println!("result day 25 part 1: {}", result_part1);
-    assert_eq!(result_part1, String::from("20=02=120-=-2110-0=1"));
-    Ok(())
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: &'static [Part] = &[Part::One];
+    const STREAMS: bool = true;
+    type Input = Vec<Base5Num>;
+    type PartOne = String;
+    type PartTwo = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(input, Base5Num::try_from)?)
+    }
+    fn part_one(base5_nums: &Self::Input) -> Result<String> {
+        let result_part1: i64 = base5_nums.iter().map(|n| n.to_dezimal()).sum();
+        let result_part1 = format!("{}", Base5Num::from_dezimal(result_part1));
+        Ok(result_part1)
+    }
+    fn part_two(_base5_nums: &Self::Input) -> Result<String> {
+        Err(anyhow!("day 25 has only one part"))
+    }
+    fn solve_reader(reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        let mut result_part1: i64 = 0;
+        read_lines(reader, |line| {
+            result_part1 += Base5Num::try_from(line)?.to_dezimal();
+            Ok(())
+        })?;
+        let result_part1 = format!("{}", Base5Num::from_dezimal(result_part1));
+        Ok(vec![Ok(result_part1.into())])
+    }
 }
 
 #[cfg(test)]
@@ -202,34 +264,10 @@ mod tests {
         for line in test_input.lines().map(|l| l.trim()) {
             let mut lsplit = line.split_whitespace();
             let dezimal = lsplit.next().unwrap().parse::<i64>()?;
-            let base5_num = Base5Num::from(lsplit.next().unwrap());
+            let base5_num = Base5Num::try_from(lsplit.next().unwrap())?;
             assert_eq!(base5_num.to_dezimal(), dezimal);
         }
 
         Ok(())
     }
-
-    #[test]
-    fn test_example() -> Result<()> {
-        let input = "1=-0-2\n\
-                           12111\n\
-                           2=0=\n\
-                           21\n\
-                           2=01\n\
-                           111\n\
-                           20012\n\
-                           112\n\
-                           1=-1=\n\
-                           1-12\n\
-                           12\n\
-                           1=\n\
-                           122";
-        let result_part1: i64 = input.lines().map(|l| Base5Num::from(l).to_dezimal()).sum();
-        assert_eq!(result_part1, 4_890);
-        let result_part1 = format!("{}", Base5Num::from_dezimal(result_part1));
-        println!("result example day 25 part 1: {}", result_part1);
-        assert_eq!(result_part1, String::from("2=-1=0"));
-
-        Ok(())
-    }
 }
@@ -1,28 +1,49 @@
 //!mod.rs
 //pub mod days
 
-pub mod day_01;
-pub mod day_02;
-pub mod day_03;
-pub mod day_04;
-pub mod day_05;
-pub mod day_06;
-pub mod day_07;
-pub mod day_08;
-pub mod day_09;
-pub mod day_10;
-pub mod day_11;
-pub mod day_12;
-pub mod day_13;
-pub mod day_14;
-pub mod day_15;
-pub mod day_16;
-pub mod day_17;
-pub mod day_18;
-pub mod day_19;
-pub mod day_20;
-pub mod day_21;
-pub mod day_22;
-pub mod day_23;
-pub mod day_24;
-pub mod day_25;
+use crate::solution::{DynSolution, Registered};
+
+// adding a day: implement Solution in day_NN.rs and add one line here
+macro_rules! register_days {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        static REGISTRY: &[&dyn DynSolution] = &[$(&Registered::<$module::$solution>::new()),*];
+    };
+}
+
+register_days! {
+    day_01::Day01,
+    day_02::Day02,
+    day_03::Day03,
+    day_04::Day04,
+    day_05::Day05,
+    day_06::Day06,
+    day_07::Day07,
+    day_08::Day08,
+    day_09::Day09,
+    day_10::Day10,
+    day_11::Day11,
+    day_12::Day12,
+    day_13::Day13,
+    day_14::Day14,
+    day_15::Day15,
+    day_16::Day16,
+    day_17::Day17,
+    day_18::Day18,
+    day_19::Day19,
+    day_20::Day20,
+    day_21::Day21,
+    day_22::Day22,
+    day_23::Day23,
+    day_24::Day24,
+    day_25::Day25,
+}
+
+pub fn registry() -> &'static [&'static dyn DynSolution] {
+    REGISTRY
+}
+
+pub fn get(day: u8) -> Option<&'static dyn DynSolution> {
+    REGISTRY.iter().find(|s| s.day() == day).copied()
+}
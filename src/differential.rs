@@ -0,0 +1,158 @@
+//!differential.rs
+
+use crate::answer::Answer;
+use crate::generate;
+use crate::selection::Part;
+use crate::solution::{catch_panic, DynSolution, Params};
+use anyhow::{Context, Result};
+use std::fmt::Display;
+
+pub const DEFAULT_RUNS: u64 = 100;
+// small inputs keep reference solvers fast and mismatches readable
+pub const DEFAULT_SIZE: usize = 10;
+
+// first generated input, on which optimized and reference solver of a part disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: Part,
+    pub seed: u64,
+    pub reference: String,
+    pub optimized: String,
+    // generated input with all lines, or chars of single line inputs, removed,
+    // which are not needed to show the mismatch
+    pub input: String,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "day {:02} part {}: mismatch with seed {}, reference {}, optimized {}",
+            self.day, self.part, self.seed, self.reference, self.optimized
+        )?;
+        writeln!(f, "minimized input:")?;
+        write!(f, "{}", self.input)
+    }
+}
+
+// answer or error of a solver, equal outcomes match
+fn outcome(result: Result<Answer>) -> String {
+    match result {
+        Ok(answer) => answer.to_string(),
+        Err(err) => format!("error: {:#}", err),
+    }
+}
+
+// outcomes of reference and optimized solver, if they differ;
+// inputs, which fail to parse or fail the reference, are not valid
+fn compare(
+    solution: &dyn DynSolution,
+    part: Part,
+    input: &str,
+    params: &Params,
+) -> Result<Option<(String, String)>> {
+    let parsed = catch_panic(|| solution.parse_with_params(input, params))?;
+    let reference = catch_panic(|| solution.reference(parsed.as_ref(), part))?.to_string();
+    let optimized = outcome(catch_panic(|| solution.solve(parsed.as_ref(), part)));
+    Ok((reference != optimized).then_some((reference, optimized)))
+}
+
+// greedy removal of chunks of lines, or chars of single line inputs, as long as mismatch remains
+pub fn minimize(solution: &dyn DynSolution, part: Part, input: &str, params: &Params) -> String {
+    let (mut units, separator): (Vec<&str>, &str) = if input.lines().count() > 1 {
+        (input.lines().collect(), "\n")
+    } else {
+        let chars = input
+            .char_indices()
+            .map(|(i, c)| &input[i..i + c.len_utf8()]);
+        (chars.collect(), "")
+    };
+    let mut chunk = units.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < units.len() {
+            let end = (start + chunk).min(units.len());
+            let candidate: Vec<&str> = [&units[..start], &units[end..]].concat();
+            if !candidate.is_empty()
+                && matches!(
+                    compare(solution, part, &candidate.join(separator), params),
+                    Ok(Some(_))
+                )
+            {
+                units = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    units.join(separator)
+}
+
+// runs reference and optimized solver of part on inputs of seeds first_seed..first_seed + runs
+pub fn check_part(
+    solution: &dyn DynSolution,
+    part: Part,
+    first_seed: u64,
+    runs: u64,
+    size: usize,
+) -> Result<Option<Mismatch>> {
+    for seed in first_seed..first_seed.saturating_add(runs) {
+        let (input, params) = generate::generate_with_params(solution.day(), seed, size)?;
+        let compared = compare(solution, part, &input, &params).with_context(|| {
+            format!(
+                "generated input of day {:02} with seed {} is not valid",
+                solution.day(),
+                seed
+            )
+        })?;
+        if let Some((reference, optimized)) = compared {
+            return Ok(Some(Mismatch {
+                day: solution.day(),
+                part,
+                seed,
+                reference,
+                optimized,
+                input: minimize(solution, part, &input, &params),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days;
+
+    #[test]
+    fn test_differential() -> Result<()> {
+        for day in [17, 19, 20] {
+            let solution = days::get(day).unwrap();
+            for part in solution.reference_parts().iter() {
+                assert_eq!(check_part(solution, *part, 0, 3, 4)?, None);
+            }
+        }
+        // part 1 of day 16 ignores valves with flow rate below minimum_valve_value
+        let day_16 = days::get(16).unwrap();
+        let mismatch = check_part(day_16, Part::One, 2030, 1, 10)?.unwrap();
+        assert_eq!(mismatch.seed, 2030);
+        assert_eq!(
+            (mismatch.reference.as_str(), mismatch.optimized.as_str()),
+            ("1190", "1173")
+        );
+        assert!(compare(day_16, Part::One, &mismatch.input, &Params::new())?.is_some());
+        assert!(mismatch
+            .to_string()
+            .starts_with("day 16 part 1: mismatch with seed 2030"));
+
+        let day_20 = days::get(20).unwrap();
+        assert!(day_20.reference_parts().contains(&Part::One));
+        assert!(day_20
+            .reference(day_20.parse("1\n0")?.as_ref(), Part::Two)
+            .is_err());
+        Ok(())
+    }
+}
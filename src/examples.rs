@@ -0,0 +1,213 @@
+//!examples.rs
+
+use crate::answer::Answer;
+use crate::days;
+use crate::manifest::{parse_answer, parse_part_key};
+use crate::selection::{Part, FIRST_DAY, LAST_DAY};
+use crate::solution::{catch_panic, Params};
+use anyhow::{anyhow, Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// expected answers of all example inputs, placed next to them
+pub const FILE_NAME: &str = "examples.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExampleSpec {
+    pub answers: BTreeMap<Part, Answer>,
+    pub params: Params,
+    // reason, why example cannot be solved
+    pub skip: Option<String>,
+}
+
+impl TryFrom<(&str, &toml::Value)> for ExampleSpec {
+    type Error = anyhow::Error;
+
+    fn try_from((name, value): (&str, &toml::Value)) -> Result<Self> {
+        let table = value
+            .as_table()
+            .ok_or(anyhow!("'{}' must be a table of example answers", name))?;
+        let mut spec = ExampleSpec::default();
+        for (key, value) in table.iter() {
+            match key.as_str() {
+                "params" => {
+                    let params = value
+                        .as_table()
+                        .ok_or(anyhow!("params of '{}' must be a table", name))?;
+                    for (param, value) in params.iter() {
+                        let value = value.as_integer().ok_or(anyhow!(
+                            "param {}.{} must be an integer",
+                            name,
+                            param
+                        ))?;
+                        spec.params.insert(param, value);
+                    }
+                }
+                "skip" => {
+                    let reason = value
+                        .as_str()
+                        .ok_or(anyhow!("skip of '{}' must be a string", name))?;
+                    spec.skip = Some(reason.to_string());
+                }
+                part_key => {
+                    let part = parse_part_key(part_key)?;
+                    spec.answers
+                        .insert(part, parse_answer(value, name, part_key)?);
+                }
+            }
+        }
+        if spec.answers.is_empty() {
+            return Err(anyhow!("example '{}' has no expected answers", name));
+        }
+        Ok(spec)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Example {
+    // file name without extension, e.g. day_09_example_2
+    pub name: String,
+    pub day: u8,
+    pub input: String,
+    pub spec: ExampleSpec,
+}
+
+// day of example file names day_NN_example.txt and day_NN_example_M.txt
+fn example_day(file_name: &str) -> Option<u8> {
+    let name = file_name.strip_suffix(".txt")?;
+    let (day, suffix) = name.strip_prefix("day_")?.split_once("_example")?;
+    if !(suffix.is_empty() || suffix.starts_with('_')) {
+        return None;
+    }
+    day.parse::<u8>()
+        .ok()
+        .filter(|day| (FIRST_DAY..=LAST_DAY).contains(day))
+}
+
+// discovers all example files of dir, each of them needs expected answers in examples.toml
+pub fn load(dir: &Path) -> Result<Vec<Example>> {
+    let manifest_path = dir.join(FILE_NAME);
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let mut specs = manifest
+        .parse::<toml::Table>()
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+    let mut examples = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(day) = example_day(file_name) else {
+            continue;
+        };
+        let name = file_name.trim_end_matches(".txt").to_string();
+        let spec = specs.remove(&name).ok_or(anyhow!(
+            "no expected answers of {} in {}",
+            file_name,
+            FILE_NAME
+        ))?;
+        let input = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        examples.push(Example {
+            spec: ExampleSpec::try_from((name.as_str(), &spec))?,
+            name,
+            day,
+            input,
+        });
+    }
+    if let Some(name) = specs.keys().next() {
+        return Err(anyhow!("example file of '{}' not found", name));
+    }
+    examples.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(examples)
+}
+
+impl Example {
+    // solves all parts with expected answers, fails on the first wrong or missing answer
+    pub fn check(&self) -> Result<()> {
+        let solution = days::get(self.day).ok_or(anyhow!("day {} not found", self.day))?;
+        let parsed = catch_panic(|| solution.parse_with_params(&self.input, &self.spec.params))
+            .with_context(|| format!("failed to parse {}", self.name))?;
+        for (part, expected) in self.spec.answers.iter() {
+            let answer = catch_panic(|| solution.solve(parsed.as_ref(), *part))
+                .with_context(|| format!("failed to solve {} part {}", self.name, part))?;
+            // compare textual representation like answers of --verify
+            if answer.to_string() != expected.to_string() {
+                return Err(anyhow!(
+                    "{} part {}: expected {}, got {}",
+                    self.name,
+                    part,
+                    expected,
+                    answer
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::pool;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_example_spec() -> Result<()> {
+        let spec: toml::Table = "[day_15_example]\npart_2 = 56\nparams = { row = 10 }".parse()?;
+        let spec = ExampleSpec::try_from(("day_15_example", &spec["day_15_example"]))?;
+        assert_eq!(spec.answers[&Part::Two], Answer::from(56));
+        assert_eq!(spec.params.get("row"), Some(10));
+        assert_eq!(spec.skip, None);
+
+        for bad_spec in [
+            "skip = 'no answers'",
+            "part_3 = 1",
+            "part_1 = 1\nparams = 1",
+        ] {
+            let table: toml::Table = format!("[bad]\n{}", bad_spec).parse()?;
+            assert!(
+                ExampleSpec::try_from(("bad", &table["bad"])).is_err(),
+                "{}",
+                bad_spec
+            );
+        }
+
+        assert_eq!(example_day("day_09_example.txt"), Some(9));
+        assert_eq!(example_day("day_09_example_2.txt"), Some(9));
+        assert_eq!(example_day("day_09.txt"), None);
+        assert_eq!(example_day("day_09_examples.txt"), None);
+        assert_eq!(example_day("day_26_example.txt"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        let examples = load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("assets"))?;
+        // every day has at least one example
+        let days: BTreeSet<u8> = examples.iter().map(|e| e.day).collect();
+        assert_eq!(days, (FIRST_DAY..=LAST_DAY).collect());
+
+        let mut failures: Vec<String> = Vec::new();
+        pool::run_ordered(
+            pool::available_workers(),
+            &examples,
+            |example| match &example.spec.skip {
+                Some(reason) => {
+                    eprintln!("skipped {}: {}", example.name, reason);
+                    Ok(())
+                }
+                None => example.check(),
+            },
+            |result| {
+                if let Err(err) = result {
+                    failures.push(format!("{:#}", err));
+                }
+            },
+        );
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,346 @@
+//!fetch.rs
+
+use crate::input;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const YEAR: u16 = 2022;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+// config file in current directory, if no --config is given
+pub const DEFAULT_CONFIG: &str = "aoc.toml";
+pub const DEFAULT_ACCOUNT: &str = "default";
+// environment variables override values of config file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const ACCOUNT_VAR: &str = "AOC_ACCOUNT";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const CACHE_DIR_VAR: &str = "AOC_CACHE_DIR";
+
+const USER_AGENT: &str = concat!(
+    "advent-of-code-2022/",
+    env!("CARGO_PKG_VERSION"),
+    " (input fetcher)"
+);
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+// where and as whom puzzle inputs are downloaded and cached
+#[derive(Clone, PartialEq, Eq)]
+pub struct FetchConfig {
+    pub base_url: String,
+    pub session: Option<String>,
+    // name of account, which owns session; inputs differ between accounts
+    pub account: String,
+    pub cache_dir: PathBuf,
+    // download even if input is cached
+    pub refresh: bool,
+}
+
+// session token must not show up in logs
+impl std::fmt::Debug for FetchConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FetchConfig")
+            .field("base_url", &self.base_url)
+            .field("session", &self.session.as_ref().map(|_| "***"))
+            .field("account", &self.account)
+            .field("cache_dir", &self.cache_dir)
+            .field("refresh", &self.refresh)
+            .finish()
+    }
+}
+
+// cache dir following XDG conventions, local directory as last resort
+fn default_cache_dir(var: &impl Fn(&str) -> Option<String>) -> PathBuf {
+    // empty or relative paths are ignored like unset variables
+    let absolute = |name| var(name).filter(|path| Path::new(path).is_absolute());
+    match (absolute("XDG_CACHE_HOME"), absolute("HOME")) {
+        (Some(cache), _) => PathBuf::from(cache).join("advent-of-code"),
+        (None, Some(home)) => PathBuf::from(home).join(".cache").join("advent-of-code"),
+        (None, None) => PathBuf::from(".aoc-cache"),
+    }
+}
+
+// account names become directory names of cache
+fn check_account(account: &str) -> Result<()> {
+    if account.is_empty()
+        || !account
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "bad account name '{}', use letters, digits, '-' and '_'",
+            account
+        ));
+    }
+    Ok(())
+}
+
+impl FetchConfig {
+    // config of file content and environment variables given by var
+    pub fn new(config: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let table = match config {
+            Some(config) => config.parse::<toml::Table>()?,
+            None => toml::Table::new(),
+        };
+        let value = |key: &str, env: &str| -> Result<Option<String>> {
+            if let Some(value) = var(env) {
+                return Ok(Some(value));
+            }
+            match table.get(key) {
+                Some(toml::Value::String(value)) => Ok(Some(value.to_owned())),
+                Some(_) => Err(anyhow!("'{}' of config must be a string", key)),
+                None => Ok(None),
+            }
+        };
+        let fetch_config = Self {
+            base_url: value("base_url", BASE_URL_VAR)?
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            session: value("session", SESSION_VAR)?.map(|s| s.trim().to_string()),
+            account: value("account", ACCOUNT_VAR)?.unwrap_or_else(|| DEFAULT_ACCOUNT.to_string()),
+            cache_dir: value("cache_dir", CACHE_DIR_VAR)?
+                .map(PathBuf::from)
+                .unwrap_or_else(|| default_cache_dir(&var)),
+            refresh: false,
+        };
+        check_account(&fetch_config.account)?;
+        Ok(fetch_config)
+    }
+    // reads config file, by default aoc.toml if present, and environment variables
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => Some(path.to_path_buf()),
+            None => Some(PathBuf::from(DEFAULT_CONFIG)).filter(|p| p.is_file()),
+        };
+        let config = match &path {
+            Some(path) => Some(
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read config file {}", path.display()))?,
+            ),
+            None => None,
+        };
+        Self::new(config.as_deref(), |name| std::env::var(name).ok()).with_context(|| match &path {
+            Some(path) => format!("bad fetch config {}", path.display()),
+            None => String::from("bad fetch config"),
+        })
+    }
+    // cache directory of inputs of year and account
+    pub fn account_dir(&self) -> PathBuf {
+        self.cache_dir.join(YEAR.to_string()).join(&self.account)
+    }
+    pub fn cache_path(&self, day: u8) -> PathBuf {
+        self.account_dir().join(input::file_name(day))
+    }
+    pub fn input_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+    pub fn agent(&self) -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .timeout(TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+    }
+    pub fn session(&self) -> Result<&str> {
+        self.session.as_deref().ok_or(anyhow!(
+            "no session token, set {} or session in {}",
+            SESSION_VAR,
+            DEFAULT_CONFIG
+        ))
+    }
+}
+
+// failed download, only Offline falls back to cached input
+#[derive(Debug)]
+pub enum DownloadError {
+    Offline(String),
+    Rejected(String),
+}
+
+impl std::fmt::Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::Offline(reason) => write!(f, "server not reachable: {}", reason),
+            DownloadError::Rejected(reason) => write!(f, "request rejected: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for DownloadError {}
+
+// turns errors of ureq into DownloadError, reading error message of server
+pub fn download_error(err: ureq::Error) -> DownloadError {
+    match err {
+        ureq::Error::Status(code, response) => {
+            let message = response.into_string().unwrap_or_default();
+            let reason = match code {
+                400 | 401 | 403 => String::from("session token not accepted"),
+                404 => String::from("not found, puzzle may not be unlocked yet"),
+                _ => format!("status {}", code),
+            };
+            match message.trim() {
+                "" => DownloadError::Rejected(reason),
+                message => DownloadError::Rejected(format!("{} ({})", reason, message)),
+            }
+        }
+        ureq::Error::Transport(transport) => DownloadError::Offline(transport.to_string()),
+    }
+}
+
+pub fn download(config: &FetchConfig, day: u8) -> Result<String, DownloadError> {
+    let session = config
+        .session()
+        .map_err(|err| DownloadError::Rejected(err.to_string()))?;
+    let input = config
+        .agent()
+        .get(&config.input_url(day))
+        .set("Cookie", &format!("session={}", session))
+        .call()
+        .map_err(download_error)?
+        .into_string()
+        .map_err(|err| DownloadError::Offline(err.to_string()))?;
+    if input.trim().is_empty() {
+        return Err(DownloadError::Rejected(String::from("empty input")));
+    }
+    Ok(input)
+}
+
+// cached input of day, downloading and caching it if missing or refresh is requested;
+// if server is not reachable, cached input is used anyway
+pub fn fetch_input(config: &FetchConfig, day: u8) -> Result<String> {
+    let path = config.cache_path(day);
+    if !config.refresh && path.is_file() {
+        return read_cached(&path);
+    }
+    match download(config, day) {
+        Ok(input) => {
+            std::fs::create_dir_all(config.account_dir())
+                .and_then(|_| std::fs::write(&path, &input))
+                .with_context(|| format!("failed to cache input at {}", path.display()))?;
+            Ok(input)
+        }
+        Err(DownloadError::Offline(_)) if path.is_file() => read_cached(&path),
+        Err(err) => Err(anyhow!(err)).with_context(|| {
+            format!(
+                "failed to fetch input of day {} from {}",
+                day,
+                config.input_url(day)
+            )
+        }),
+    }
+}
+
+fn read_cached(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read cached input {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::mock_server::MockServer;
+
+    fn test_config(base_url: &str, cache_dir: &Path) -> Result<FetchConfig> {
+        let config = format!(
+            "base_url = '{}/'\ncache_dir = '{}'\naccount = 'alice'",
+            base_url,
+            cache_dir.display()
+        );
+        FetchConfig::new(Some(&config), |name| {
+            (name == SESSION_VAR).then(|| String::from("secret"))
+        })
+    }
+
+    #[test]
+    fn test_fetch_config() -> Result<()> {
+        let config = FetchConfig::new(None, |name| match name {
+            "HOME" => Some(String::from("/home/alice")),
+            _ => None,
+        })?;
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+        assert_eq!(config.session, None);
+        assert!(config.session().is_err());
+        assert_eq!(
+            config.cache_path(7),
+            PathBuf::from("/home/alice/.cache/advent-of-code/2022/default/day_07.txt")
+        );
+        assert_eq!(
+            config.input_url(7),
+            "https://adventofcode.com/2022/day/7/input"
+        );
+
+        let config = test_config("http://localhost:8080", Path::new("cache"))?;
+        assert_eq!(
+            config.input_url(25),
+            "http://localhost:8080/2022/day/25/input"
+        );
+        assert_eq!(config.session()?, "secret");
+        assert!(!format!("{:?}", config).contains("secret"));
+        assert_eq!(
+            config.cache_path(1),
+            PathBuf::from("cache/2022/alice/day_01.txt")
+        );
+
+        let config = FetchConfig::new(None, |name| match name {
+            "XDG_CACHE_HOME" => Some(String::new()),
+            "HOME" => Some(String::from("/home/alice")),
+            _ => None,
+        })?;
+        assert_eq!(
+            config.cache_dir,
+            PathBuf::from("/home/alice/.cache/advent-of-code")
+        );
+        let config = FetchConfig::new(None, |name| match name {
+            "XDG_CACHE_HOME" => Some(String::from("cache")),
+            "HOME" => Some(String::new()),
+            _ => None,
+        })?;
+        assert_eq!(config.cache_dir, PathBuf::from(".aoc-cache"));
+
+        assert!(FetchConfig::new(Some("account = '../bob'"), |_| None).is_err());
+        assert!(FetchConfig::new(Some("session = 1"), |_| None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_input() -> Result<()> {
+        let server = MockServer::start(|request| match request.path.as_str() {
+            _ if request.header("cookie") != Some("session=secret") => {
+                (400, String::from("Please log in."))
+            }
+            "/2022/day/1/input" => (200, String::from("1000\n2000\n")),
+            _ => (404, String::from("Not Found")),
+        })?;
+        let cache_dir = std::env::temp_dir().join(format!("aoc_2022_fetch_{}", std::process::id()));
+        let mut config = test_config(&server.base_url, &cache_dir)?;
+
+        assert_eq!(fetch_input(&config, 1)?, "1000\n2000\n");
+        assert_eq!(
+            std::fs::read_to_string(config.cache_path(1))?,
+            "1000\n2000\n"
+        );
+        // second call is served from cache
+        assert_eq!(fetch_input(&config, 1)?, "1000\n2000\n");
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(server.requests()[0].method, "GET");
+        assert!(server.requests()[0]
+            .header("user-agent")
+            .is_some_and(|agent| agent.starts_with("advent-of-code-2022")));
+
+        let err = fetch_input(&config, 2).unwrap_err();
+        assert!(format!("{:#}", err).contains("may not be unlocked yet"));
+        config.session = Some(String::from("wrong"));
+        config.refresh = true;
+        let err = fetch_input(&config, 1).unwrap_err();
+        assert!(format!("{:#}", err).contains("session token not accepted"));
+
+        // offline refresh falls back to cache
+        let base_url = server.base_url.clone();
+        drop(server);
+        config.base_url = base_url;
+        assert_eq!(fetch_input(&config, 1)?, "1000\n2000\n");
+        assert!(fetch_input(&config, 3).is_err());
+        std::fs::remove_dir_all(&cache_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,957 @@
+//!generate.rs
+
+use crate::grid;
+use crate::ocr;
+use crate::selection::{FIRST_DAY, LAST_DAY};
+use crate::solution::Params;
+use anyhow::{anyhow, Result};
+use std::collections::HashSet;
+
+pub const DEFAULT_SEED: u64 = 2022;
+// number of lines or items of generated inputs, side length of grids
+pub const DEFAULT_SIZE: usize = 100;
+
+// SplitMix64, own generator keeps inputs of a seed the same across versions of dependencies
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    // random number of min..=max
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max);
+        let span = (max as i128 - min as i128 + 1) as u128;
+        (min as i128 + (self.next_u64() as u128 % span) as i128) as i64
+    }
+    // random index of 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        (self.next_u64() % n as u64) as usize
+    }
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.next_u64() % 100 < percent
+    }
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+    fn letter(&mut self, letters: &str) -> char {
+        let letters: Vec<char> = letters.chars().collect();
+        *self.pick(&letters)
+    }
+    fn word(&mut self, letters: &str, min_len: usize, max_len: usize) -> String {
+        let len = self.range(min_len as i64, max_len as i64) as usize;
+        (0..len).map(|_| self.letter(letters)).collect()
+    }
+}
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// random puzzle input of day, which is valid for parser and solver of day;
+// the CRT of day 10 has a fixed size and ignores size
+pub fn generate(day: u8, seed: u64, size: usize) -> Result<String> {
+    if !(FIRST_DAY..=LAST_DAY).contains(&day) {
+        return Err(anyhow!("day {} has no input generator", day));
+    }
+    let mut rng = day_rng(day, seed);
+    let size = size.max(1);
+    let input = match day {
+        1 => calorie_lists(&mut rng, size),
+        2 => strategy_guide(&mut rng, size),
+        3 => rucksacks(&mut rng, size),
+        4 => section_pairs(&mut rng, size),
+        5 => crate_stacks(&mut rng, size),
+        6 => datastream(&mut rng, size),
+        7 => terminal_output(&mut rng, size),
+        8 => tree_heights(&mut rng, size),
+        9 => rope_motions(&mut rng, size),
+        10 => cpu_program(&mut rng)?,
+        11 => monkey_notes(&mut rng, size),
+        12 => heightmap(&mut rng, size),
+        13 => packet_pairs(&mut rng, size),
+        14 => rock_paths(&mut rng, size),
+        15 => sensor_report(&mut rng, size, 4_000_000),
+        16 => valve_scan(&mut rng, size),
+        17 => jet_pattern(&mut rng, size),
+        18 => lava_droplet(&mut rng, size),
+        19 => blue_prints(&mut rng, size),
+        20 => encrypted_file(&mut rng, size),
+        21 => monkey_jobs(&mut rng, size),
+        22 => monkey_map(&mut rng, size),
+        23 => elve_grove(&mut rng, size),
+        24 => blizzard_vale(&mut rng, size),
+        _ => snafu_numbers(&mut rng, size),
+    };
+    Ok(input)
+}
+
+// like generate(), but puzzle specific values are scaled down with size as well, which
+// keeps inputs small enough for reference solvers; returned params are needed to solve input
+pub fn generate_with_params(day: u8, seed: u64, size: usize) -> Result<(String, Params)> {
+    let mut params = Params::new();
+    if day != 15 {
+        return Ok((generate(day, seed, size)?, params));
+    }
+    let size = size.max(1);
+    let max_range = 20 * size as i64;
+    params.insert("row", max_range / 2);
+    params.insert("max_range", max_range);
+    let input = sensor_report(&mut day_rng(day, seed), size, max_range);
+    Ok((input, params))
+}
+
+// same seed gives different inputs for each day
+fn day_rng(day: u8, seed: u64) -> Rng {
+    Rng::new(seed ^ ((day as u64) << 56))
+}
+
+fn calorie_lists(rng: &mut Rng, size: usize) -> String {
+    // part 2 needs at least three elves
+    let elves: Vec<String> = (0..(size / 4).max(3))
+        .map(|_| {
+            let calories: Vec<String> = (0..rng.range(1, 6))
+                .map(|_| rng.range(1_000, 60_000).to_string())
+                .collect();
+            calories.join("\n")
+        })
+        .collect();
+    elves.join("\n\n")
+}
+
+fn strategy_guide(rng: &mut Rng, size: usize) -> String {
+    let rounds: Vec<String> = (0..size)
+        .map(|_| format!("{} {}", rng.letter("ABC"), rng.letter("XYZ")))
+        .collect();
+    rounds.join("\n")
+}
+
+fn rucksacks(rng: &mut Rng, size: usize) -> String {
+    let mut rucksacks: Vec<String> = Vec::new();
+    for _group in 0..size.div_ceil(3) {
+        let mut letters: Vec<char> = LOWERCASE.chars().chain(UPPERCASE.chars()).collect();
+        rng.shuffle(&mut letters);
+        // badge is the only item of all three rucksacks of group, the other items
+        // of each rucksack come from its own pool of letters
+        let badge = letters.pop().unwrap();
+        for pool in letters.chunks(letters.len() / 3).take(3) {
+            let (common, pool) = pool.split_first().unwrap();
+            // common item is the only item in both compartments
+            let (pool_1, pool_2) = pool.split_at(pool.len() / 2);
+            let len = rng.range(3, 12) as usize;
+            let mut compartment_1: Vec<char> = vec![*common];
+            let mut compartment_2: Vec<char> = vec![*common];
+            if rng.chance(50) {
+                compartment_1.push(badge);
+            } else {
+                compartment_2.push(badge);
+            }
+            while compartment_1.len() < len {
+                compartment_1.push(*rng.pick(pool_1));
+            }
+            while compartment_2.len() < len {
+                compartment_2.push(*rng.pick(pool_2));
+            }
+            rng.shuffle(&mut compartment_1);
+            rng.shuffle(&mut compartment_2);
+            rucksacks.push(compartment_1.into_iter().chain(compartment_2).collect());
+        }
+    }
+    rucksacks.join("\n")
+}
+
+fn section_pairs(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size)
+        .map(|_| {
+            let mut sections = [0; 4];
+            for pair in sections.chunks_mut(2) {
+                pair[0] = rng.range(1, 99);
+                pair[1] = rng.range(pair[0], 99);
+            }
+            format!(
+                "{}-{},{}-{}",
+                sections[0], sections[1], sections[2], sections[3]
+            )
+        })
+        .collect();
+    pairs.join("\n")
+}
+
+fn crate_stacks(rng: &mut Rng, size: usize) -> String {
+    let num_stacks = rng.range(3, 9) as usize;
+    let mut stacks: Vec<Vec<char>> = (0..num_stacks)
+        .map(|_| {
+            (0..rng.range(1, 8))
+                .map(|_| rng.letter(UPPERCASE))
+                .collect()
+        })
+        .collect();
+    // at least one crate can be moved
+    if stacks.iter().all(|s| s.len() == 1) {
+        stacks[0].push(rng.letter(UPPERCASE));
+    }
+    let max_height = stacks.iter().map(|s| s.len()).max().unwrap();
+    let mut lines: Vec<String> = (0..max_height)
+        .rev()
+        .map(|level| {
+            let crates: Vec<String> = stacks
+                .iter()
+                .map(|s| match s.get(level) {
+                    Some(c) => format!("[{}]", c),
+                    None => String::from("   "),
+                })
+                .collect();
+            crates.join(" ")
+        })
+        .collect();
+    let numbers: Vec<String> = (1..=num_stacks).map(|n| format!(" {} ", n)).collect();
+    lines.push(numbers.join(" "));
+    lines.push(String::new());
+    // moves leave at least one crate on each stack, so that top crates exist
+    for _ in 0..size {
+        let sources: Vec<usize> = (0..num_stacks).filter(|s| stacks[*s].len() > 1).collect();
+        if sources.is_empty() {
+            break;
+        }
+        let source = *rng.pick(&sources);
+        let target = (source + 1 + rng.below(num_stacks - 1)) % num_stacks;
+        let count = rng.range(1, (stacks[source].len() - 1).min(255) as i64) as usize;
+        let split = stacks[source].len() - count;
+        let moved = stacks[source].split_off(split);
+        stacks[target].extend(moved);
+        lines.push(format!(
+            "move {} from {} to {}",
+            count,
+            source + 1,
+            target + 1
+        ));
+    }
+    lines.join("\n")
+}
+
+fn datastream(rng: &mut Rng, size: usize) -> String {
+    // few letters make markers rare, 14 different letters guarantee both markers
+    let mut signal: Vec<char> = (0..size.max(14)).map(|_| rng.letter("abcdef")).collect();
+    let mut marker: Vec<char> = LOWERCASE.chars().collect();
+    rng.shuffle(&mut marker);
+    let position = rng.below(signal.len() - 13);
+    signal.splice(position..position + 14, marker.into_iter().take(14));
+    signal.into_iter().collect()
+}
+
+struct Dir {
+    name: String,
+    sub_dirs: Vec<usize>,
+    files: Vec<(String, u64)>,
+}
+
+fn terminal_output(rng: &mut Rng, size: usize) -> String {
+    let mut dirs: Vec<Dir> = vec![Dir {
+        name: String::from("/"),
+        sub_dirs: Vec::new(),
+        files: Vec::new(),
+    }];
+    let mut names: HashSet<(usize, String)> = HashSet::new();
+    for _ in 0..size {
+        let parent = rng.below(dirs.len());
+        let mut name = rng.word(LOWERCASE, 1, 8);
+        // files get an extension some times, names are unique in each directory
+        let is_dir = rng.chance(25);
+        if !is_dir && rng.chance(60) {
+            name = format!("{}.{}", name, rng.word(LOWERCASE, 1, 3));
+        }
+        if !names.insert((parent, name.clone())) {
+            continue;
+        }
+        if is_dir {
+            let sub_dir = dirs.len();
+            dirs[parent].sub_dirs.push(sub_dir);
+            dirs.push(Dir {
+                name,
+                sub_dirs: Vec::new(),
+                files: Vec::new(),
+            });
+        } else {
+            dirs[parent]
+                .files
+                .push((name, rng.range(1_000, 300_000) as u64));
+        }
+    }
+    // part 2 needs a used space between 40_000_000 and 70_000_000
+    let used_space = rng.range(40_000_001, 70_000_000) as u64;
+    let total_size: u64 = dirs.iter().flat_map(|d| d.files.iter()).map(|f| f.1).sum();
+    let mut scaled_size: u64 = 0;
+    for file in dirs.iter_mut().flat_map(|d| d.files.iter_mut()) {
+        if total_size > used_space {
+            file.1 = (file.1 * used_space / total_size).max(1);
+        }
+        scaled_size += file.1;
+    }
+    if scaled_size < used_space {
+        let mut name = rng.word(LOWERCASE, 9, 12);
+        while names.contains(&(0, name.clone())) {
+            name = rng.word(LOWERCASE, 9, 12);
+        }
+        dirs[0].files.push((name, used_space - scaled_size));
+    }
+    let mut lines: Vec<String> = vec![String::from("$ cd /")];
+    list_dir(rng, &dirs, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn list_dir(rng: &mut Rng, dirs: &[Dir], dir: usize, lines: &mut Vec<String>) {
+    lines.push(String::from("$ ls"));
+    let mut entries: Vec<String> = dirs[dir]
+        .sub_dirs
+        .iter()
+        .map(|d| format!("dir {}", dirs[*d].name))
+        .chain(dirs[dir].files.iter().map(|(n, s)| format!("{} {}", s, n)))
+        .collect();
+    rng.shuffle(&mut entries);
+    lines.extend(entries);
+    for sub_dir in dirs[dir].sub_dirs.iter() {
+        lines.push(format!("$ cd {}", dirs[*sub_dir].name));
+        list_dir(rng, dirs, *sub_dir, lines);
+        lines.push(String::from("$ cd .."));
+    }
+}
+
+// lines of width characters each
+fn grid(rng: &mut Rng, width: usize, height: usize, cell: impl Fn(&mut Rng) -> char) -> String {
+    let lines: Vec<String> = (0..height)
+        .map(|_| (0..width).map(|_| cell(rng)).collect())
+        .collect();
+    lines.join("\n")
+}
+
+fn tree_heights(rng: &mut Rng, size: usize) -> String {
+    let side = size.clamp(2, grid::MAX_SIDE);
+    grid(rng, side, side, |rng| rng.letter("0123456789"))
+}
+
+fn rope_motions(rng: &mut Rng, size: usize) -> String {
+    let motions: Vec<String> = (0..size)
+        .map(|_| format!("{} {}", rng.letter("UDLR"), rng.range(1, 20)))
+        .collect();
+    motions.join("\n")
+}
+
+fn cpu_program(rng: &mut Rng) -> Result<String> {
+    // exactly 240 cycles for the 40x6 CRT, which draws 8 random letters; sprite starts at
+    // x = 1 and lights the first two pixels, which only some letters start with
+    let mut letters = String::from(rng.letter("BEFPRZ"));
+    let font: String = ocr::FONT.iter().map(|(letter, _)| *letter).collect();
+    letters.extend((1..8).map(|_| rng.letter(&font)));
+    let image = ocr::render(&letters)?;
+    // x of each pair of pixels, which are drawn during the two cycles of one instruction;
+    // sprite lights x - 1..=x + 1
+    let mut xs: Vec<i64> = Vec::new();
+    for row in image.lines() {
+        let pixels: Vec<bool> = row.chars().map(|c| c == '#').collect();
+        for c in (0..pixels.len() as i64).step_by(2) {
+            xs.push(match (pixels[c as usize], pixels[c as usize + 1]) {
+                (true, true) => c + rng.range(0, 1),
+                (true, false) => c - 1,
+                (false, true) => c + 2,
+                (false, false) if c + 4 < 40 => c + rng.range(4, 39 - c),
+                (false, false) => c - 3,
+            });
+        }
+    }
+    xs[0] = 1;
+    // each instruction sets x of next pair, last pair is followed by nothing
+    xs.push(xs[xs.len() - 1]);
+    let program: Vec<String> = xs
+        .windows(2)
+        .map(|pair| match pair[1] - pair[0] {
+            0 => String::from("noop\nnoop"),
+            delta => format!("addx {}", delta),
+        })
+        .collect();
+    Ok(program.join("\n"))
+}
+
+struct MonkeyNote {
+    items: Vec<i64>,
+    // None squares worry level
+    factor: Option<i64>,
+    summand: i64,
+    divisor: i64,
+    targets: (usize, usize),
+}
+
+// worry levels of part 1 stay in range of i64, values of part 2 are bound by divisors
+fn fits_part_one(monkeys: &[MonkeyNote]) -> bool {
+    let mut items: Vec<Vec<i64>> = monkeys.iter().map(|m| m.items.clone()).collect();
+    for _round in 0..20 {
+        for (index, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[index]) {
+                let new = match monkey.factor {
+                    Some(factor) => item.checked_mul(factor),
+                    None => item.checked_mul(item),
+                }
+                .and_then(|new| new.checked_add(monkey.summand));
+                let Some(new) = new.map(|new| new / 3) else {
+                    return false;
+                };
+                let target = match new % monkey.divisor {
+                    0 => monkey.targets.0,
+                    _ => monkey.targets.1,
+                };
+                items[target].push(new);
+            }
+        }
+    }
+    true
+}
+
+fn monkey_notes(rng: &mut Rng, size: usize) -> String {
+    let num_monkeys = (size / 12).clamp(4, 8);
+    // like puzzle input, only one monkey squares worry levels; notes are drawn again
+    // until worry levels of part 1 fit, at last with additions only
+    let mut attempts = 0;
+    let monkeys = loop {
+        attempts += 1;
+        let multiplies = attempts <= 100;
+        let mut divisors = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+        rng.shuffle(&mut divisors);
+        let square = rng.below(num_monkeys);
+        let monkeys: Vec<MonkeyNote> = (0..num_monkeys)
+            .map(|monkey| {
+                let (factor, summand) = if multiplies && monkey == square {
+                    (None, 0)
+                } else if multiplies && rng.chance(30) {
+                    (Some(rng.range(2, 19)), 0)
+                } else {
+                    (Some(1), rng.range(1, 8))
+                };
+                let test_true = (monkey + 1 + rng.below(num_monkeys - 1)) % num_monkeys;
+                let mut test_false = (monkey + 1 + rng.below(num_monkeys - 1)) % num_monkeys;
+                if test_false == test_true && num_monkeys > 2 {
+                    test_false = (0..num_monkeys)
+                        .find(|m| *m != monkey && *m != test_true)
+                        .unwrap();
+                }
+                MonkeyNote {
+                    items: (0..rng.range(1, 6)).map(|_| rng.range(50, 99)).collect(),
+                    factor,
+                    summand,
+                    divisor: divisors[monkey],
+                    targets: (test_true, test_false),
+                }
+            })
+            .collect();
+        if fits_part_one(&monkeys) {
+            break monkeys;
+        }
+    };
+    let notes: Vec<String> = monkeys
+        .iter()
+        .enumerate()
+        .map(|(index, monkey)| {
+            let items: Vec<String> = monkey.items.iter().map(|i| i.to_string()).collect();
+            let operation = match monkey.factor {
+                None => String::from("old * old"),
+                Some(1) => format!("old + {}", monkey.summand),
+                Some(factor) => format!("old * {}", factor),
+            };
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}",
+                index,
+                items.join(", "),
+                operation,
+                monkey.divisor,
+                monkey.targets.0,
+                monkey.targets.1
+            )
+        })
+        .collect();
+    notes.join("\n\n")
+}
+
+fn heightmap(rng: &mut Rng, size: usize) -> String {
+    // elevation rises from west to east by at most one per column, which needs at least 26
+    // columns; the trail row from S to E has no dips
+    let width = size.clamp(26, grid::MAX_SIDE);
+    let height = (size / 3).clamp(1, grid::MAX_SIDE);
+    let trail = rng.below(height);
+    let lines: Vec<String> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    let elevation = (x * 25 / (width - 1)) as u8;
+                    let dip = if y == trail { 0 } else { rng.range(0, 2) as u8 };
+                    match x {
+                        0 if y == trail => 'S',
+                        _ if x == width - 1 && y == trail => 'E',
+                        _ => (b'a' + elevation.saturating_sub(dip)) as char,
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    lines.join("\n")
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    if depth > 0 && (depth >= 4 || rng.chance(40)) {
+        return rng.range(0, 10).to_string();
+    }
+    let items: Vec<String> = (0..rng.range(0, 5))
+        .map(|_| packet(rng, depth + 1))
+        .collect();
+    format!("[{}]", items.join(","))
+}
+
+fn packet_pairs(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size.div_ceil(2))
+        .map(|_| format!("{}\n{}", packet(rng, 0), packet(rng, 0)))
+        .collect();
+    pairs.join("\n\n")
+}
+
+fn rock_paths(rng: &mut Rng, size: usize) -> String {
+    let paths: Vec<String> = (0..size.div_ceil(5))
+        .map(|path| {
+            let (mut x, mut y) = (
+                rng.range(470, 530),
+                rng.range(10, 10 + size.min(150) as i64),
+            );
+            let mut corners = vec![format!("{},{}", x, y)];
+            // first path catches sand below source
+            if path == 0 {
+                x = rng.range(490, 499);
+                corners = vec![format!("{},{}", x, y)];
+                x = rng.range(501, 510);
+                corners.push(format!("{},{}", x, y));
+            }
+            let mut horizontal = path != 0 && rng.chance(50);
+            for _ in 0..rng.range(1, 5) {
+                let length = rng.range(1, 8) * if rng.chance(50) { 1 } else { -1 };
+                if horizontal {
+                    x += length;
+                } else {
+                    // sand source at 500,0 stays free
+                    y = (y + length).max(1);
+                }
+                horizontal = !horizontal;
+                corners.push(format!("{},{}", x, y));
+            }
+            corners.dedup();
+            corners.join(" -> ")
+        })
+        .collect();
+    paths.join("\n")
+}
+
+fn sensor_line(sensor: (i64, i64), beacon: (i64, i64)) -> String {
+    format!(
+        "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+        sensor.0, sensor.1, beacon.0, beacon.1
+    )
+}
+
+fn sensor_report(rng: &mut Rng, size: usize, max_range: i64) -> String {
+    // part 2 needs exactly one position in range, which is not covered by sensors;
+    // four sensors in diagonal directions of it cover all other positions
+    let (x, y) = (rng.range(0, max_range - 1), rng.range(0, max_range - 1));
+    let k = x.max(max_range - x).max(y).max(max_range - y) + rng.range(0, max_range / 4_000);
+    let mut lines: Vec<String> = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .into_iter()
+        .map(|(dx, dy)| {
+            let sensor = (x + dx * k, y + dy * k);
+            sensor_line(sensor, (sensor.0 - dx * (2 * k - 1), sensor.1))
+        })
+        .collect();
+    // further sensors do not reach distress beacon
+    for _ in 4..size.max(4) {
+        let sensor = (rng.range(0, max_range), rng.range(0, max_range));
+        let distance = (sensor.0 - x).abs() + (sensor.1 - y).abs();
+        if distance < 2 {
+            continue;
+        }
+        let range = rng.range(0, (distance - 1).min(max_range / 4));
+        let dx = rng.range(-range, range);
+        let dy = (range - dx.abs()) * if rng.chance(50) { 1 } else { -1 };
+        lines.push(sensor_line(sensor, (sensor.0 + dx, sensor.1 + dy)));
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+fn valve_scan(rng: &mut Rng, size: usize) -> String {
+    let num_valves = (size / 2).clamp(2, 60);
+    let mut labels: Vec<String> = vec![String::from("AA")];
+    while labels.len() < num_valves {
+        let label = rng.word(UPPERCASE, 2, 2);
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    // connected tunnels of a random spanning tree and some more
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); num_valves];
+    let connect = |a: usize, b: usize, tunnels: &mut Vec<Vec<usize>>| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for valve in 1..num_valves {
+        let other = rng.below(valve);
+        connect(valve, other, &mut tunnels);
+    }
+    for _ in 0..num_valves / 3 {
+        let (a, b) = (rng.below(num_valves), rng.below(num_valves));
+        connect(a, b, &mut tunnels);
+    }
+    // like puzzle input, only a few valves have a flow rate, which keeps solving fast
+    let mut flow_rates: Vec<i64> = (1..num_valves)
+        .map(|i| if i <= 15 { rng.range(1, 25) } else { 0 })
+        .collect();
+    rng.shuffle(&mut flow_rates);
+    flow_rates.insert(0, 0);
+    let mut lines: Vec<String> = (0..num_valves)
+        .map(|valve| {
+            let targets: Vec<&str> = tunnels[valve].iter().map(|t| labels[*t].as_str()).collect();
+            let tunnels = match targets.len() {
+                1 => "tunnel leads to valve",
+                _ => "tunnels lead to valves",
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}",
+                labels[valve],
+                flow_rates[valve],
+                tunnels,
+                targets.join(", ")
+            )
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+fn jet_pattern(rng: &mut Rng, size: usize) -> String {
+    // unbalanced patterns may keep a column of chamber empty, then tower never repeats
+    let len = size.max(40);
+    let mut jets: Vec<char> = (0..len)
+        .map(|i| if i < len / 2 { '<' } else { '>' })
+        .collect();
+    rng.shuffle(&mut jets);
+    jets.into_iter().collect()
+}
+
+fn lava_droplet(rng: &mut Rng, size: usize) -> String {
+    // blob of connected cubes, which grows at random cubes of its surface
+    let directions = [
+        (1, 0, 0),
+        (-1, 0, 0),
+        (0, 1, 0),
+        (0, -1, 0),
+        (0, 0, 1),
+        (0, 0, -1),
+    ];
+    let mut cubes: Vec<(i64, i64, i64)> = vec![(10, 10, 10)];
+    let mut seen: HashSet<(i64, i64, i64)> = cubes.iter().copied().collect();
+    let max_cubes = size.min(21 * 21 * 21 / 2);
+    while cubes.len() < max_cubes {
+        let (x, y, z) = *rng.pick(&cubes);
+        let (dx, dy, dz) = *rng.pick(&directions);
+        let cube = (x + dx, y + dy, z + dz);
+        if [cube.0, cube.1, cube.2]
+            .iter()
+            .all(|c| (0..=20).contains(c))
+            && seen.insert(cube)
+        {
+            cubes.push(cube);
+        }
+    }
+    rng.shuffle(&mut cubes);
+    let lines: Vec<String> = cubes
+        .iter()
+        .map(|(x, y, z)| format!("{},{},{}", x, y, z))
+        .collect();
+    lines.join("\n")
+}
+
+fn blue_prints(rng: &mut Rng, size: usize) -> String {
+    let lines: Vec<String> = (1..=size.clamp(1, 30))
+        .map(|id| {
+            format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+                id,
+                rng.range(2, 4),
+                rng.range(2, 4),
+                rng.range(2, 4),
+                rng.range(5, 20),
+                rng.range(2, 4),
+                rng.range(5, 20)
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
+
+fn encrypted_file(rng: &mut Rng, size: usize) -> String {
+    // exactly one 0, from which coordinates are counted
+    let mut codes: Vec<i64> = (1..size.max(2))
+        .map(|_| {
+            let code = rng.range(1, 10_000);
+            if rng.chance(50) {
+                -code
+            } else {
+                code
+            }
+        })
+        .collect();
+    codes.insert(rng.below(codes.len() + 1), 0);
+    let lines: Vec<String> = codes.iter().map(|c| c.to_string()).collect();
+    lines.join("\n")
+}
+
+// expression tree of monkey jobs
+struct MonkeyJobs {
+    jobs: Vec<(String, String)>,
+    names: HashSet<String>,
+}
+
+impl MonkeyJobs {
+    fn name(&mut self, rng: &mut Rng) -> String {
+        loop {
+            let name = rng.word(LOWERCASE, 4, 4);
+            if name != "root" && name != "humn" && self.names.insert(name.clone()) {
+                return name;
+            }
+        }
+    }
+    // monkey with job of value, operations of subtree are exact;
+    // humn is only numerator of divisions, so that part 2 can be solved
+    fn monkey(&mut self, rng: &mut Rng, num_monkeys: usize, humn: Option<i64>) -> (String, i64) {
+        if num_monkeys <= 1 {
+            return match humn {
+                Some(value) => (String::from("humn"), value),
+                None => {
+                    let name = self.name(rng);
+                    let value = rng.range(1, 20);
+                    self.jobs.push((name.clone(), value.to_string()));
+                    (name, value)
+                }
+            };
+        }
+        let left_monkeys = 1 + rng.below(num_monkeys - 1);
+        let humn_left = rng.chance(50);
+        let (left, left_value) = self.monkey(rng, left_monkeys, humn.filter(|_| humn_left));
+        let (right, right_value) =
+            self.monkey(rng, num_monkeys - left_monkeys, humn.filter(|_| !humn_left));
+        let humn_right = humn.is_some() && !humn_left;
+        let (symbol, value) =
+            if right_value != 0 && !humn_right && left_value % right_value == 0 && rng.chance(50) {
+                ("/", left_value / right_value)
+            } else if left_value != 0
+                && right_value != 0
+                && (left_value * right_value).abs() < 1_000_000
+                && rng.chance(40)
+            {
+                ("*", left_value * right_value)
+            } else if rng.chance(50) {
+                ("-", left_value - right_value)
+            } else {
+                ("+", left_value + right_value)
+            };
+        let name = self.name(rng);
+        self.jobs
+            .push((name.clone(), format!("{} {} {}", left, symbol, right)));
+        (name, value)
+    }
+}
+
+fn monkey_jobs(rng: &mut Rng, size: usize) -> String {
+    let mut monkey_jobs = MonkeyJobs {
+        jobs: Vec::new(),
+        names: HashSet::new(),
+    };
+    // value of humn is answer of part 2, other side of root is built to match it
+    let num_monkeys = size.max(4) / 2;
+    let humn = rng.range(1, 20);
+    let (humn_side, humn_value) = monkey_jobs.monkey(rng, num_monkeys, Some(humn));
+    let (other, other_value) = monkey_jobs.monkey(rng, num_monkeys - 1, None);
+    let offset = monkey_jobs.name(rng);
+    monkey_jobs
+        .jobs
+        .push((offset.clone(), (humn_value - other_value).to_string()));
+    let other_side = monkey_jobs.name(rng);
+    monkey_jobs
+        .jobs
+        .push((other_side.clone(), format!("{} + {}", other, offset)));
+    monkey_jobs.jobs.push((
+        String::from("root"),
+        format!("{} + {}", humn_side, other_side),
+    ));
+    // part 1 shouts another number
+    monkey_jobs
+        .jobs
+        .push((String::from("humn"), rng.range(1, 20).to_string()));
+    rng.shuffle(&mut monkey_jobs.jobs);
+    let lines: Vec<String> = monkey_jobs
+        .jobs
+        .iter()
+        .map(|(name, job)| format!("{}: {}", name, job))
+        .collect();
+    lines.join("\n")
+}
+
+fn monkey_map(rng: &mut Rng, size: usize) -> String {
+    // cube net of puzzle input with faces of size 50 for default size
+    let n = (size / 2).clamp(2, grid::MAX_SIDE / 4);
+    let net = [".##", ".#.", "##.", "#.."];
+    let mut lines: Vec<String> = Vec::new();
+    for (face_row, faces) in net.iter().enumerate() {
+        for y in 0..n {
+            let line: String = faces
+                .chars()
+                .enumerate()
+                .flat_map(|(face_column, face)| (0..n).map(move |x| (face_column, face, x)))
+                .map(|(face_column, face, x)| match face {
+                    '#' if face_row == 0 && y == 0 && face_column == 1 && x == 0 => '.',
+                    '#' if rng.chance(8) => '#',
+                    '#' => '.',
+                    _ => ' ',
+                })
+                .collect();
+            lines.push(line.trim_end().to_string());
+        }
+    }
+    let mut path = rng.range(1, 50).to_string();
+    for _ in 0..size {
+        path.push(rng.letter("LR"));
+        path.push_str(&rng.range(1, 50).to_string());
+    }
+    format!("{}\n\n{}", lines.join("\n"), path)
+}
+
+fn elve_grove(rng: &mut Rng, size: usize) -> String {
+    let side = size.clamp(2, 80);
+    grid(
+        rng,
+        side,
+        side,
+        |rng| if rng.chance(50) { '#' } else { '.' },
+    )
+}
+
+fn blizzard_vale(rng: &mut Rng, size: usize) -> String {
+    let width = size.clamp(3, 150);
+    let height = (size / 4).clamp(2, 40);
+    let mut lines: Vec<String> = vec![format!("#.{}", "#".repeat(width))];
+    for _ in 0..height {
+        let row: String = (0..width)
+            .map(|x| {
+                // like puzzle input, no vertical blizzards in columns of entry and exit
+                let directions = if x == 0 || x == width - 1 {
+                    "<>"
+                } else {
+                    "<>^v"
+                };
+                if rng.chance(60) {
+                    rng.letter(directions)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        lines.push(format!("#{}#", row));
+    }
+    lines.push(format!("{}.#", "#".repeat(width)));
+    lines.join("\n")
+}
+
+fn snafu(mut value: i64) -> String {
+    let mut digits: Vec<char> = Vec::new();
+    while value > 0 {
+        let (digit, carry) = match value % 5 {
+            3 => ('=', 1),
+            4 => ('-', 1),
+            d => ((b'0' + d as u8) as char, 0),
+        };
+        digits.push(digit);
+        value = value / 5 + carry;
+    }
+    digits.iter().rev().collect()
+}
+
+fn snafu_numbers(rng: &mut Rng, size: usize) -> String {
+    let lines: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1, 12) as u32;
+            snafu(rng.range(1, 10_i64.pow(digits)))
+        })
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days;
+    use crate::days::day_10::{run_register, Day10};
+    use crate::solution::{catch_panic, Solution};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let numbers: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        assert_eq!(Rng::new(7).next_u64(), numbers[0]);
+        assert_ne!(numbers[0], numbers[1]);
+        for _ in 0..1_000 {
+            assert!((-3..=3).contains(&rng.range(-3, 3)));
+            assert!(rng.below(5) < 5);
+        }
+        assert_eq!(snafu(1), "1");
+        assert_eq!(snafu(2022), "1=11-2");
+        assert_eq!(snafu(314159265), "1121-1110-1=0");
+    }
+
+    #[test]
+    fn test_generate() -> Result<()> {
+        assert!(generate(0, 1, 10).is_err());
+        assert_eq!(generate(7, 1, 50)?, generate(7, 1, 50)?);
+        assert_ne!(generate(7, 1, 50)?, generate(7, 2, 50)?);
+        // CRT of day 10 draws eight letters
+        let program = Day10::parse(&generate(10, 3, 1)?)?;
+        assert_eq!(run_register(&program).crt_letters()?.len(), 8);
+        let mut failures: Vec<String> = Vec::new();
+        for solution in days::registry().iter() {
+            let day = solution.day();
+            for seed in 0..2 {
+                let input = generate(day, seed, 8)?;
+                // generated inputs are solved by all parts without slow parts
+                let result = catch_panic(|| solution.parse(&input)).and_then(|parsed| {
+                    for part in solution.parts().iter() {
+                        if !solution.slow_parts().contains(part) {
+                            catch_panic(|| solution.solve(parsed.as_ref(), *part))?;
+                        }
+                    }
+                    Ok(())
+                });
+                if let Err(err) = result {
+                    failures.push(format!("day {:02} seed {}: {:#}", day, seed, err));
+                }
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        Ok(())
+    }
+}
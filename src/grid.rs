@@ -0,0 +1,246 @@
+//!grid.rs
+
+use crate::parse::{check_grid_size, grid_cells, ParseError};
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+// longest side of grids parsed from input, larger inputs are rejected before allocating them
+pub const MAX_SIDE: usize = 1_000;
+
+// cardinal directions as steps of x and y, y grows downwards
+pub const EAST: (isize, isize) = (1, 0);
+pub const SOUTH: (isize, isize) = (0, 1);
+pub const WEST: (isize, isize) = (-1, 0);
+pub const NORTH: (isize, isize) = (0, -1);
+// clockwise starting east
+pub const CARDINALS: [(isize, isize); 4] = [EAST, SOUTH, WEST, NORTH];
+
+pub fn turn_right((dx, dy): (isize, isize)) -> (isize, isize) {
+    (-dy, dx)
+}
+
+pub fn turn_left((dx, dy): (isize, isize)) -> (isize, isize) {
+    (dy, -dx)
+}
+
+// cells of width x height, sized at runtime; positions are (x, y) with (0, 0) top left
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+    // parses lines of equal length, cell parses char and its token in text
+    pub fn parse(
+        text: &str,
+        cell: impl FnMut(char, &str) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        Self::parse_lines(text, None, cell)
+    }
+    // parses lines of any length, shorter lines are filled up with fill
+    pub fn parse_padded(
+        text: &str,
+        fill: T,
+        cell: impl FnMut(char, &str) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        Self::parse_lines(text, Some(fill), cell)
+    }
+    fn parse_lines(
+        text: &str,
+        fill: Option<T>,
+        mut cell: impl FnMut(char, &str) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        for (position, _, token) in grid_cells(text) {
+            check_grid_size(text, token, position, (MAX_SIDE, MAX_SIDE))?;
+        }
+        let lines: Vec<&str> = text.lines().collect();
+        if lines.len() > MAX_SIDE {
+            return Err(ParseError::new(
+                text,
+                lines[MAX_SIDE],
+                format!("grid of at most {} lines", MAX_SIDE),
+            ));
+        }
+        let width = match fill {
+            Some(_) => lines.iter().map(|l| l.chars().count()).max(),
+            None => lines.first().map(|l| l.chars().count()),
+        }
+        .unwrap_or(0);
+        if width == 0 {
+            return Err(ParseError::new(text, text, "grid"));
+        }
+        let mut cells: Vec<T> = Vec::with_capacity(width * lines.len());
+        for line in lines.iter() {
+            let columns = line.chars().count();
+            if fill.is_none() && columns != width {
+                return Err(ParseError::new(
+                    text,
+                    line,
+                    format!("line of {} columns like the first line", width),
+                ));
+            }
+            for (_, c, token) in grid_cells(line) {
+                cells.push(cell(c, token)?);
+            }
+            if let Some(fill) = fill.as_ref() {
+                cells.extend(std::iter::repeat_n(fill.clone(), width - columns));
+            }
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[self.offset(position)])
+    }
+    fn offset(&self, position: (usize, usize)) -> usize {
+        assert!(self.contains(position), "{:?} is outside of grid", position);
+        position.1 * self.width + position.0
+    }
+    // cells in rows from top to bottom
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| ((i % width, i / width), value))
+    }
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+    // neighbor in direction, None at edge of grid
+    pub fn step(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx).filter(|x| *x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(|y| *y < self.height)?;
+        Some((x, y))
+    }
+    // neighbor in direction, which wraps around to the opposite edge of grid
+    pub fn step_wrap(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> (usize, usize) {
+        (
+            (x as isize + dx).rem_euclid(self.width as isize) as usize,
+            (y as isize + dy).rem_euclid(self.height as isize) as usize,
+        )
+    }
+    // cardinal neighbors inside of grid
+    pub fn neighbors(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        CARDINALS
+            .iter()
+            .filter_map(move |direction| self.step(position, *direction))
+    }
+    // positions in direction from position, which is excluded, to edge of grid
+    pub fn ray(
+        &self,
+        position: (usize, usize),
+        direction: (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.step(position, direction), move |p| {
+            self.step(*p, direction)
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &Self::Output {
+        &self.cells[self.offset(position)]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut Self::Output {
+        let offset = self.offset(position);
+        &mut self.cells[offset]
+    }
+}
+
+// rows of cells separated by new lines
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for value in self.row(y) {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use anyhow::Result;
+
+    fn digit(text: &str) -> impl FnMut(char, &str) -> Result<u32, ParseError> + '_ {
+        |c, token| {
+            c.to_digit(10)
+                .ok_or_else(|| ParseError::new(text, token, "digit"))
+        }
+    }
+
+    #[test]
+    fn test_grid() -> Result<()> {
+        let text = "123\n456";
+        let grid = Grid::parse(text, digit(text))?;
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.to_string(), text);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &5)));
+        assert_eq!(grid.step((0, 0), WEST), None);
+        assert_eq!(grid.step_wrap((0, 0), WEST), (2, 0));
+        assert_eq!(grid.neighbors((0, 1)).collect::<Vec<_>>(), [(1, 1), (0, 0)]);
+        assert_eq!(grid.ray((0, 1), EAST).collect::<Vec<_>>(), [(1, 1), (2, 1)]);
+        assert_eq!(turn_right(EAST), SOUTH);
+        assert_eq!(turn_left(EAST), NORTH);
+
+        let padded = Grid::parse_padded(" #\n#", ' ', |c, _| Ok(c))?;
+        assert_eq!(padded.to_string(), " #\n# ");
+
+        let text = "123\n45\n";
+        let err = Grid::parse(text, digit(text)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2 column 1: expected line of 3 columns like the first line, found '45'"
+        );
+        let text = "12\n3x";
+        assert_eq!(Grid::parse(text, digit(text)).unwrap_err().column, 2);
+        assert!(Grid::parse("", digit("")).is_err());
+        let wide = "1".repeat(MAX_SIDE + 1);
+        let err = Grid::parse(&wide, digit(&wide)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, MAX_SIDE + 1));
+        let high = "1\n".repeat(MAX_SIDE + 1);
+        let err = Grid::parse(&high, digit(&high)).unwrap_err();
+        assert_eq!(err.line, MAX_SIDE + 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//!input.rs
+
+use crate::fetch::{self, FetchConfig};
+use crate::manifest;
+use anyhow::{anyhow, Context, Result};
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InputSource {
+    // puzzle inputs compiled into the binary from ../assets
+    #[default]
+    Embedded,
+    File(PathBuf),
+    Stdin,
+    // directory containing day_NN.txt files, missing days fall back to embedded assets
+    Dir(PathBuf),
+    // inputs downloaded with session of account and cached on disk
+    Fetch(FetchConfig),
+}
+
+impl From<&str> for InputSource {
+    fn from(value: &str) -> Self {
+        match value {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        }
+    }
+}
+
+impl InputSource {
+    // File and Stdin provide exactly one input and therefore only fit one day
+    pub fn is_single_input(&self) -> bool {
+        matches!(self, InputSource::File(_) | InputSource::Stdin)
+    }
+    // true, if input of day is taken from embedded assets
+    pub fn is_embedded(&self, day: u8) -> bool {
+        match self {
+            InputSource::Embedded => true,
+            InputSource::Dir(dir) => !dir.join(file_name(day)).is_file(),
+            InputSource::File(_) | InputSource::Stdin | InputSource::Fetch(_) => false,
+        }
+    }
+    // answers manifest next to input files
+    pub fn manifest_path(&self) -> Option<PathBuf> {
+        let path = match self {
+            InputSource::Dir(dir) => dir.join(manifest::FILE_NAME),
+            InputSource::File(path) => path.with_file_name(manifest::FILE_NAME),
+            InputSource::Fetch(config) => config.account_dir().join(manifest::FILE_NAME),
+            InputSource::Embedded | InputSource::Stdin => return None,
+        };
+        path.is_file().then_some(path)
+    }
+    pub fn load(&self, day: u8) -> Result<Cow<'static, str>> {
+        match self {
+            InputSource::Embedded => embedded(day).map(Cow::Borrowed),
+            InputSource::File(path) => read_file(path).map(Cow::Owned),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("failed to read input from stdin")?;
+                Ok(Cow::Owned(input))
+            }
+            InputSource::Dir(dir) => {
+                let path = dir.join(file_name(day));
+                if path.is_file() {
+                    read_file(&path).map(Cow::Owned)
+                } else {
+                    embedded(day).map(Cow::Borrowed)
+                }
+            }
+            InputSource::Fetch(config) => fetch::fetch_input(config, day).map(Cow::Owned),
+        }
+    }
+    // reader of input of day, files and stdin are read piece by piece instead of all at once
+    pub fn open(&self, day: u8) -> Result<Box<dyn BufRead>> {
+        match self {
+            InputSource::Embedded => Ok(Box::new(embedded(day)?.as_bytes())),
+            InputSource::File(path) => open_file(path),
+            InputSource::Stdin => Ok(Box::new(std::io::stdin().lock())),
+            InputSource::Dir(dir) => {
+                let path = dir.join(file_name(day));
+                if path.is_file() {
+                    open_file(&path)
+                } else {
+                    Ok(Box::new(embedded(day)?.as_bytes()))
+                }
+            }
+            InputSource::Fetch(config) => {
+                let input = fetch::fetch_input(config, day)?;
+                Ok(Box::new(std::io::Cursor::new(input)))
+            }
+        }
+    }
+}
+
+pub fn file_name(day: u8) -> String {
+    format!("day_{:02}.txt", day)
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read input file {}", path.display()))
+}
+
+fn open_file(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("failed to read input file {}", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+pub fn embedded(day: u8) -> Result<&'static str> {
+    let input = match day {
+        1 => include_str!("../assets/day_01.txt"),
+        2 => include_str!("../assets/day_02.txt"),
+        3 => include_str!("../assets/day_03.txt"),
+        4 => include_str!("../assets/day_04.txt"),
+        5 => include_str!("../assets/day_05.txt"),
+        6 => include_str!("../assets/day_06.txt"),
+        7 => include_str!("../assets/day_07.txt"),
+        8 => include_str!("../assets/day_08.txt"),
+        9 => include_str!("../assets/day_09.txt"),
+        10 => include_str!("../assets/day_10.txt"),
+        11 => include_str!("../assets/day_11.txt"),
+        12 => include_str!("../assets/day_12.txt"),
+        13 => include_str!("../assets/day_13.txt"),
+        14 => include_str!("../assets/day_14.txt"),
+        15 => include_str!("../assets/day_15.txt"),
+        16 => include_str!("../assets/day_16.txt"),
+        17 => include_str!("../assets/day_17.txt"),
+        18 => include_str!("../assets/day_18.txt"),
+        19 => include_str!("../assets/day_19.txt"),
+        20 => include_str!("../assets/day_20.txt"),
+        21 => include_str!("../assets/day_21.txt"),
+        22 => include_str!("../assets/day_22.txt"),
+        23 => include_str!("../assets/day_23.txt"),
+        24 => include_str!("../assets/day_24.txt"),
+        25 => include_str!("../assets/day_25.txt"),
+        _ => return Err(anyhow!("no embedded input for day {}", day)),
+    };
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_input_dir() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc_2022_input_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join(file_name(1)), "1\n2\n\n3")?;
+        let source = InputSource::Dir(dir.clone());
+        assert_eq!(source.load(1)?, "1\n2\n\n3");
+        // missing day falls back to embedded asset
+        assert_eq!(source.load(2)?, embedded(2)?);
+        assert!(!source.is_embedded(1));
+        assert!(source.is_embedded(2));
+        assert_eq!(source.manifest_path(), None);
+        std::fs::write(dir.join(manifest::FILE_NAME), "")?;
+        assert_eq!(source.manifest_path(), Some(dir.join(manifest::FILE_NAME)));
+        let source = InputSource::from(dir.join(file_name(1)).to_str().unwrap());
+        assert_eq!(source.load(1)?, "1\n2\n\n3");
+        let mut lines: Vec<String> = Vec::new();
+        for line in source.open(1)?.lines() {
+            lines.push(line?);
+        }
+        assert_eq!(lines, ["1", "2", "", "3"]);
+        std::fs::remove_dir_all(&dir)?;
+
+        assert_eq!(InputSource::from("-"), InputSource::Stdin);
+        assert!(InputSource::File(dir.join("missing.txt")).load(1).is_err());
+        assert!(embedded(26).is_err());
+        Ok(())
+    }
+}
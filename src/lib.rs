@@ -1,35 +1,665 @@
 //!lib.rs
+//!
+//! Solutions of Advent of Code 2022 as library and command line runner.
+//!
+//! Each day is a unit struct `DayNN` implementing [`solution::Solution`]: `DayNN::parse()` turns
+//! the puzzle input into a typed input, `DayNN::part_one()` and `DayNN::part_two()` return typed
+//! answers without printing anything. [`days::get`] and [`days::registry`] give access to all
+//! days without knowing their types. Types and functions of a day's puzzle are documented in
+//! its module, e.g. [`days::day_13`]. Import the common items with [`prelude`].
 
+pub mod answer;
+pub mod bench;
+pub mod budget;
+pub mod cache;
+pub mod cli;
 pub mod days;
+pub mod differential;
+pub mod examples;
+pub mod fetch;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod manifest;
+pub mod matrix;
+#[cfg(test)]
+mod mock_server;
+pub mod ocr;
+pub mod parse;
+pub mod pool;
+pub mod prelude;
+pub mod progress;
+pub mod repl;
+pub mod report;
+pub mod selection;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod solution;
+pub mod submit;
+pub mod trace;
+pub mod visualize;
 
-use anyhow::Result;
-
-pub fn run() -> Result<()> {
-    days::day_01::day_01()?;
-    days::day_02::day_02()?;
-    days::day_03::day_03()?;
-    days::day_04::day_04()?;
-    days::day_05::day_05()?;
-    days::day_06::day_06()?;
-    days::day_07::day_07()?;
-    days::day_08::day_08()?;
-    days::day_09::day_09()?;
-    days::day_10::day_10()?;
-    days::day_11::day_11()?;
-    days::day_12::day_12()?;
-    days::day_13::day_13()?;
-    days::day_14::day_14()?;
-    days::day_15::day_15()?;
-    days::day_16::day_16()?;
-    days::day_17::day_17()?;
-    days::day_18::day_18()?;
-    days::day_19::day_19()?;
-    days::day_20::day_20()?;
-    days::day_21::day_21()?;
-    days::day_22::day_22()?;
-    days::day_23::day_23()?;
-    days::day_24::day_24()?;
-    days::day_25::day_25()?;
+use answer::Answer;
+use anyhow::{anyhow, Context, Result};
+use bench::{bench_day, Baseline};
+use budget::CancelToken;
+use cache::ResultCache;
+use cli::{Options, USAGE};
+use input::InputSource;
+use manifest::Expectations;
+use matrix::{InputSet, Matrix};
+use report::{CacheStatus, Outcome, Record, Report};
+use selection::Part;
+use solution::{catch_panic, DynSolution};
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use visualize::{FrameDump, FrameSink, Terminal, DEFAULT_FPS};
 
+pub fn run<I, S>(args: I) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut options = Options::parse(args)?;
+    trace::init(options.trace.as_deref()).context("bad trace filter")?;
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if options.list {
+        list_days();
+        return Ok(());
+    }
+    if options.generate.is_some() {
+        return run_generate(&options);
+    }
+    if options.differential {
+        return run_differential(&options);
+    }
+    if options.matrix.is_some() {
+        return run_matrix(&options);
+    }
+    options.resolve_fetch()?;
+    if options.bench {
+        return run_bench(&options);
+    }
+    if options.submit {
+        return run_submit(&options);
+    }
+    if options.visualize {
+        return run_visualize(&options);
+    }
+    run_selection(&options)
+}
+
+pub fn list_days() {
+    for solution in days::registry().iter() {
+        let parts: Vec<String> = solution.parts().iter().map(|p| p.to_string()).collect();
+        println!("day {:02}: part {}", solution.day(), parts.join(", "));
+    }
+}
+
+pub fn run_selection(options: &Options) -> Result<()> {
+    check_single_input(options)?;
+    let expectations = if options.verify {
+        Some(Expectations::new(
+            &options.input,
+            options.answers.as_deref(),
+        )?)
+    } else {
+        None
+    };
+    let cache = result_cache(options)?;
+    let selected_days = selected_parts(options)?;
+    let mut report = Report::stdout(options.format);
+    let mut report_result: Result<()> = Ok(());
+    let mut failed: Vec<(u8, Part)> = Vec::new();
+    let mut first_error: Option<anyhow::Error> = None;
+    pool::run_ordered(
+        options.workers(),
+        &selected_days,
+        |(solution, parts)| {
+            solve_day(
+                *solution,
+                parts,
+                options,
+                expectations.as_ref(),
+                cache.as_ref(),
+            )
+        },
+        |(records, error)| {
+            if first_error.is_none() {
+                first_error = error;
+            }
+            for record in records.iter() {
+                if record.is_failure() {
+                    failed.push((record.day, record.part));
+                }
+                if report_result.is_ok() {
+                    report_result = report.emit(record);
+                }
+            }
+        },
+    );
+    report_result?;
+    if failed.is_empty() {
+        return Ok(());
+    }
+    let failed: Vec<String> = failed
+        .iter()
+        .map(|(day, part)| format!("{:02}:{}", day, part))
+        .collect();
+    let message = format!("failed days: {}", failed.join(", "));
+    match first_error {
+        // keep first error as source, e.g. location of a parse error
+        Some(err) => Err(err.context(message)),
+        None => Err(anyhow!(message)),
+    }
+}
+
+// result cache of --fetch config, None with --no-cache or a bad config, which runs without
+// fetching do not need
+fn result_cache(options: &Options) -> Result<Option<Mutex<ResultCache>>> {
+    if options.no_cache {
+        return Ok(None);
+    }
+    let fetch_config = match options.fetch_config() {
+        Ok(fetch_config) => fetch_config,
+        Err(err) => {
+            trace::event!(Warn, "result cache disabled: {:#}", err);
+            return Ok(None);
+        }
+    };
+    let path = cache::cache_path(&fetch_config);
+    Ok(Some(Mutex::new(ResultCache::load(&path)?)))
+}
+
+fn selected_parts(options: &Options) -> Result<Vec<(&'static dyn DynSolution, Vec<Part>)>> {
+    let mut selected_days: Vec<(&dyn DynSolution, Vec<Part>)> = Vec::new();
+    for day in options.selection.days() {
+        let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+        let parts: Vec<Part> = solution
+            .parts()
+            .iter()
+            .filter(|part| options.selection.contains(day, **part))
+            .copied()
+            .collect();
+        selected_days.push((solution, parts));
+    }
+    Ok(selected_days)
+}
+
+// verifies selected parts on the inputs of each set of --matrix and prints a grid of verdicts
+pub fn run_matrix(options: &Options) -> Result<()> {
+    let Some(dir) = options.matrix.as_deref() else {
+        return Ok(());
+    };
+    let sets = matrix::input_sets(dir)?;
+    let matrix = solve_matrix(options, &sets)?;
+    println!("{}", matrix.render());
+    let failed: Vec<String> = matrix
+        .failures()
+        .iter()
+        .map(|(name, record)| format!("{} {:02}:{}", name, record.day, record.part))
+        .collect();
+    if failed.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!("failed cells: {}", failed.join(", ")))
+}
+
+// days without input file in a set have no records of this set
+fn solve_matrix(options: &Options, sets: &[InputSet]) -> Result<Matrix> {
+    let cache = result_cache(options)?;
+    let selected_days = selected_parts(options)?;
+    let mut set_runs: Vec<(Options, Expectations)> = Vec::new();
+    let mut jobs: Vec<(usize, &dyn DynSolution, &[Part])> = Vec::new();
+    for (index, set) in sets.iter().enumerate() {
+        let input = InputSource::Dir(set.dir.clone());
+        let expectations = Expectations::new(&input, None)?;
+        for (solution, parts) in selected_days.iter() {
+            if !input.is_embedded(solution.day()) {
+                jobs.push((index, *solution, parts));
+            }
+        }
+        let set_options = Options {
+            input,
+            ..options.clone()
+        };
+        set_runs.push((set_options, expectations));
+    }
+    let rows: Vec<(u8, Part)> = selected_days
+        .iter()
+        .flat_map(|(solution, parts)| parts.iter().map(|part| (solution.day(), *part)))
+        .collect();
+    let names: Vec<String> = sets.iter().map(|set| set.name.clone()).collect();
+    let mut matrix = Matrix::new(names, rows);
+    pool::run_ordered(
+        options.workers(),
+        &jobs,
+        |(index, solution, parts)| {
+            let (set_options, expectations) = &set_runs[*index];
+            let (records, _) = solve_day(
+                *solution,
+                parts,
+                set_options,
+                Some(expectations),
+                cache.as_ref(),
+            );
+            (*index, records)
+        },
+        |(index, records)| {
+            for record in records {
+                matrix.insert(index, record);
+            }
+        },
+    );
+    Ok(matrix)
+}
+
+// solves the single selected part and submits its answer
+pub fn run_submit(options: &Options) -> Result<()> {
+    let selected: Vec<(u8, Part)> = options.selection.iter().collect();
+    let [(day, part)] = selected[..] else {
+        return Err(anyhow!(
+            "--submit requires exactly one selected part, e.g. 7:1, got {}",
+            selected.len()
+        ));
+    };
+    let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+    let input = options.input.load(day).context("failed to load input")?;
+    let answer = catch_panic(|| solution.run(&input, part))?;
+    let config = options.fetch_config()?;
+    let mut history = submit::History::load(&submit::history_path(&config))?;
+    let response = submit::submit(
+        &config,
+        &mut history,
+        day,
+        part,
+        &answer,
+        submit::unix_now(),
+    )?;
+    println!(
+        "day {:02} part {}: submitted {}, {}",
+        day, part, answer, response
+    );
+    match response {
+        submit::Response::Correct => Ok(()),
+        response => Err(anyhow!(
+            "answer {} of day {:02} part {} not accepted: {}",
+            answer,
+            day,
+            part,
+            response
+        )),
+    }
+}
+
+// writes random inputs of selected days, which can be solved with --input-dir
+pub fn run_generate(options: &Options) -> Result<()> {
+    let Some(dir) = options.generate.as_deref() else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create input dir {}", dir.display()))?;
+    let seed = options.seed.unwrap_or(generate::DEFAULT_SEED);
+    let size = options.size.unwrap_or(generate::DEFAULT_SIZE);
+    for day in options.selection.days() {
+        let input = generate::generate(day, seed, size)?;
+        let path = dir.join(input::file_name(day));
+        std::fs::write(&path, input)
+            .with_context(|| format!("failed to write input {}", path.display()))?;
+        println!("day {:02}: {}", day, path.display());
+    }
     Ok(())
 }
+
+// compares optimized and reference solvers of selected parts on generated inputs
+pub fn run_differential(options: &Options) -> Result<()> {
+    let first_seed = options.seed.unwrap_or(generate::DEFAULT_SEED);
+    let runs = options.runs.unwrap_or(differential::DEFAULT_RUNS);
+    let size = options.size.unwrap_or(differential::DEFAULT_SIZE);
+    let mut mismatches: Vec<String> = Vec::new();
+    for day in options.selection.days() {
+        let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+        for part in solution.reference_parts().iter().copied() {
+            if !options.selection.contains(day, part) {
+                continue;
+            }
+            match differential::check_part(solution, part, first_seed, runs, size)? {
+                Some(mismatch) => {
+                    println!("{}", mismatch);
+                    mismatches.push(format!("{:02}:{}", day, part));
+                }
+                None => println!("day {:02} part {}: {} inputs match", day, part, runs),
+            }
+        }
+    }
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "mismatches of reference: {}",
+        mismatches.join(", ")
+    ))
+}
+
+// animates selected parts of the single selected day, or dumps their frames
+pub fn run_visualize(options: &Options) -> Result<()> {
+    let selected_days = options.selection.days();
+    let [day] = selected_days[..] else {
+        return Err(anyhow!(
+            "--visualize requires exactly one selected day, got {}",
+            selected_days.len()
+        ));
+    };
+    let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+    let parsed = parse_input(solution, &options.input)?;
+    // one terminal for all parts, which reads commands of stdin
+    let mut terminal: Option<Terminal> = None;
+    for part in solution.parts().iter().copied() {
+        if !options.selection.contains(day, part) {
+            continue;
+        }
+        let mut frame_dump: FrameDump;
+        let sink: &mut dyn FrameSink = match options.dump_frames.as_deref() {
+            Some(dir) => {
+                frame_dump = FrameDump::new(&dir.join(format!("part_{}", part)))?;
+                &mut frame_dump
+            }
+            None => {
+                terminal.get_or_insert_with(|| Terminal::new(options.fps.unwrap_or(DEFAULT_FPS)))
+            }
+        };
+        solution.visualize(parsed.as_ref(), part, sink)?;
+    }
+    Ok(())
+}
+
+// solves selected parts of one day, parsing input only once;
+// errors and panics are reported as failed records of this day,
+// the first of them is returned as well
+fn solve_day(
+    solution: &dyn DynSolution,
+    parts: &[Part],
+    options: &Options,
+    expectations: Option<&Expectations>,
+    cache: Option<&Mutex<ResultCache>>,
+) -> (Vec<Record>, Option<anyhow::Error>) {
+    if options.stream && solution.streams() {
+        return solve_day_streamed(solution, parts, options, expectations);
+    }
+    let day = solution.day();
+    let mut records: Vec<Record> = Vec::new();
+    let mut first_error: Option<anyhow::Error> = None;
+    let mut loaded: Option<Result<Cow<str>, String>> = None;
+    let mut parsed: Option<Result<Box<dyn Any>, String>> = None;
+    for part in parts.iter().copied() {
+        if is_skipped_slow_part(solution, part, options) {
+            let outcome = Outcome::Skipped("long run time".into());
+            records.push(Record::new(day, part, Duration::ZERO, outcome));
+            continue;
+        }
+        let input = loaded.get_or_insert_with(|| {
+            options
+                .input
+                .load(day)
+                .context("failed to load input")
+                .map_err(|err| {
+                    let message = format!("{:#}", err);
+                    first_error.get_or_insert(err);
+                    message
+                })
+        });
+        let input = match input {
+            Ok(input) => &**input,
+            Err(message) => {
+                let outcome = Outcome::Failed(message.clone());
+                records.push(Record::new(day, part, Duration::ZERO, outcome));
+                continue;
+            }
+        };
+        let key = cache.map(|_| cache::Key::new(solution, part, input));
+        // --verify recomputes answers instead of trusting cached ones
+        let cached = match (cache, key.as_ref()) {
+            (Some(cache), Some(key)) if !options.verify => cache.lock().unwrap().get(key).cloned(),
+            _ => None,
+        };
+        if let Some(answer) = cached {
+            trace::event!(Debug, "day {:02} part {} served from cache", day, part);
+            let verdict = expectations.map(|e| e.verify(day, part, &answer));
+            let outcome = Outcome::Solved(answer, verdict);
+            let record = Record::new(day, part, Duration::ZERO, outcome);
+            records.push(record.with_cache(CacheStatus::Hit));
+            continue;
+        }
+        let day_input = parsed.get_or_insert_with(|| {
+            catch_panic(|| solution.parse(input))
+                .context("failed to parse input")
+                .map_err(|err| {
+                    let message = format!("{:#}", err);
+                    first_error.get_or_insert(err);
+                    message
+                })
+        });
+        let day_input = match day_input {
+            Ok(day_input) => day_input,
+            Err(message) => {
+                let outcome = Outcome::Failed(message.clone());
+                records.push(Record::new(day, part, Duration::ZERO, outcome));
+                continue;
+            }
+        };
+        let token = CancelToken::with_budget(options.budget);
+        let start = Instant::now();
+        let solve = || {
+            budget::with_token(&token, || {
+                catch_panic(|| solution.solve(day_input.as_ref(), part))
+            })
+        };
+        let result = match options.progress_sink() {
+            Some(sink) => progress::with_sink(&sink, solve),
+            None => solve(),
+        };
+        let elapsed = start.elapsed();
+        trace::event!(
+            Debug,
+            "day {:02} part {} finished in {:?}",
+            day,
+            part,
+            elapsed
+        );
+        let outcome = match (result, options.budget) {
+            (Err(err), Some(budget)) if budget::is_cancelled(&err) => Outcome::TimedOut(budget),
+            (Ok(answer), budget) => {
+                // answers found after last check of the token are still correct
+                if budget.is_some_and(|budget| elapsed > budget) {
+                    trace::event!(Info, "day {:02} part {} solved after its budget", day, part);
+                }
+                let verdict = expectations.map(|e| e.verify(day, part, &answer));
+                Outcome::Solved(answer, verdict)
+            }
+            (Err(err), _) => {
+                let outcome = Outcome::Failed(err.to_string());
+                first_error.get_or_insert(err);
+                outcome
+            }
+        };
+        let mut record = Record::new(day, part, elapsed, outcome);
+        if let (Some(cache), Some(key)) = (cache, key) {
+            // only answers are cached, failures may be fixed by a larger budget or better input
+            if let Outcome::Solved(answer, _) = &record.outcome {
+                if let Err(err) = cache.lock().unwrap().insert(key, answer.clone()) {
+                    trace::event!(Warn, "failed to cache answer: {:#}", err);
+                }
+            }
+            let status = if options.verify {
+                CacheStatus::Refresh
+            } else {
+                CacheStatus::Miss
+            };
+            record = record.with_cache(status);
+        }
+        records.push(record);
+    }
+    (records, first_error)
+}
+
+// solves all parts in one pass over the input reader, so records of parts share its time
+fn solve_day_streamed(
+    solution: &dyn DynSolution,
+    parts: &[Part],
+    options: &Options,
+    expectations: Option<&Expectations>,
+) -> (Vec<Record>, Option<anyhow::Error>) {
+    let day = solution.day();
+    let start = Instant::now();
+    let result = options
+        .input
+        .open(day)
+        .context("failed to open input")
+        .and_then(|mut reader| catch_panic(|| solution.solve_reader(&mut reader)));
+    let elapsed = start.elapsed();
+    trace::event!(Debug, "day {:02} streamed in {:?}", day, elapsed);
+    let answers = match result {
+        Ok(answers) => answers,
+        Err(err) => {
+            let message = format!("{:#}", err);
+            let records = parts
+                .iter()
+                .map(|part| Record::new(day, *part, elapsed, Outcome::Failed(message.clone())))
+                .collect();
+            return (records, Some(err));
+        }
+    };
+    // single parts may fail, e.g. letters of day 10
+    let mut answers: Vec<Option<Result<Answer>>> = answers.into_iter().map(Some).collect();
+    let mut first_error = None;
+    let mut records: Vec<Record> = Vec::new();
+    for part in parts.iter().copied() {
+        let answer = solution
+            .parts()
+            .iter()
+            .position(|p| *p == part)
+            .and_then(|index| answers.get_mut(index).and_then(Option::take));
+        let outcome = match answer {
+            Some(Ok(answer)) => {
+                let verdict = expectations.map(|e| e.verify(day, part, &answer));
+                Outcome::Solved(answer, verdict)
+            }
+            Some(Err(err)) => {
+                let outcome = Outcome::Failed(err.to_string());
+                first_error.get_or_insert(err);
+                outcome
+            }
+            None => Outcome::Failed(format!(
+                "day {:02} streamed no answer of part {}",
+                day, part
+            )),
+        };
+        records.push(Record::new(day, part, elapsed, outcome));
+    }
+    (records, first_error)
+}
+
+fn check_single_input(options: &Options) -> Result<()> {
+    let selected_days = options.selection.days().len();
+    if options.input.is_single_input() && selected_days != 1 {
+        return Err(anyhow!(
+            "a single input file requires exactly one selected day, got {}",
+            selected_days
+        ));
+    }
+    Ok(())
+}
+
+fn is_skipped_slow_part(solution: &dyn DynSolution, part: Part, options: &Options) -> bool {
+    !options.runs_slow_parts() && solution.slow_parts().contains(&part)
+}
+
+pub fn run_bench(options: &Options) -> Result<()> {
+    check_single_input(options)?;
+    let config = &options.bench_config;
+    let baseline = match config.baseline.as_deref() {
+        Some(path) => Some(Baseline::load(path)?),
+        None => None,
+    };
+    let mut current = Baseline::default();
+    let mut failed: Vec<u8> = Vec::new();
+    let mut regressions: Vec<String> = Vec::new();
+    for day in options.selection.days() {
+        let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+        let mut parts: Vec<Part> = Vec::new();
+        for part in solution.parts().iter() {
+            if !options.selection.contains(day, *part) {
+                continue;
+            }
+            if is_skipped_slow_part(solution, *part, options) {
+                println!(
+                    "day {:02} part {} skipped because of long run time",
+                    day, part
+                );
+                continue;
+            }
+            parts.push(*part);
+        }
+        let results = options.input.load(day).and_then(|input| {
+            catch_panic(|| bench_day(solution, &input, &parts, config, options.budget))
+        });
+        let results = match results {
+            Ok(results) => results,
+            Err(err) if budget::is_cancelled(&err) => {
+                println!("day {:02} timed out: {:#}", day, err);
+                failed.push(day);
+                continue;
+            }
+            Err(err) => {
+                println!("day {:02} failed: {:#}", day, err);
+                failed.push(day);
+                continue;
+            }
+        };
+        for (phase, stats) in results.iter() {
+            current.insert(day, *phase, stats.median);
+            let change = baseline
+                .as_ref()
+                .and_then(|b| b.change(day, *phase, stats.median));
+            let change_str = match change {
+                Some(change) if change > config.tolerance => {
+                    regressions.push(format!("{:02}:{}", day, phase));
+                    format!("  {:+.1}% vs baseline, regression", change)
+                }
+                Some(change) => format!("  {:+.1}% vs baseline", change),
+                None => String::new(),
+            };
+            println!(
+                "day {:02} {:<7} min {:>12.3?}  median {:>12.3?}  max {:>12.3?}{}",
+                day, phase, stats.min, stats.median, stats.max, change_str
+            );
+        }
+    }
+    if let Some(path) = config.save_baseline.as_deref() {
+        current.save(path)?;
+    }
+    if !failed.is_empty() {
+        let failed: Vec<String> = failed.iter().map(|day| format!("{:02}", day)).collect();
+        return Err(anyhow!("failed days: {}", failed.join(", ")));
+    }
+    if !regressions.is_empty() {
+        return Err(anyhow!(
+            "regressions beyond {}% tolerance: {}",
+            config.tolerance,
+            regressions.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+fn parse_input(solution: &dyn DynSolution, input_source: &InputSource) -> Result<Box<dyn Any>> {
+    let input = input_source
+        .load(solution.day())
+        .context("failed to load input")?;
+    catch_panic(|| solution.parse(&input)).context("failed to parse input")
+}
@@ -3,12 +3,13 @@
 use advent_of_code_2022::run;
 
 fn main() {
-    if let Err(err) = run() {
-        println!("Error occured: {}", err);
+    if let Err(err) = run(std::env::args().skip(1)) {
+        eprintln!("Error occured: {}", err);
 
-        // look for source
-        if let Some(source) = err.source() {
-            println!("Source of error: {:?}", source);
+        // look for sources, e.g. location of parse errors
+        for source in err.chain().skip(1) {
+            eprintln!("Source of error: {}", source);
         }
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,225 @@
+//!manifest.rs
+
+use crate::answer::Answer;
+use crate::input::InputSource;
+use crate::selection::{Part, FIRST_DAY, LAST_DAY};
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+
+// manifest of expected answers, placed next to the puzzle inputs
+pub const FILE_NAME: &str = "answers.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    // contains expected answer
+    Fail(Answer),
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail(_) => write!(f, "fail"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    answers: HashMap<(u8, Part), Answer>,
+}
+
+fn parse_day_key(key: &str) -> Result<u8> {
+    let day = key
+        .strip_prefix("day_")
+        .and_then(|d| d.parse::<u8>().ok())
+        .ok_or(anyhow!("bad day key '{}', expected day_NN", key))?;
+    if !(FIRST_DAY..=LAST_DAY).contains(&day) {
+        return Err(anyhow!("day key '{}' out of range", key));
+    }
+    Ok(day)
+}
+
+pub fn parse_part_key(key: &str) -> Result<Part> {
+    key.strip_prefix("part_")
+        .ok_or(anyhow!("bad part key '{}', expected part_1 or part_2", key))
+        .and_then(Part::try_from)
+}
+
+// answer of table_key.part_key
+pub fn parse_answer(value: &toml::Value, table_key: &str, part_key: &str) -> Result<Answer> {
+    match value {
+        toml::Value::Integer(n) => Ok(Answer::Number(*n)),
+        toml::Value::String(t) => Ok(Answer::Text(t.to_owned())),
+        _ => Err(anyhow!(
+            "answer of {}.{} must be an integer or a string",
+            table_key,
+            part_key
+        )),
+    }
+}
+
+impl TryFrom<&str> for Manifest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let table = value.parse::<toml::Table>()?;
+        let mut answers = HashMap::new();
+        for (day_key, parts) in table.iter() {
+            let day = parse_day_key(day_key)?;
+            let parts = parts
+                .as_table()
+                .ok_or(anyhow!("'{}' must be a table of part answers", day_key))?;
+            for (part_key, answer) in parts.iter() {
+                let part = parse_part_key(part_key)?;
+                answers.insert((day, part), parse_answer(answer, day_key, part_key)?);
+            }
+        }
+        Ok(Self { answers })
+    }
+}
+
+impl Manifest {
+    pub fn embedded() -> Result<Self> {
+        Manifest::try_from(include_str!("../assets/answers.toml"))
+            .context("failed to parse embedded answers")
+    }
+    pub fn load(path: &Path) -> Result<Self> {
+        let manifest = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read answers file {}", path.display()))?;
+        Manifest::try_from(manifest.as_str())
+            .with_context(|| format!("failed to parse answers file {}", path.display()))
+    }
+    pub fn expected(&self, day: u8, part: Part) -> Option<&Answer> {
+        self.answers.get(&(day, part))
+    }
+    pub fn verify(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        match self.expected(day, part) {
+            // compare textual representation, since big numbers may be stored as text
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+// expected answers of an input source: an explicit answers file applies to all days,
+// otherwise embedded inputs use embedded answers and input files the answers.toml next to them
+#[derive(Debug, Clone)]
+pub struct Expectations {
+    input_source: InputSource,
+    embedded: Manifest,
+    // answers file of --answers
+    explicit: Option<Manifest>,
+    // answers.toml next to input files
+    local: Option<Manifest>,
+}
+
+impl Expectations {
+    pub fn new(input_source: &InputSource, answers: Option<&Path>) -> Result<Self> {
+        let explicit = answers.map(Manifest::load).transpose()?;
+        let local = match (&explicit, input_source.manifest_path()) {
+            (None, Some(path)) => Some(Manifest::load(&path)?),
+            _ => None,
+        };
+        Ok(Self {
+            input_source: input_source.clone(),
+            embedded: Manifest::embedded()?,
+            explicit,
+            local,
+        })
+    }
+    fn manifest(&self, day: u8) -> Option<&Manifest> {
+        match (&self.explicit, self.input_source.is_embedded(day)) {
+            (Some(explicit), _) => Some(explicit),
+            (None, true) => Some(&self.embedded),
+            (None, false) => self.local.as_ref(),
+        }
+    }
+    pub fn verify(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        self.manifest(day)
+            .map(|m| m.verify(day, part, answer))
+            .unwrap_or(Verdict::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_manifest() -> Result<()> {
+        let manifest = Manifest::try_from(
+            "[day_05]\npart_1 = \"CMZ\"\n\n[day_10]\npart_1 = 13_140\npart_2 = '''\n#.\n.#'''\n",
+        )?;
+        assert_eq!(manifest.expected(5, Part::One), Some(&Answer::from("CMZ")));
+        assert_eq!(
+            manifest.expected(10, Part::Two),
+            Some(&Answer::from("#.\n.#"))
+        );
+        assert_eq!(
+            manifest.verify(10, Part::One, &Answer::from(13_140)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            manifest.verify(5, Part::One, &Answer::from("MCD")),
+            Verdict::Fail(Answer::from("CMZ"))
+        );
+        assert_eq!(
+            manifest.verify(5, Part::Two, &Answer::from("MCD")),
+            Verdict::Unknown
+        );
+
+        for bad_manifest in [
+            "[day_26]\npart_1 = 1",
+            "[day_01]\npart_3 = 1",
+            "[day_01]\npart_1 = 1.5",
+            "day_01 = 1",
+        ] {
+            assert!(
+                Manifest::try_from(bad_manifest).is_err(),
+                "{}",
+                bad_manifest
+            );
+        }
+
+        let manifest = Manifest::embedded()?;
+        assert_eq!(manifest.answers.len(), 49);
+        assert_eq!(
+            manifest.expected(25, Part::One),
+            Some(&Answer::from("20=02=120-=-2110-0=1"))
+        );
+
+        // days without input file in dir fall back to embedded input and answers
+        let dir = std::env::temp_dir().join(format!("aoc_2022_manifest_{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(dir.join("day_01.txt"), "1\n")?;
+        std::fs::write(
+            dir.join(FILE_NAME),
+            "[day_01]\npart_1 = 1\n[day_25]\npart_1 = \"1\"\n",
+        )?;
+        let expectations = Expectations::new(&InputSource::Dir(dir.clone()), None)?;
+        assert_eq!(
+            expectations.verify(1, Part::One, &Answer::from(1)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            expectations.verify(25, Part::One, &Answer::from("20=02=120-=-2110-0=1")),
+            Verdict::Pass
+        );
+        let expectations =
+            Expectations::new(&InputSource::Dir(dir.clone()), Some(&dir.join(FILE_NAME)))?;
+        assert_eq!(
+            expectations.verify(25, Part::One, &Answer::from("1")),
+            Verdict::Pass
+        );
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
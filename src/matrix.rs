@@ -0,0 +1,197 @@
+//!matrix.rs
+
+use crate::manifest::Verdict;
+use crate::report::{Outcome, Record};
+use crate::selection::Part;
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// inputs and answers.toml of one account, e.g. DIR/alice/day_NN.txt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSet {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+// sub directories of dir sorted by name, e.g. the cache dir of --fetch with one per account
+pub fn input_sets(dir: &Path) -> Result<Vec<InputSet>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read input sets of {}", dir.display()))?;
+    let mut sets: Vec<InputSet> = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            sets.push(InputSet {
+                name: name.to_string(),
+                dir: path.clone(),
+            });
+        }
+    }
+    if sets.is_empty() {
+        return Err(anyhow!("no input sets in {}", dir.display()));
+    }
+    sets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(sets)
+}
+
+// records of parts by input set; parts without input in a set have no record
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    sets: Vec<String>,
+    rows: Vec<(u8, Part)>,
+    cells: HashMap<(usize, u8, Part), Record>,
+}
+
+// cell of parts without input of set
+const NO_INPUT: &str = "-";
+
+impl Matrix {
+    pub fn new(sets: Vec<String>, rows: Vec<(u8, Part)>) -> Self {
+        Self {
+            sets,
+            rows,
+            cells: HashMap::new(),
+        }
+    }
+    // record of set with index set
+    pub fn insert(&mut self, set: usize, record: Record) {
+        self.cells.insert((set, record.day, record.part), record);
+    }
+    pub fn get(&self, set: usize, day: u8, part: Part) -> Option<&Record> {
+        self.cells.get(&(set, day, part))
+    }
+    // failed cells by rows and sets, e.g. ("bob", record of day 15 part 1)
+    pub fn failures(&self) -> Vec<(&str, &Record)> {
+        let mut failures = Vec::new();
+        for (day, part) in self.rows.iter() {
+            for (set, name) in self.sets.iter().enumerate() {
+                if let Some(record) = self.get(set, *day, *part) {
+                    if record.is_failure() {
+                        failures.push((name.as_str(), record));
+                    }
+                }
+            }
+        }
+        failures
+    }
+    // grid of status of parts by input sets, followed by the reason of each failure
+    pub fn render(&self) -> String {
+        let width = self
+            .sets
+            .iter()
+            .map(|name| name.len())
+            .chain(["timed out".len()])
+            .max()
+            .unwrap_or(0);
+        let mut lines: Vec<String> = Vec::new();
+        let header: Vec<String> = self
+            .sets
+            .iter()
+            .map(|name| format!("{:<width$}", name))
+            .collect();
+        lines.push(format!("     {}", header.join(" ")));
+        for (day, part) in self.rows.iter() {
+            let cells: Vec<String> = (0..self.sets.len())
+                .map(|set| {
+                    let status = self
+                        .get(set, *day, *part)
+                        .map_or(NO_INPUT.to_string(), |r| r.status());
+                    format!("{:<width$}", status)
+                })
+                .collect();
+            lines.push(format!("{:02}:{} {}", day, part, cells.join(" ")));
+        }
+        for (name, record) in self.failures() {
+            let reason = match &record.outcome {
+                Outcome::Solved(answer, Some(Verdict::Fail(expected))) => {
+                    format!("answer {}, expected {}", answer, expected)
+                }
+                Outcome::Failed(message) => message.clone(),
+                Outcome::TimedOut(budget) => format!("budget of {:?} exceeded", budget),
+                Outcome::Solved(..) | Outcome::Skipped(_) => continue,
+            };
+            lines.push(format!(
+                "{} {:02}:{}: {}",
+                name, record.day, record.part, reason
+            ));
+        }
+        // lines of grid end with padding of last column
+        lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::answer::Answer;
+    use crate::cli::Options;
+    use std::time::Duration;
+
+    #[test]
+    fn test_matrix() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc_2022_matrix_{}", std::process::id()));
+        let example = include_str!("../assets/day_01_example.txt");
+        std::fs::create_dir_all(dir.join("bob"))?;
+        std::fs::create_dir_all(dir.join("alice"))?;
+        std::fs::write(dir.join("alice/day_01.txt"), example)?;
+        std::fs::write(
+            dir.join("alice/answers.toml"),
+            "[day_01]\npart_1 = 24000\npart_2 = 45000\n",
+        )?;
+        std::fs::write(dir.join("bob/day_01.txt"), example)?;
+        std::fs::write(dir.join("bob/answers.toml"), "[day_01]\npart_1 = 24001\n")?;
+        std::fs::write(dir.join("bob/day_02.txt"), "A X\nA Q\n")?;
+        std::fs::write(dir.join("results.jsonl"), "")?;
+
+        let sets = input_sets(&dir)?;
+        let names: Vec<&str> = sets.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["alice", "bob"]);
+        let options = Options::parse(["--no-cache", "1", "2"])?;
+        let matrix = crate::solve_matrix(&options, &sets)?;
+        assert_eq!(
+            matrix.render(),
+            "     alice     bob\n\
+             01:1 pass      fail\n\
+             01:2 pass      unknown\n\
+             02:1 -         error\n\
+             02:2 -         error\n\
+             bob 01:1: answer 24000, expected 24001\n\
+             bob 02:1: failed to parse input: day 02 line 2 column 3: expected A, B, C, X, Y or Z, found 'Q'\n\
+             bob 02:2: failed to parse input: day 02 line 2 column 3: expected A, B, C, X, Y or Z, found 'Q'"
+        );
+        assert_eq!(matrix.failures().len(), 3);
+        // day 1 does not poll its budget, its late answers are kept
+        let options = Options::parse(["--no-cache", "--budget", "0", "1:1"])?;
+        let matrix = crate::solve_matrix(&options, &sets)?;
+        assert_eq!(
+            matrix.get(0, 1, Part::One).map(|r| r.status()),
+            Some("pass".into())
+        );
+        std::fs::remove_dir_all(&dir)?;
+        assert!(input_sets(&dir).is_err());
+
+        let mut matrix = Matrix::new(vec!["carol".into()], vec![(19, Part::Two)]);
+        let budget = Duration::from_secs(2);
+        matrix.insert(
+            0,
+            Record::new(19, Part::Two, budget, Outcome::TimedOut(budget)),
+        );
+        assert_eq!(
+            matrix.render(),
+            "     carol\n19:2 timed out\ncarol 19:2: budget of 2s exceeded"
+        );
+        let solved = Outcome::Solved(Answer::from(1), None);
+        matrix.insert(0, Record::new(19, Part::Two, budget, solved));
+        assert!(matrix.failures().is_empty());
+        Ok(())
+    }
+}
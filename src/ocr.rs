@@ -0,0 +1,237 @@
+//!ocr.rs
+
+use crate::grid::Grid;
+use anyhow::{anyhow, Result};
+use my_lib::my_map_two_dim::MyMap2D;
+
+// glyphs of the AoC font are 4 pixels wide and 6 pixels high, followed by one dark column
+pub const GLYPH_WIDTH: usize = 4;
+pub const GLYPH_HEIGHT: usize = 6;
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+// letters of the font, rows of pixels from top to bottom
+pub const FONT: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...", "#...", ".#.#", "..#.", "..#.", "..#."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+// image of lit and dark pixels, e.g. a CRT
+pub trait Pixels {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn is_lit(&self, x: usize, y: usize) -> bool;
+}
+
+// '#' is lit
+impl<const X: usize, const Y: usize> Pixels for MyMap2D<char, X, Y> {
+    fn width(&self) -> usize {
+        X
+    }
+    fn height(&self) -> usize {
+        Y
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        *self.get((x, y).into()) == '#'
+    }
+}
+
+impl<const X: usize, const Y: usize> Pixels for MyMap2D<bool, X, Y> {
+    fn width(&self) -> usize {
+        X
+    }
+    fn height(&self) -> usize {
+        Y
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        *self.get((x, y).into())
+    }
+}
+
+// '#' is lit
+impl Pixels for Grid<char> {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        self[(x, y)] == '#'
+    }
+}
+
+impl Pixels for Grid<bool> {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        self[(x, y)]
+    }
+}
+
+// rows of pixels, shorter rows are dark at their end
+impl Pixels for [Vec<bool>] {
+    fn width(&self) -> usize {
+        self.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+    fn height(&self) -> usize {
+        self.len()
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        self[y].get(x).copied().unwrap_or(false)
+    }
+}
+
+// lines of text, '#' is lit
+impl Pixels for str {
+    fn width(&self) -> usize {
+        self.lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+    fn height(&self) -> usize {
+        self.lines().count()
+    }
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.lines().nth(y).and_then(|line| line.chars().nth(x)) == Some('#')
+    }
+}
+
+// rows of glyph starting at column x
+fn glyph_pattern(pixels: &(impl Pixels + ?Sized), x: usize) -> Vec<String> {
+    (0..GLYPH_HEIGHT)
+        .map(|y| {
+            (x..x + GLYPH_WIDTH)
+                .map(|x| {
+                    if x < pixels.width() && pixels.is_lit(x, y) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// letters of image, which are one glyph high; fails on unknown glyphs with their pattern
+pub fn recognize(pixels: &(impl Pixels + ?Sized)) -> Result<String> {
+    if pixels.height() != GLYPH_HEIGHT {
+        return Err(anyhow!(
+            "letters are {} pixels high, image has {} rows",
+            GLYPH_HEIGHT,
+            pixels.height()
+        ));
+    }
+    let mut letters = String::new();
+    for x in (0..pixels.width()).step_by(GLYPH_SPACING) {
+        let pattern = glyph_pattern(pixels, x);
+        let letter = FONT
+            .iter()
+            .find(|(_, glyph)| glyph.iter().zip(pattern.iter()).all(|(g, p)| g == p))
+            .map(|(letter, _)| *letter);
+        match letter {
+            Some(letter) => letters.push(letter),
+            // trailing columns without a lit pixel are no glyph
+            None if pattern.iter().all(|row| !row.contains('#')) => (),
+            None => {
+                return Err(anyhow!(
+                    "unknown glyph at column {}:\n{}",
+                    x,
+                    pattern.join("\n")
+                ))
+            }
+        }
+    }
+    Ok(letters)
+}
+
+// image of letters, which are all part of FONT
+pub fn render(letters: &str) -> Result<String> {
+    let mut rows: Vec<String> = vec![String::new(); GLYPH_HEIGHT];
+    for letter in letters.chars() {
+        let (_, glyph) = FONT
+            .iter()
+            .find(|(l, _)| *l == letter)
+            .ok_or(anyhow!("letter '{}' is not part of font", letter))?;
+        for (row, glyph_row) in rows.iter_mut().zip(glyph.iter()) {
+            row.push_str(glyph_row);
+            row.push('.');
+        }
+    }
+    Ok(rows.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_recognize() -> Result<()> {
+        let image = "###...##..####.####.#..#.#..#.###..#..#.\n\
+                     #..#.#..#....#.#....#..#.#..#.#..#.#.#..\n\
+                     #..#.#......#..###..####.#..#.#..#.##...\n\
+                     ###..#.##..#...#....#..#.#..#.###..#.#..\n\
+                     #.#..#..#.#....#....#..#.#..#.#.#..#.#..\n\
+                     #..#..###.####.####.#..#..##..#..#.#..#.";
+        assert_eq!(recognize(image)?, "RGZEHURK");
+        let letters: String = FONT.iter().map(|(letter, _)| *letter).collect();
+        assert_eq!(recognize(render(&letters)?.as_str())?, letters);
+
+        let bools: Vec<Vec<bool>> = image
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+        assert_eq!(recognize(&bools[..])?, "RGZEHURK");
+        let grid = Grid::parse(image, |c, _| Ok(c))?;
+        assert_eq!(recognize(&grid)?, "RGZEHURK");
+        let mut map = MyMap2D::<bool, 10, 6>::default();
+        for (x, y) in [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (0, 1),
+            (0, 2),
+            (1, 2),
+            (2, 2),
+        ] {
+            map.set((x, y).into(), true);
+        }
+        for y in 3..6 {
+            map.set((0, y).into(), true);
+        }
+        assert_eq!(recognize(&map)?, "F");
+
+        let unknown = image.replacen("###...##", "###..###", 1);
+        let err = recognize(unknown.as_str()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown glyph at column 5:\n###.\n#..#\n#...\n#.##\n#..#\n.###"
+        );
+        assert!(recognize("####").is_err());
+        assert!(render("RX").is_err());
+        Ok(())
+    }
+}
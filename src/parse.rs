@@ -0,0 +1,297 @@
+//!parse.rs
+
+use anyhow::Context;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::str::FromStr;
+
+// malformed puzzle input with location, line and column start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    // empty, if end of input was found
+    pub found: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {:02} ", day)?;
+        }
+        write!(
+            f,
+            "line {} column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.found.is_empty() {
+            write!(f, "end of input")
+        } else {
+            write!(f, "'{}'", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// line and column of sub, which must be a sub slice of text
+fn locate(text: &str, sub: &str) -> (usize, usize) {
+    let start = text.as_ptr() as usize;
+    let offset = (sub.as_ptr() as usize)
+        .checked_sub(start)
+        .filter(|offset| *offset <= text.len())
+        .unwrap_or(0);
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+    (line, column)
+}
+
+// found tokens are cut at end of line to keep messages readable
+fn found_token(token: &str) -> String {
+    token.lines().next().unwrap_or_default().to_string()
+}
+
+impl ParseError {
+    // token is a sub slice of text, which is the input of the current parser
+    pub fn new(text: &str, token: &str, expected: impl Into<String>) -> Self {
+        let (line, column) = locate(text, token);
+        Self {
+            day: None,
+            line,
+            column,
+            expected: expected.into(),
+            found: found_token(token),
+        }
+    }
+    // end of text reached while expecting more input
+    pub fn end_of(text: &str, expected: impl Into<String>) -> Self {
+        ParseError::new(text, &text[text.len()..], expected)
+    }
+    // translates location of error in inner to location in outer,
+    // inner must be a sub slice of outer
+    pub fn within(mut self, outer: &str, inner: &str) -> Self {
+        let (line, column) = locate(outer, inner);
+        if self.line == 1 {
+            self.column += column - 1;
+        }
+        self.line += line - 1;
+        self
+    }
+    // moves error of a single line to line number of input, which starts at 1
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line += line - 1;
+        self
+    }
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+pub fn number<T: FromStr>(text: &str, token: &str) -> Result<T, ParseError> {
+    let token = token.trim();
+    token
+        .parse::<T>()
+        .map_err(|_| ParseError::new(text, token, "number"))
+}
+
+pub fn split_once<'a>(
+    text: &str,
+    token: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    token
+        .split_once(delimiter)
+        .ok_or_else(|| ParseError::new(text, token, format!("'{}'", delimiter)))
+}
+
+pub fn strip_prefix<'a>(text: &str, token: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    token
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseError::new(text, token, format!("'{}'", prefix)))
+}
+
+pub fn next_token<'a>(
+    text: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+    expected: &str,
+) -> Result<&'a str, ParseError> {
+    tokens
+        .next()
+        .ok_or_else(|| ParseError::end_of(text, expected))
+}
+
+pub fn next_number<'a, T: FromStr>(
+    text: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<T, ParseError> {
+    number(text, next_token(text, tokens, "number")?)
+}
+
+pub fn keyword<'a>(
+    text: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+    word: &str,
+) -> Result<(), ParseError> {
+    let expected = format!("'{}'", word);
+    match next_token(text, tokens, &expected)? {
+        token if token == word => Ok(()),
+        token => Err(ParseError::new(text, token, expected)),
+    }
+}
+
+// parses token with TryFrom of T, errors are located in text
+pub fn parse_token<'a, T>(text: &str, token: &'a str) -> Result<T, ParseError>
+where
+    T: TryFrom<&'a str, Error = ParseError>,
+{
+    T::try_from(token).map_err(|err| err.within(text, token))
+}
+
+pub fn single_char(text: &str, token: &str, expected: &str) -> Result<char, ParseError> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(ParseError::new(text, token, expected)),
+    }
+}
+
+// cells of a character grid as (x, y), char and token of char in text
+pub fn grid_cells(text: &str) -> impl Iterator<Item = ((usize, usize), char, &str)> {
+    text.lines().enumerate().flat_map(|(y, line)| {
+        line.char_indices()
+            .enumerate()
+            .map(move |(x, (i, c))| ((x, y), c, &line[i..i + c.len_utf8()]))
+    })
+}
+
+// checks that grid cell is inside of grid with size x_size * y_size
+pub fn check_grid_size(
+    text: &str,
+    token: &str,
+    (x, y): (usize, usize),
+    (x_size, y_size): (usize, usize),
+) -> Result<(), ParseError> {
+    if x < x_size && y < y_size {
+        return Ok(());
+    }
+    Err(ParseError::new(
+        text,
+        token,
+        format!("grid of at most {} columns and {} lines", x_size, y_size),
+    ))
+}
+
+// parses each line of input, errors are located in input
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    parse_line: impl Fn(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse_line(line).map_err(|err| err.within(input, line)))
+        .collect()
+}
+
+// parses each line of reader without holding more than one line, errors are located in input
+pub fn read_lines(
+    reader: &mut dyn BufRead,
+    mut parse_line: impl FnMut(&str) -> Result<(), ParseError>,
+) -> anyhow::Result<()> {
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_line(&mut line)
+            .context("failed to read input")?;
+        if read == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+        // same line endings as str::lines()
+        let text = line.strip_suffix('\n').unwrap_or(&line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        parse_line(text).map_err(|err| err.at_line(line_number))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_error_location() {
+        let input = "move 1 from 2 to 3\nmove x from 2 to 3";
+        let parse_line = |line: &str| -> Result<u32, ParseError> {
+            let mut tokens = line.split_whitespace().skip(1);
+            number(line, next_token(line, &mut tokens, "number")?)
+        };
+        assert_eq!(
+            parse_lines(input, parse_line),
+            Err(ParseError {
+                day: None,
+                line: 2,
+                column: 6,
+                expected: "number".into(),
+                found: "x".into(),
+            })
+        );
+        assert_eq!(
+            parse_lines(input, parse_line)
+                .unwrap_err()
+                .in_day(5)
+                .to_string(),
+            "day 05 line 2 column 6: expected number, found 'x'"
+        );
+
+        let mut numbers: Vec<u32> = Vec::new();
+        let err = read_lines(&mut input.as_bytes(), |line| {
+            numbers.push(parse_line(line)?);
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(numbers, [1]);
+        assert_eq!(
+            err.to_string(),
+            "line 2 column 6: expected number, found 'x'"
+        );
+
+        let err = parse_lines("move 1\nmove", parse_line).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 5));
+        assert_eq!(
+            err.to_string(),
+            "line 2 column 5: expected number, found end of input"
+        );
+
+        // nested parsers
+        let block = "Monkey 0:\n  Test: divisible by 1x";
+        let line = block.lines().nth(1).unwrap();
+        let value = line.trim().rsplit(' ').next().unwrap();
+        let err = number::<u64>(line, value).unwrap_err().within(block, line);
+        assert_eq!((err.line, err.column), (2, 22));
+        assert_eq!(split_once(block, block, "=").unwrap_err().column, 1);
+        assert_eq!(single_char(line, "ab", "letter").unwrap_err().found, "ab");
+
+        let line = "move 1 form 2";
+        let mut tokens = line.split_whitespace();
+        assert!(keyword(line, &mut tokens, "move").is_ok());
+        assert_eq!(next_number::<u8>(line, &mut tokens), Ok(1));
+        let err = keyword(line, &mut tokens, "from").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1 column 8: expected 'from', found 'form'"
+        );
+
+        let grid = "ab\ncde";
+        let cells: Vec<((usize, usize), char)> = grid_cells(grid).map(|(p, c, _)| (p, c)).collect();
+        assert_eq!(cells[3], ((1, 1), 'd'));
+        let (position, _, token) = grid_cells(grid).last().unwrap();
+        let err = check_grid_size(grid, token, position, (2, 2)).unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 3, "e"));
+    }
+}
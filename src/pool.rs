@@ -0,0 +1,83 @@
+//!pool.rs
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+// number of workers for --jobs 0
+pub fn available_workers() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+// runs work for all items on a pool of worker threads and hands the results to emit
+// in order of items, each as soon as all previous results have been emitted
+pub fn run_ordered<T, R, W, E>(workers: usize, items: &[T], work: W, mut emit: E)
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    E: FnMut(R),
+{
+    let workers = workers.clamp(1, items.len().max(1));
+    if workers == 1 {
+        items.iter().map(&work).for_each(emit);
+        return;
+    }
+    let next_item = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel::<(usize, R)>();
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (next_item, work) = (&next_item, &work);
+            scope.spawn(move || loop {
+                let index = next_item.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+        let mut pending: BTreeMap<usize, R> = BTreeMap::new();
+        let mut next_emit: usize = 0;
+        for (index, result) in receiver.iter() {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&next_emit) {
+                emit(result);
+                next_emit += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        for workers in [1, 4, 50] {
+            let mut results: Vec<u64> = Vec::new();
+            run_ordered(
+                workers,
+                &items,
+                |i| {
+                    // later items finish first
+                    std::thread::sleep(Duration::from_millis(20 - i));
+                    i * i
+                },
+                |r| results.push(r),
+            );
+            let expected: Vec<u64> = items.iter().map(|i| i * i).collect();
+            assert_eq!(results, expected);
+        }
+        run_ordered(4, &[] as &[u64], |i| *i, |_| panic!("no items"));
+    }
+}
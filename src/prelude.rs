@@ -0,0 +1,66 @@
+//!prelude.rs
+//!
+//! Everything needed to solve puzzles from code, `use advent_of_code_2022::prelude::*;`.
+
+pub use crate::answer::Answer;
+pub use crate::days::day_01::Day01;
+pub use crate::days::day_02::Day02;
+pub use crate::days::day_03::Day03;
+pub use crate::days::day_04::Day04;
+pub use crate::days::day_05::Day05;
+pub use crate::days::day_06::Day06;
+pub use crate::days::day_07::Day07;
+pub use crate::days::day_08::Day08;
+pub use crate::days::day_09::Day09;
+pub use crate::days::day_10::Day10;
+pub use crate::days::day_11::Day11;
+pub use crate::days::day_12::Day12;
+pub use crate::days::day_13::Day13;
+pub use crate::days::day_14::Day14;
+pub use crate::days::day_15::Day15;
+pub use crate::days::day_16::Day16;
+pub use crate::days::day_17::Day17;
+pub use crate::days::day_18::Day18;
+pub use crate::days::day_19::Day19;
+pub use crate::days::day_20::Day20;
+pub use crate::days::day_21::Day21;
+pub use crate::days::day_22::Day22;
+pub use crate::days::day_23::Day23;
+pub use crate::days::day_24::Day24;
+pub use crate::days::day_25::Day25;
+pub use crate::days::{get, registry};
+pub use crate::parse::ParseError;
+pub use crate::selection::Part;
+pub use crate::solution::{DynSolution, Params, Solution};
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days::day_13::Packet;
+    use crate::days::day_17::Chamber;
+    use crate::days::day_25::Base5Num;
+    use anyhow::Result;
+
+    #[test]
+    fn test_prelude() -> Result<()> {
+        let input = include_str!("../assets/day_01_example.txt");
+        let parsed = Day01::parse(input)?;
+        assert_eq!(Day01::part_one(&parsed)?, 24_000);
+        assert_eq!(Day01::part_two(&parsed)?, 45_000);
+        let day_01 = get(1).unwrap();
+        let answer = day_01.solve(day_01.parse(input)?.as_ref(), Part::One)?;
+        assert_eq!(answer.to_string(), "24000");
+
+        let packet = Packet::try_from("[1,[2,[3,[4,[5,6,7]]]],8,9]")?;
+        assert_eq!(packet.to_string(), "[1,[2,[3,[4,[5,6,7]]]],8,9]");
+
+        let snafu = Base5Num::try_from("1121-1110-1=0")?;
+        assert_eq!(i64::from(&snafu), 314_159_265);
+        assert_eq!(Base5Num::from(2022).to_string(), "1=11-2");
+
+        let jet_streams = include_str!("../assets/day_17_example.txt").trim_end();
+        assert_eq!(Chamber::tower_height(jet_streams, 2022)?, 3_068);
+        Ok(())
+    }
+}
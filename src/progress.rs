@@ -0,0 +1,258 @@
+//!progress.rs
+
+use std::cell::{Cell, RefCell};
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// snapshot of a long running search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub label: String,
+    pub states: u64,
+    // number of states of searches with known size, e.g. rounds of day 11
+    pub total: Option<u64>,
+    pub cache_size: Option<usize>,
+    pub best: Option<i64>,
+    pub elapsed: Duration,
+    // last report of search
+    pub finished: bool,
+}
+
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} states", self.label, self.states)?;
+        if let Some(total) = self.total {
+            write!(f, " of {}", total)?;
+        }
+        if let Some(cache_size) = self.cache_size {
+            write!(f, ", cache {}", cache_size)?;
+        }
+        if let Some(best) = self.best {
+            write!(f, ", best {}", best)?;
+        }
+        write!(f, ", {:.1?}", self.elapsed)
+    }
+}
+
+// receives progress of searches of threads, which run with it as current sink
+pub trait ProgressSink: Send + Sync {
+    fn report(&self, progress: &Progress);
+}
+
+// collects reports, e.g. for tests
+impl ProgressSink for Mutex<Vec<Progress>> {
+    fn report(&self, progress: &Progress) {
+        self.lock().unwrap().push(progress.clone());
+    }
+}
+
+// draws progress as single line on stderr, which is cleared when search is finished
+#[derive(Debug, Default)]
+pub struct ProgressLine;
+
+impl ProgressSink for ProgressLine {
+    fn report(&self, progress: &Progress) {
+        let mut stderr = std::io::stderr().lock();
+        // errors of stderr are ignored like those of eprint!()
+        let _ = if progress.finished {
+            write!(stderr, "\r\x1b[2K")
+        } else {
+            write!(stderr, "\r\x1b[2K{}", progress)
+        };
+        let _ = stderr.flush();
+    }
+}
+
+// searches report at most this often, quick searches do not report at all
+const REPORT_INTERVAL: Duration = Duration::from_millis(200);
+// reading the clock on every state slows down hot loops
+const CLOCK_INTERVAL: u32 = 1024;
+
+struct Search {
+    progress: Progress,
+    start: Instant,
+    last_report: Instant,
+    reported: bool,
+}
+
+thread_local! {
+    static CURRENT_SINK: RefCell<Option<Arc<dyn ProgressSink>>> = const { RefCell::new(None) };
+    static SEARCH: RefCell<Option<Search>> = const { RefCell::new(None) };
+    static STATES_UNTIL_CLOCK: Cell<u32> = const { Cell::new(CLOCK_INTERVAL) };
+}
+
+// restores previous sink of thread, even if solver panics
+struct RestoreSink(Option<Arc<dyn ProgressSink>>);
+
+impl Drop for RestoreSink {
+    fn drop(&mut self) {
+        CURRENT_SINK.with(|current| current.replace(self.0.take()));
+    }
+}
+
+// runs f with sink as current sink of this thread; without sink searches are silent
+pub fn with_sink<T>(sink: &Arc<dyn ProgressSink>, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT_SINK.with(|current| current.replace(Some(sink.clone())));
+    let _restore = RestoreSink(previous);
+    f()
+}
+
+// search of thread, which ends when dropped
+pub struct SearchGuard(());
+
+impl Drop for SearchGuard {
+    fn drop(&mut self) {
+        let Some(mut search) = SEARCH.with(|s| s.take()) else {
+            return;
+        };
+        if search.reported {
+            search.progress.elapsed = search.start.elapsed();
+            search.progress.finished = true;
+            report(&search.progress);
+        }
+    }
+}
+
+// starts search of label, e.g. "day 19 blueprint 2", which replaces running search of thread
+pub fn start(label: impl Into<String>) -> SearchGuard {
+    let has_sink = CURRENT_SINK.with(|current| current.borrow().is_some());
+    let search = has_sink.then(|| {
+        let now = Instant::now();
+        Search {
+            progress: Progress {
+                label: label.into(),
+                states: 0,
+                total: None,
+                cache_size: None,
+                best: None,
+                elapsed: Duration::ZERO,
+                finished: false,
+            },
+            start: now,
+            last_report: now,
+            reported: false,
+        }
+    });
+    SEARCH.with(|s| s.replace(search));
+    STATES_UNTIL_CLOCK.with(|count| count.set(CLOCK_INTERVAL));
+    SearchGuard(())
+}
+
+fn report(progress: &Progress) {
+    CURRENT_SINK.with(|current| {
+        if let Some(sink) = current.borrow().as_ref() {
+            sink.report(progress);
+        }
+    });
+}
+
+fn update(f: impl FnOnce(&mut Progress)) {
+    SEARCH.with(|s| {
+        if let Some(search) = s.borrow_mut().as_mut() {
+            f(&mut search.progress);
+        }
+    });
+}
+
+// counts one explored state and reports progress from time to time
+pub fn state() {
+    let read_clock = STATES_UNTIL_CLOCK.with(|count| match count.get() {
+        0 => {
+            count.set(CLOCK_INTERVAL - 1);
+            true
+        }
+        n => {
+            count.set(n - 1);
+            false
+        }
+    });
+    SEARCH.with(|s| {
+        let mut search = s.borrow_mut();
+        let Some(search) = search.as_mut() else {
+            return;
+        };
+        search.progress.states += 1;
+        if read_clock && search.last_report.elapsed() >= REPORT_INTERVAL {
+            search.last_report = Instant::now();
+            search.reported = true;
+            search.progress.elapsed = search.start.elapsed();
+            report(&search.progress);
+        }
+    });
+}
+
+pub fn total(total: u64) {
+    update(|progress| progress.total = Some(total));
+}
+
+pub fn cache_size(cache_size: usize) {
+    update(|progress| progress.cache_size = Some(cache_size));
+}
+
+// best so far is the maximum of all values of search
+pub fn best(value: i64) {
+    update(|progress| progress.best = Some(progress.best.map_or(value, |b| b.max(value))));
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn search(states: u64) {
+        let _search = start("test search");
+        total(states);
+        for state_number in 0..states {
+            state();
+            cache_size(state_number as usize);
+            best(state_number as i64);
+        }
+    }
+
+    #[test]
+    fn test_progress() {
+        // silent without sink
+        search(1_000);
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink: Arc<dyn ProgressSink> = reports.clone();
+        // quick searches do not report
+        with_sink(&sink, || search(1_000));
+        assert!(reports.lock().unwrap().is_empty());
+
+        with_sink(&sink, || {
+            let _search = start("test search");
+            best(3);
+            best(1);
+            for _ in 0..CLOCK_INTERVAL {
+                state();
+            }
+            std::thread::sleep(REPORT_INTERVAL);
+            for _ in 0..CLOCK_INTERVAL {
+                state();
+            }
+        });
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].best, Some(3));
+        assert!(!reports[0].finished && reports[1].finished);
+        assert_eq!(reports[1].states, 2 * CLOCK_INTERVAL as u64);
+        assert!(reports[1].elapsed >= REPORT_INTERVAL);
+        let progress = Progress {
+            total: Some(10_000),
+            cache_size: Some(12),
+            elapsed: Duration::from_millis(1_500),
+            ..reports[0].clone()
+        };
+        assert_eq!(
+            progress.to_string(),
+            format!(
+                "test search: {} states of 10000, cache 12, best 3, 1.5s",
+                progress.states
+            )
+        );
+        // sink is only current inside of with_sink()
+        assert!(CURRENT_SINK.with(|current| current.borrow().is_none()));
+    }
+}
@@ -0,0 +1,264 @@
+//!repl.rs
+
+use crate::days;
+use crate::input::InputSource;
+use crate::selection::Part;
+use crate::solution::{catch_panic, DynSolution};
+use anyhow::{anyhow, Context, Result};
+use std::any::Any;
+use std::io::{BufRead, Write};
+
+// usage and description of a command, e.g. ("step [N]", "moves N rounds, default 1")
+pub type Command = (&'static str, &'static str);
+
+// interactive view of the puzzle state of a day, created from its parsed input
+pub trait Explore {
+    // day specific commands, name of command is first word of usage
+    fn commands(&self) -> &'static [Command];
+    // output of command; errors are printed and do not end the session
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<String>;
+}
+
+const COMMANDS: &[Command] = &[
+    (
+        "load DAY [FILE]",
+        "parses input of DAY, default is puzzle input",
+    ),
+    ("solve [PART]", "solves loaded input, default are all parts"),
+    ("reset", "restores puzzle state of loaded input"),
+    ("help", "lists commands"),
+    ("quit", "ends session"),
+];
+
+// parses n-th argument of a command or returns default, if it is missing
+pub fn argument<T: std::str::FromStr>(args: &[&str], n: usize, default: Option<T>) -> Result<T> {
+    match args.get(n) {
+        Some(arg) => arg
+            .parse()
+            .map_err(|_| anyhow!("argument {} '{}' is not valid", n + 1, arg)),
+        None => default.ok_or(anyhow!("argument {} is missing", n + 1)),
+    }
+}
+
+struct Loaded {
+    solution: &'static dyn DynSolution,
+    parsed: Box<dyn Any>,
+    explorer: Option<Box<dyn Explore>>,
+}
+
+pub struct Repl {
+    source: InputSource,
+    loaded: Option<Loaded>,
+}
+
+impl Repl {
+    pub fn new(source: InputSource) -> Self {
+        Self {
+            source,
+            loaded: None,
+        }
+    }
+    pub fn prompt(&self) -> String {
+        match self.loaded.as_ref() {
+            Some(loaded) => format!("day {:02}> ", loaded.solution.day()),
+            None => String::from("> "),
+        }
+    }
+    // loads input of day from source and creates its puzzle state
+    pub fn load_source(&mut self, day: u8, source: &InputSource) -> Result<()> {
+        let input = source.load(day)?;
+        self.load(day, &input)
+    }
+    // parses input of day and creates its puzzle state
+    pub fn load(&mut self, day: u8, input: &str) -> Result<()> {
+        let solution = days::get(day).ok_or(anyhow!("day {} is not registered", day))?;
+        let parsed = catch_panic(|| solution.parse(input))?;
+        let explorer = solution.explore(parsed.as_ref()).ok();
+        self.loaded = Some(Loaded {
+            solution,
+            parsed,
+            explorer,
+        });
+        Ok(())
+    }
+    // output of command line, None ends the session
+    pub fn execute(&mut self, line: &str) -> Result<Option<String>> {
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            return Ok(Some(String::new()));
+        };
+        let args: Vec<&str> = words.collect();
+        let output = match command {
+            "quit" | "exit" => return Ok(None),
+            "help" => self.help(),
+            "load" => {
+                let day: u8 = argument(&args, 0, None)?;
+                let source = match args.get(1) {
+                    Some(path) => InputSource::from(*path),
+                    None => self.source.clone(),
+                };
+                self.load_source(day, &source)?;
+                format!("loaded day {:02}", day)
+            }
+            "solve" => {
+                let loaded = self.loaded()?;
+                let selected = args.first().map(|p| Part::try_from(*p)).transpose()?;
+                let mut answers: Vec<String> = Vec::new();
+                for part in loaded.solution.parts().iter() {
+                    if selected.is_none() || selected == Some(*part) {
+                        let answer =
+                            catch_panic(|| loaded.solution.solve(loaded.parsed.as_ref(), *part))?;
+                        answers.push(format!("part {}: {}", part, answer));
+                    }
+                }
+                answers.join("\n")
+            }
+            "reset" => {
+                let loaded = self.loaded.as_mut().ok_or(anyhow!("no day loaded"))?;
+                loaded.explorer = Some(loaded.solution.explore(loaded.parsed.as_ref())?);
+                String::from("puzzle state restored")
+            }
+            _ => {
+                let loaded = self
+                    .loaded
+                    .as_mut()
+                    .ok_or(anyhow!("unknown command '{}', see help", command))?;
+                let day = loaded.solution.day();
+                let explorer = loaded.explorer.as_mut().ok_or(anyhow!(
+                    "unknown command '{}', day {:02} has no commands",
+                    command,
+                    day
+                ))?;
+                if !explorer
+                    .commands()
+                    .iter()
+                    .any(|(usage, _)| usage.split_whitespace().next() == Some(command))
+                {
+                    return Err(anyhow!("unknown command '{}', see help", command));
+                }
+                catch_panic(|| explorer.execute(command, &args))?
+            }
+        };
+        Ok(Some(output))
+    }
+    fn loaded(&self) -> Result<&Loaded> {
+        self.loaded.as_ref().ok_or(anyhow!("no day loaded"))
+    }
+    fn help(&self) -> String {
+        let day_commands = self
+            .loaded
+            .as_ref()
+            .and_then(|l| l.explorer.as_ref())
+            .map_or(&[][..], |e| e.commands());
+        COMMANDS
+            .iter()
+            .chain(day_commands.iter())
+            .map(|(usage, description)| format!("{:<20}{}", usage, description))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// reads commands line by line until quit or end of input
+pub fn run_repl(repl: &mut Repl, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    write!(output, "{}", repl.prompt())?;
+    output.flush()?;
+    for line in input.lines() {
+        let line = line.context("failed to read command")?;
+        match repl.execute(&line) {
+            Ok(Some(text)) if text.is_empty() => (),
+            Ok(Some(text)) => writeln!(output, "{}", text)?,
+            Ok(None) => return Ok(()),
+            Err(err) => writeln!(output, "error: {:#}", err)?,
+        }
+        write!(output, "{}", repl.prompt())?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn run(repl: &mut Repl, commands: &str) -> Result<Vec<String>> {
+        let mut output: Vec<u8> = Vec::new();
+        run_repl(repl, commands.as_bytes(), &mut output)?;
+        Ok(String::from_utf8(output)?
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    #[test]
+    fn test_repl() -> Result<()> {
+        let mut repl = Repl::new(InputSource::Embedded);
+        assert_eq!(
+            run(&mut repl, "solve\nstep\n")?,
+            [
+                "> error: no day loaded",
+                "> error: unknown command 'step', see help",
+                "> "
+            ]
+        );
+        assert_eq!(repl.execute("quit")?, None);
+
+        repl.load(5, include_str!("../assets/day_05_example.txt"))?;
+        assert_eq!(repl.prompt(), "day 05> ");
+        let commands = "step\nstack 1\ntop\nstep 5\ntop\nstep\nreset\ncrane 9001\nstep 4\n\
+                        top\nsolve 2\nstack 4\nquit\nstep";
+        let output = run(&mut repl, commands)?;
+        let expected = [
+            "day 05> 1: move 1 from 2 to 1",
+            "day 05> 1: DNZ",
+            "day 05> DCP",
+            "day 05> 2: move 3 from 1 to 3",
+            "3: move 2 from 2 to 1",
+            "4: move 1 from 1 to 2",
+            "day 05> CMZ",
+            "day 05> all crane commands applied",
+            "day 05> puzzle state restored",
+            "day 05> CrateMover 9001 selected",
+            "day 05> 1: move 1 from 2 to 1",
+            "2: move 3 from 1 to 3",
+            "3: move 2 from 2 to 1",
+            "4: move 1 from 1 to 2",
+            "day 05> MCD",
+            "day 05> part 2: MCD",
+            "day 05> error: stack 4 does not exist",
+            "day 05> ",
+        ];
+        assert_eq!(output, expected);
+        assert!(repl.execute("help")?.unwrap().contains("crane 9000|9001"));
+
+        repl.load(21, include_str!("../assets/day_21_example.txt"))?;
+        let commands = "job root\neval root\nhumn\nset humn 301\neval pppw\neval sjmn\n\
+                        set humn x\neval abcd\ncrane 9000";
+        let output = run(&mut repl, commands)?;
+        let expected = [
+            "day 21> pppw + sjmn",
+            "day 21> 152",
+            "day 21> 301",
+            "day 21> humn: 301",
+            "day 21> 150",
+            "day 21> 150",
+            "day 21> error: argument 2 'x' is not valid",
+            "day 21> error: monkey abcd does not exist",
+            "day 21> error: unknown command 'crane', see help",
+            "day 21> ",
+        ];
+        assert_eq!(output, expected);
+
+        repl.load(1, include_str!("../assets/day_01_example.txt"))?;
+        assert_eq!(
+            repl.execute("step").unwrap_err().to_string(),
+            "unknown command 'step', day 01 has no commands"
+        );
+        assert_eq!(
+            repl.execute("solve")?.unwrap(),
+            "part 1: 24000\npart 2: 45000"
+        );
+        Ok(())
+    }
+}
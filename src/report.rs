@@ -0,0 +1,372 @@
+//!report.rs
+
+use crate::answer::Answer;
+use crate::manifest::Verdict;
+use crate::selection::Part;
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    // one json object per line
+    Json,
+    Csv,
+}
+
+impl TryFrom<&str> for Format {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(anyhow!(
+                "bad format '{}', expected text, json or csv",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    // verdict is only available in verify mode
+    Solved(Answer, Option<Verdict>),
+    Failed(String),
+    Skipped(String),
+    // stopped after exceeding its time budget
+    TimedOut(Duration),
+}
+
+// use of result cache by a record, None if cache is disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    // recomputed by --verify, which does not read cached answers
+    Refresh,
+}
+
+impl Display for CacheStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CacheStatus::Hit => write!(f, "hit"),
+            CacheStatus::Miss => write!(f, "miss"),
+            CacheStatus::Refresh => write!(f, "refresh"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub elapsed: Duration,
+    pub outcome: Outcome,
+    pub cache: Option<CacheStatus>,
+}
+
+impl Record {
+    pub fn new(day: u8, part: Part, elapsed: Duration, outcome: Outcome) -> Self {
+        Self {
+            day,
+            part,
+            elapsed,
+            outcome,
+            cache: None,
+        }
+    }
+    pub fn with_cache(mut self, cache: CacheStatus) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+    pub fn status(&self) -> String {
+        match &self.outcome {
+            Outcome::Solved(_, None) => "solved".into(),
+            Outcome::Solved(_, Some(verdict)) => verdict.to_string(),
+            Outcome::Failed(_) => "error".into(),
+            Outcome::Skipped(_) => "skipped".into(),
+            Outcome::TimedOut(_) => "timed out".into(),
+        }
+    }
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self.outcome,
+            Outcome::Failed(_) | Outcome::TimedOut(_) | Outcome::Solved(_, Some(Verdict::Fail(_)))
+        )
+    }
+    fn answer(&self) -> Option<&Answer> {
+        match &self.outcome {
+            Outcome::Solved(answer, _) => Some(answer),
+            _ => None,
+        }
+    }
+    fn expected(&self) -> Option<&Answer> {
+        match &self.outcome {
+            Outcome::Solved(_, Some(Verdict::Fail(expected))) => Some(expected),
+            _ => None,
+        }
+    }
+    fn message(&self) -> Option<String> {
+        match &self.outcome {
+            Outcome::Failed(message) | Outcome::Skipped(message) => Some(message.clone()),
+            Outcome::TimedOut(budget) => Some(format!("budget of {:?} exceeded", budget)),
+            Outcome::Solved(..) => None,
+        }
+    }
+    fn elapsed_us(&self) -> u64 {
+        self.elapsed.as_micros() as u64
+    }
+    // fields of json output, e.g. of --format json
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "day": self.day,
+            "part": u8::from(self.part),
+            "status": self.status(),
+            "answer": json_answer(self.answer()),
+            "expected": json_answer(self.expected()),
+            "elapsed_us": self.elapsed_us(),
+            "cache": self.cache.map(|cache| cache.to_string()),
+            "message": self.message(),
+        })
+    }
+}
+
+fn json_answer(answer: Option<&Answer>) -> serde_json::Value {
+    match answer {
+        Some(Answer::Number(n)) => serde_json::Value::from(*n),
+        Some(Answer::Text(t)) => serde_json::Value::from(t.as_str()),
+        None => serde_json::Value::Null,
+    }
+}
+
+// text answers are always quoted to keep them text, e.g. SNAFU numbers in spreadsheets
+fn csv_answer(answer: Option<&Answer>) -> String {
+    match answer {
+        Some(Answer::Number(n)) => n.to_string(),
+        Some(Answer::Text(t)) => csv_quote(t),
+        None => String::new(),
+    }
+}
+
+// quoting as defined by RFC 4180, line breaks are kept inside quotes
+fn csv_quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        csv_quote(field)
+    } else {
+        field.to_string()
+    }
+}
+
+const CSV_HEADER: &str = "day,part,status,answer,expected,elapsed_us,cache,message";
+
+pub struct Report<W: Write> {
+    format: Format,
+    out: W,
+    header_written: bool,
+}
+
+impl Report<std::io::Stdout> {
+    pub fn stdout(format: Format) -> Self {
+        Report::new(format, std::io::stdout())
+    }
+}
+
+impl<W: Write> Report<W> {
+    pub fn new(format: Format, out: W) -> Self {
+        Self {
+            format,
+            out,
+            header_written: false,
+        }
+    }
+    pub fn emit(&mut self, record: &Record) -> Result<()> {
+        match self.format {
+            Format::Text => self.emit_text(record)?,
+            Format::Json => self.emit_json(record)?,
+            Format::Csv => self.emit_csv(record)?,
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+    fn emit_text(&mut self, record: &Record) -> Result<()> {
+        let (day, part) = (record.day, record.part);
+        match &record.outcome {
+            Outcome::Solved(answer, verdict) => {
+                let mut verdict_str = verdict
+                    .as_ref()
+                    .map(|v| format!(" [{}]", v))
+                    .unwrap_or_default();
+                if record.cache == Some(CacheStatus::Hit) {
+                    verdict_str.push_str(" (cached)");
+                }
+                if answer.is_multi_line() {
+                    writeln!(
+                        self.out,
+                        "result day {:02} part {}{}:\n{}",
+                        day, part, verdict_str, answer
+                    )?;
+                } else {
+                    writeln!(
+                        self.out,
+                        "result day {:02} part {}: {}{}",
+                        day, part, answer, verdict_str
+                    )?;
+                }
+                if let Some(expected) = record.expected() {
+                    let seperator = if expected.is_multi_line() { "\n" } else { " " };
+                    writeln!(
+                        self.out,
+                        "expected day {:02} part {}:{}{}",
+                        day, part, seperator, expected
+                    )?;
+                }
+            }
+            Outcome::Failed(message) => {
+                writeln!(self.out, "day {:02} part {} failed: {}", day, part, message)?
+            }
+            Outcome::Skipped(reason) => writeln!(
+                self.out,
+                "day {:02} part {} skipped because of {}",
+                day, part, reason
+            )?,
+            Outcome::TimedOut(budget) => writeln!(
+                self.out,
+                "day {:02} part {} timed out after budget of {:?}",
+                day, part, budget
+            )?,
+        }
+        Ok(())
+    }
+    fn emit_json(&mut self, record: &Record) -> Result<()> {
+        writeln!(self.out, "{}", record.to_json())?;
+        Ok(())
+    }
+    fn emit_csv(&mut self, record: &Record) -> Result<()> {
+        if !self.header_written {
+            writeln!(self.out, "{}", CSV_HEADER)?;
+            self.header_written = true;
+        }
+        writeln!(
+            self.out,
+            "{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            record.status(),
+            csv_answer(record.answer()),
+            csv_answer(record.expected()),
+            record.elapsed_us(),
+            record
+                .cache
+                .map(|cache| cache.to_string())
+                .unwrap_or_default(),
+            record
+                .message()
+                .map(|message| csv_field(&message))
+                .unwrap_or_default()
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn emit_all(format: Format, records: &[Record]) -> Result<String> {
+        let mut report = Report::new(format, Vec::new());
+        for record in records.iter() {
+            report.emit(record)?;
+        }
+        Ok(String::from_utf8(report.out)?)
+    }
+
+    #[test]
+    fn test_structured_output() -> Result<()> {
+        let crt = Answer::from("##..\n#..#\n\"#\"");
+        let snafu = Answer::from("2=-1=0");
+        let records = [
+            Record::new(
+                10,
+                Part::Two,
+                Duration::from_micros(42),
+                Outcome::Solved(crt.clone(), Some(Verdict::Pass)),
+            )
+            .with_cache(CacheStatus::Refresh),
+            Record::new(
+                25,
+                Part::One,
+                Duration::from_micros(7),
+                Outcome::Solved(snafu.clone(), Some(Verdict::Fail(Answer::from("1=")))),
+            ),
+            Record::new(
+                11,
+                Part::Two,
+                Duration::ZERO,
+                Outcome::Skipped("long run time".into()),
+            ),
+            Record::new(
+                19,
+                Part::Two,
+                Duration::from_secs(2),
+                Outcome::TimedOut(Duration::from_secs(2)),
+            ),
+        ];
+
+        let json = emit_all(Format::Json, &records)?;
+        let lines: Vec<serde_json::Value> = json
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["answer"].as_str(), Some(crt.to_string().as_str()));
+        assert_eq!(lines[0]["status"], "pass");
+        assert_eq!(lines[0]["elapsed_us"], 42);
+        assert_eq!(lines[0]["cache"], "refresh");
+        assert_eq!(lines[1]["cache"], serde_json::Value::Null);
+        assert_eq!(lines[1]["answer"].as_str(), Some("2=-1=0"));
+        assert_eq!(lines[1]["expected"].as_str(), Some("1="));
+        assert_eq!(lines[1]["status"], "fail");
+        assert_eq!(lines[2]["answer"], serde_json::Value::Null);
+        assert_eq!(lines[2]["message"], "long run time");
+        assert_eq!(lines[3]["status"], "timed out");
+        assert_eq!(lines[3]["message"], "budget of 2s exceeded");
+
+        let csv = emit_all(Format::Csv, &records)?;
+        assert_eq!(
+            csv,
+            "day,part,status,answer,expected,elapsed_us,cache,message\n\
+             10,2,pass,\"##..\n#..#\n\"\"#\"\"\",,42,refresh,\n\
+             25,1,fail,\"2=-1=0\",\"1=\",7,,\n\
+             11,2,skipped,,,0,,long run time\n\
+             19,2,timed out,,,2000000,,budget of 2s exceeded\n"
+        );
+
+        let text = emit_all(Format::Text, &records[..1])?;
+        assert_eq!(text, format!("result day 10 part 2 [pass]:\n{}\n", crt));
+        let text = emit_all(Format::Text, &records[3..])?;
+        assert_eq!(text, "day 19 part 2 timed out after budget of 2s\n");
+        assert!(records[3].is_failure());
+        let hit = Record::new(
+            16,
+            Part::One,
+            Duration::ZERO,
+            Outcome::Solved(1651.into(), None),
+        )
+        .with_cache(CacheStatus::Hit);
+        let text = emit_all(Format::Text, &[hit])?;
+        assert_eq!(text, "result day 16 part 1: 1651 (cached)\n");
+        assert!(Format::try_from("xml").is_err());
+        Ok(())
+    }
+}
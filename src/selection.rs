@@ -0,0 +1,202 @@
+//!selection.rs
+
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+pub const FIRST_DAY: u8 = 1;
+pub const LAST_DAY: u8 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl TryFrom<&str> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow!("bad part '{}', expected 1 or 2", value)),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(value: Part) -> Self {
+        match value {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Part {
+    pub fn both() -> [Part; 2] {
+        [Part::One, Part::Two]
+    }
+}
+
+// one command line selector: "7", "10..=15", "10..15", "22:2" or "10..=15:1"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector {
+    days: RangeInclusive<u8>,
+    part: Option<Part>,
+}
+
+fn parse_day(value: &str) -> Result<u8> {
+    let day = value
+        .trim()
+        .parse::<u8>()
+        .map_err(|_| anyhow!("bad day '{}'", value))?;
+    if !(FIRST_DAY..=LAST_DAY).contains(&day) {
+        return Err(anyhow!(
+            "day {} out of range {}..={}",
+            day,
+            FIRST_DAY,
+            LAST_DAY
+        ));
+    }
+    Ok(day)
+}
+
+impl TryFrom<&str> for Selector {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let (days, part) = match value.split_once(':') {
+            Some((days, part)) => (days, Some(Part::try_from(part)?)),
+            None => (value, None),
+        };
+        let days = if let Some((start, end)) = days.split_once("..=") {
+            parse_day(start)?..=parse_day(end)?
+        } else if let Some((start, end)) = days.split_once("..") {
+            // exclusive end, as in any proper range definition, so 1..26 selects all days
+            let end = end
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| anyhow!("bad day '{}'", end))?;
+            if !(FIRST_DAY + 1..=LAST_DAY + 1).contains(&end) {
+                return Err(anyhow!(
+                    "exclusive end {} out of range {}..={}",
+                    end,
+                    FIRST_DAY + 1,
+                    LAST_DAY + 1
+                ));
+            }
+            parse_day(start)?..=end - 1
+        } else {
+            let day = parse_day(days)?;
+            day..=day
+        };
+        if days.is_empty() {
+            return Err(anyhow!("empty day range '{}'", value));
+        }
+        Ok(Self { days, part })
+    }
+}
+
+impl Selector {
+    fn contains(&self, day: u8, part: Part) -> bool {
+        self.days.contains(&day) && self.part.map(|p| p == part).unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    // empty list of selectors selects everything
+    selectors: Vec<Selector>,
+}
+
+impl Selection {
+    pub fn all() -> Self {
+        Self::default()
+    }
+    pub fn add(&mut self, selector: &str) -> Result<()> {
+        self.selectors.push(Selector::try_from(selector)?);
+        Ok(())
+    }
+    pub fn contains(&self, day: u8, part: Part) -> bool {
+        self.selectors.is_empty() || self.selectors.iter().any(|s| s.contains(day, part))
+    }
+    // selected days in ascending order without duplicates
+    pub fn days(&self) -> Vec<u8> {
+        let mut days: Vec<u8> = self.iter().map(|(day, _)| day).collect();
+        days.dedup();
+        days
+    }
+    // selected days and parts in ascending order without duplicates
+    pub fn iter(&self) -> impl Iterator<Item = (u8, Part)> + '_ {
+        (FIRST_DAY..=LAST_DAY)
+            .flat_map(|day| Part::both().into_iter().map(move |part| (day, part)))
+            .filter(|(day, part)| self.contains(*day, *part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_selectors() -> Result<()> {
+        let mut selection = Selection::all();
+        assert_eq!(selection.iter().count(), 50);
+
+        selection.add("7")?;
+        selection.add("10..=12")?;
+        selection.add("14..16")?;
+        selection.add("22:2")?;
+        selection.add("12")?;
+        let selected: Vec<(u8, Part)> = selection.iter().collect();
+        assert_eq!(
+            selected,
+            vec![
+                (7, Part::One),
+                (7, Part::Two),
+                (10, Part::One),
+                (10, Part::Two),
+                (11, Part::One),
+                (11, Part::Two),
+                (12, Part::One),
+                (12, Part::Two),
+                (14, Part::One),
+                (14, Part::Two),
+                (15, Part::One),
+                (15, Part::Two),
+                (22, Part::Two),
+            ]
+        );
+
+        assert_eq!(selection.days(), vec![7, 10, 11, 12, 14, 15, 22]);
+
+        let mut selection = Selection::all();
+        selection.add("24..26")?;
+        assert_eq!(selection.days(), vec![24, 25]);
+        selection.add("1..26")?;
+        assert_eq!(selection.days().len(), 25);
+
+        for bad_selector in [
+            "0", "26", "a", "5:3", "12..=10", "3..1", "3..=", ":1", "1..27", "1..1", "1..=26",
+        ] {
+            assert!(
+                Selector::try_from(bad_selector).is_err(),
+                "{}",
+                bad_selector
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,579 @@
+//!server.rs
+
+use crate::budget::{self, CancelToken};
+use crate::cli::parse_value;
+use crate::days;
+use crate::pool;
+use crate::report::{Outcome, Record};
+use crate::selection::Part;
+use crate::solution::catch_panic;
+use crate::trace;
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:2022";
+// puzzle inputs are at most a few dozen KiB
+pub const DEFAULT_MAX_BODY: usize = 1 << 20;
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+// request line and headers
+const MAX_HEAD: u64 = 16 * 1024;
+// slow clients must not block their connection thread forever
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub const USAGE: &str = "\
+usage: server [OPTIONS]
+
+POST /solve with body {\"day\": 1, \"part\": 1, \"input\": \"...\"} answers with fields day, part,
+status, answer, elapsed_us and message like --format json of the runner
+
+options:
+        --addr <ADDR>       listen on ADDR (default: 127.0.0.1:2022)
+        --max-body <BYTES>  largest accepted request body (default: 1048576)
+        --timeout <SECONDS> time of a request to parse and solve its input (default: 10)
+        --max-solves <N>    solves running at the same time, including timed out solvers
+                            until they stop (default: one per cpu)
+        --max-connections <N>
+                            open connections, further clients wait (default: 64)
+    -h, --help              print this help";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub addr: String,
+    pub max_body: usize,
+    pub timeout: Duration,
+    pub max_solves: usize,
+    pub max_connections: usize,
+    pub help: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: DEFAULT_ADDR.to_string(),
+            max_body: DEFAULT_MAX_BODY,
+            timeout: DEFAULT_TIMEOUT,
+            max_solves: pool::available_workers(),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            help: false,
+        }
+    }
+}
+
+impl ServerConfig {
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut config = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--addr" => {
+                    let value = args.next().ok_or(anyhow!("missing value of --addr"))?;
+                    config.addr = value.as_ref().to_string();
+                }
+                "--max-body" => config.max_body = parse_value(args.next(), "--max-body")?,
+                "--timeout" => {
+                    let seconds: f64 = parse_value(args.next(), "--timeout")?;
+                    config.timeout = Duration::try_from_secs_f64(seconds)
+                        .ok()
+                        .filter(|timeout| !timeout.is_zero())
+                        .ok_or(anyhow!("bad value '{}' of --timeout", seconds))?;
+                }
+                "--max-solves" => config.max_solves = parse_value(args.next(), "--max-solves")?,
+                "--max-connections" => {
+                    config.max_connections = parse_value(args.next(), "--max-connections")?
+                }
+                "-h" | "--help" => config.help = true,
+                arg => return Err(anyhow!("unknown argument '{}'\n\n{}", arg, USAGE)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// status and reason of a refused request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpError {
+    pub status: u16,
+    pub message: String,
+}
+
+impl HttpError {
+    pub fn new(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "error": self.message })
+    }
+}
+
+impl Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status, self.message)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+// request of a http client, header names are lower case
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// reads one request of reader; bodies larger than max_body are refused before reading them
+pub fn read_request(reader: &mut impl BufRead, max_body: usize) -> Result<Request, HttpError> {
+    let bad_request = |err: std::io::Error| HttpError::new(400, format!("bad request: {}", err));
+    let mut request = Request::default();
+    {
+        let mut head = reader.take(MAX_HEAD);
+        let mut line = String::new();
+        head.read_line(&mut line).map_err(bad_request)?;
+        let mut request_line = line.split_whitespace();
+        request.method = request_line.next().unwrap_or_default().to_string();
+        request.path = request_line.next().unwrap_or_default().to_string();
+        loop {
+            line.clear();
+            head.read_line(&mut line).map_err(bad_request)?;
+            if !line.ends_with('\n') {
+                return Err(match head.limit() {
+                    0 => HttpError::new(431, "request header is too large"),
+                    _ => HttpError::new(400, "incomplete request header"),
+                });
+            }
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+            request
+                .headers
+                .push((name.to_lowercase(), value.trim().to_string()));
+        }
+    }
+    let content_length: usize = match request.header("content-length") {
+        Some(length) => length
+            .parse()
+            .map_err(|_| HttpError::new(400, format!("bad content length '{}'", length)))?,
+        None if request.method == "POST" => {
+            return Err(HttpError::new(411, "content length is required"))
+        }
+        None => 0,
+    };
+    if content_length > max_body {
+        return Err(HttpError::new(
+            413,
+            format!(
+                "request body of {} bytes exceeds limit of {} bytes",
+                content_length, max_body
+            ),
+        ));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+    request.body =
+        String::from_utf8(body).map_err(|_| HttpError::new(400, "request body is not UTF-8"))?;
+    Ok(request)
+}
+
+// body of POST /solve, e.g. {"day": 1, "part": 2, "input": "1000\n2000\n"}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveRequest {
+    pub day: u8,
+    pub part: Part,
+    pub input: String,
+}
+
+impl TryFrom<&str> for SolveRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let json: serde_json::Value =
+            serde_json::from_str(value).map_err(|err| anyhow!("bad json: {}", err))?;
+        let number = |key: &str| {
+            json[key]
+                .as_u64()
+                .ok_or(anyhow!("missing number '{}' of request", key))
+        };
+        let day = number("day")?;
+        Ok(Self {
+            day: u8::try_from(day).map_err(|_| anyhow!("day {} is not registered", day))?,
+            part: Part::try_from(number("part")?.to_string().as_str())?,
+            input: json["input"]
+                .as_str()
+                .ok_or(anyhow!("missing text 'input' of request"))?
+                .to_string(),
+        })
+    }
+}
+
+fn write_response(mut stream: &TcpStream, status: u16, body: &serde_json::Value) -> Result<()> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+// limited number of threads, e.g. of running solvers
+#[derive(Debug)]
+struct Slots {
+    used: Mutex<usize>,
+    released: Condvar,
+    max: usize,
+}
+
+// slot of a thread, which is released when the thread ends, even after a panic
+struct Slot(Arc<Slots>);
+
+impl Slots {
+    fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            used: Mutex::new(0),
+            released: Condvar::new(),
+            max,
+        })
+    }
+    fn try_acquire(self: &Arc<Self>) -> Option<Slot> {
+        let mut used = self.used.lock().unwrap();
+        if *used >= self.max {
+            return None;
+        }
+        *used += 1;
+        Some(Slot(self.clone()))
+    }
+    // waits until a slot is released, if all are used
+    fn acquire(self: &Arc<Self>) -> Slot {
+        let mut used = self.used.lock().unwrap();
+        while *used >= self.max {
+            used = self.released.wait(used).unwrap();
+        }
+        *used += 1;
+        Slot(self.clone())
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        *self.0.used.lock().unwrap() -= 1;
+        self.0.released.notify_one();
+    }
+}
+
+// solvers of all days behind a small http api
+#[derive(Debug)]
+pub struct Server {
+    config: ServerConfig,
+    // solver threads, which did not return yet
+    solvers: Arc<Slots>,
+    connections: Arc<Slots>,
+}
+
+impl Server {
+    pub fn new(config: ServerConfig) -> Self {
+        Self {
+            solvers: Slots::new(config.max_solves),
+            connections: Slots::new(config.max_connections),
+            config,
+        }
+    }
+    // answers connections of listener, each in its own thread; further connections wait in the
+    // backlog of listener while max_connections are open
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> Result<()> {
+        loop {
+            let slot = self.connections.acquire();
+            let stream = match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    trace::event!(Warn, "failed to accept connection: {}", err);
+                    continue;
+                }
+            };
+            let server = self.clone();
+            std::thread::spawn(move || {
+                let _slot = slot;
+                if let Err(err) = server.handle_connection(stream) {
+                    trace::event!(Debug, "connection failed: {:#}", err);
+                }
+            });
+        }
+    }
+    fn handle_connection(&self, stream: TcpStream) -> Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (status, body) = match read_request(&mut reader, self.config.max_body) {
+            Ok(request) => {
+                let (status, body) = self.respond(&request);
+                trace::event!(Info, "{} {}: {}", request.method, request.path, status);
+                (status, body)
+            }
+            Err(err) => {
+                trace::event!(Info, "refused request: {}", err);
+                (err.status, err.to_json())
+            }
+        };
+        write_response(&stream, status, &body)?;
+        // unread rest of a refused body would reset the connection before client reads status
+        let _ = std::io::copy(
+            &mut reader.take(self.config.max_body as u64),
+            &mut std::io::sink(),
+        );
+        Ok(())
+    }
+    // status and json body of response to request
+    pub fn respond(&self, request: &Request) -> (u16, serde_json::Value) {
+        let result = match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/solve") => SolveRequest::try_from(request.body.as_str())
+                .map_err(|err| HttpError::new(400, format!("{:#}", err)))
+                .and_then(|solve_request| self.solve(solve_request)),
+            (method, "/solve") => Err(HttpError::new(
+                405,
+                format!("method {} is not allowed, use POST", method),
+            )),
+            (_, path) => Err(HttpError::new(404, format!("unknown path {}", path))),
+        };
+        match result {
+            Ok((status, record)) => (status, record.to_json()),
+            Err(err) => (err.status, err.to_json()),
+        }
+    }
+    // parses and solves input in a thread of its own, which is abandoned after the timeout;
+    // solvers polling their budget stop, others run on and keep their slot until they return
+    pub fn solve(&self, request: SolveRequest) -> Result<(u16, Record), HttpError> {
+        let SolveRequest { day, part, input } = request;
+        let solution = days::get(day)
+            .ok_or_else(|| HttpError::new(400, format!("day {} is not registered", day)))?;
+        if !solution.parts().contains(&part) {
+            return Err(HttpError::new(
+                400,
+                format!("day {:02} has no part {}", day, part),
+            ));
+        }
+        let slot = self
+            .solvers
+            .try_acquire()
+            .ok_or_else(|| HttpError::new(503, "too many running solves, retry later"))?;
+        let timeout = self.config.timeout;
+        let token = CancelToken::with_budget(Some(timeout));
+        let (sender, receiver) = mpsc::channel();
+        let solver_token = token.clone();
+        let start = Instant::now();
+        std::thread::spawn(move || {
+            let result = budget::with_token(&solver_token, || {
+                let parsed = catch_panic(|| solution.parse(&input))
+                    .map_err(|err| (422, err.context("failed to parse input")))?;
+                catch_panic(|| solution.solve(parsed.as_ref(), part)).map_err(|err| (500, err))
+            });
+            // released before answering, so the next request of the client finds it free
+            drop(slot);
+            // receiver is gone after timeout
+            let _ = sender.send(result);
+        });
+        let (status, outcome) = match receiver.recv_timeout(timeout) {
+            Ok(Ok(answer)) => (200, Outcome::Solved(answer, None)),
+            Ok(Err((_, err))) if budget::is_cancelled(&err) => (504, Outcome::TimedOut(timeout)),
+            Ok(Err((status, err))) => (status, Outcome::Failed(format!("{:#}", err))),
+            Err(_) => {
+                token.cancel();
+                (504, Outcome::TimedOut(timeout))
+            }
+        };
+        Ok((status, Record::new(day, part, start.elapsed(), outcome)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // waits for threads holding slots to end
+    fn released(slots: &Slots) -> bool {
+        (0..500).any(|_| {
+            let free = *slots.used.lock().unwrap() == 0;
+            if !free {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            free
+        })
+    }
+
+    fn solve_body(day: u8, part: u8, input: &str) -> String {
+        serde_json::json!({ "day": day, "part": part, "input": input }).to_string()
+    }
+
+    fn post(server: &Server, body: &str) -> (u16, serde_json::Value) {
+        let request = Request {
+            method: String::from("POST"),
+            path: String::from("/solve"),
+            body: body.to_string(),
+            ..Default::default()
+        };
+        server.respond(&request)
+    }
+
+    #[test]
+    fn test_read_request() -> Result<()> {
+        let mut raw = "POST /solve HTTP/1.1\r\nContent-Length: 4\r\n\r\n{}\n\nrest".as_bytes();
+        let request = read_request(&mut raw, 10)?;
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("POST", "/solve")
+        );
+        assert_eq!(request.header("content-length"), Some("4"));
+        assert_eq!(request.body, "{}\n\n");
+
+        let mut raw = "POST /solve HTTP/1.1\r\nContent-Length: 11\r\n\r\n{}".as_bytes();
+        assert_eq!(read_request(&mut raw, 10).unwrap_err().status, 413);
+        let mut raw = "POST /solve HTTP/1.1\r\n\r\n{}".as_bytes();
+        assert_eq!(read_request(&mut raw, 10).unwrap_err().status, 411);
+        let head = format!(
+            "GET / HTTP/1.1\r\nX: {}\r\n\r\n",
+            "x".repeat(MAX_HEAD as usize)
+        );
+        assert_eq!(
+            read_request(&mut head.as_bytes(), 10).unwrap_err().status,
+            431
+        );
+        let mut raw = "GET / HTTP/1.1\r\nHost: x".as_bytes();
+        assert_eq!(read_request(&mut raw, 10).unwrap_err().status, 400);
+        Ok(())
+    }
+
+    #[test]
+    fn test_server() -> Result<()> {
+        let example = include_str!("../assets/day_01_example.txt");
+        let server = Server::new(ServerConfig::default());
+        let (status, json) = post(&server, &solve_body(1, 2, example));
+        assert_eq!(status, 200);
+        assert_eq!(
+            (json["status"].as_str(), json["answer"].as_i64()),
+            (Some("solved"), Some(45_000))
+        );
+        assert!(json["elapsed_us"].is_u64());
+
+        let (status, json) = post(&server, &solve_body(1, 1, "1000\nx\n"));
+        assert_eq!(status, 422);
+        assert_eq!(
+            json["message"],
+            "failed to parse input: day 01 line 2 column 1: expected number, found 'x'"
+        );
+        assert_eq!(post(&server, &solve_body(26, 1, "")).0, 400);
+        let (status, json) = post(&server, &solve_body(25, 2, ""));
+        assert_eq!(
+            (status, json["error"].as_str()),
+            (400, Some("day 25 has no part 2"))
+        );
+        assert_eq!(post(&server, "{\"day\": 1}").0, 400);
+        let get = Request {
+            method: String::from("GET"),
+            path: String::from("/solve"),
+            ..Default::default()
+        };
+        assert_eq!(server.respond(&get).0, 405);
+
+        // search of day 19 polls its budget and stops at the timeout
+        let config = ServerConfig::parse(["--timeout", "0.05"])?;
+        let server = Server::new(config);
+        let blueprints = include_str!("../assets/day_19.txt");
+        let (status, json) = post(&server, &solve_body(19, 2, blueprints));
+        assert_eq!((status, json["status"].as_str()), (504, Some("timed out")));
+        let server = Server::new(ServerConfig::parse(["--max-solves", "0"])?);
+        assert_eq!(post(&server, &solve_body(1, 1, example)).0, 503);
+        // timed out search of day 16 stops and releases its slot for the next solve
+        let config = ServerConfig::parse(["--timeout", "0.05", "--max-solves", "1"])?;
+        let server = Server::new(config);
+        let valves = include_str!("../assets/day_16.txt");
+        for _ in 0..2 {
+            assert_eq!(post(&server, &solve_body(16, 2, valves)).0, 504);
+            assert!(released(&server.solvers));
+        }
+
+        // round trip over http
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}/solve", listener.local_addr()?);
+        let config = ServerConfig::parse(["--max-body", "1024"])?;
+        std::thread::spawn(move || Arc::new(Server::new(config)).serve(listener));
+        let response = ureq::post(&url).send_string(&solve_body(1, 1, example))?;
+        let json: serde_json::Value = serde_json::from_str(&response.into_string()?)?;
+        assert_eq!(json["answer"], 24_000);
+        let body = solve_body(1, 1, &"1\n".repeat(512));
+        match ureq::post(&url).send_string(&body) {
+            Err(ureq::Error::Status(status, response)) => {
+                assert_eq!(status, 413);
+                assert!(response
+                    .into_string()?
+                    .contains("exceeds limit of 1024 bytes"));
+            }
+            result => panic!("expected status 413, got {:?}", result.map(|r| r.status())),
+        }
+
+        // clients wait while max_connections are open
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let server = Server::new(ServerConfig::parse(["--max-connections", "1"])?);
+        std::thread::spawn(move || Arc::new(server).serve(listener));
+        let idle = TcpStream::connect(addr)?;
+        let url = format!("http://{}/solve", addr);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_millis(200))
+            .build();
+        assert!(agent
+            .post(&url)
+            .send_string(&solve_body(1, 1, example))
+            .is_err());
+        // waiting request is served, once idle client closes its connection
+        drop(idle);
+        let response = ureq::post(&url).send_string(&solve_body(1, 1, example))?;
+        assert_eq!(response.status(), 200);
+
+        assert!(ServerConfig::parse(["--timeout", "0"]).is_err());
+        assert!(ServerConfig::parse(["--port"]).is_err());
+        Ok(())
+    }
+}
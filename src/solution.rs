@@ -0,0 +1,403 @@
+//!solution.rs
+
+use crate::answer::Answer;
+use crate::parse::ParseError;
+use crate::repl::Explore;
+use crate::selection::Part;
+use crate::visualize::FrameSink;
+use anyhow::{anyhow, Result};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::sync::Once;
+
+// named values, which differ between example and puzzle input, e.g. the scanned row of day 15
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, i64>);
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn insert(&mut self, name: &str, value: i64) {
+        self.0.insert(name.to_string(), value);
+    }
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.0.get(name).copied()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    // fails on first param, which is not known by day
+    pub fn check_known(&self, day: u8, known: &[&str]) -> Result<()> {
+        match self.0.keys().find(|name| !known.contains(&name.as_str())) {
+            Some(name) => Err(anyhow!("day {:02} has no parameter '{}'", day, name)),
+            None => Ok(()),
+        }
+    }
+}
+
+pub trait Solution {
+    const DAY: u8;
+    // day 25 has only one part
+    const PARTS: &'static [Part] = &[Part::One, Part::Two];
+    // parts which only run with --include-slow or a --budget, they should call budget::check()
+    const SLOW_PARTS: &'static [Part] = &[];
+    // parts with a naive reference solver, which checks tricks of the optimized solver
+    const REFERENCE_PARTS: &'static [Part] = &[];
+    // line oriented days solve their input from a reader with solve_reader()
+    const STREAMS: bool = false;
+    // bump it, when answers of a day change, so that cached answers of it are not used
+    const VERSION: &'static str = "1";
+
+    type Input: 'static;
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part_one(input: &Self::Input) -> Result<Self::PartOne>;
+    fn part_two(input: &Self::Input) -> Result<Self::PartTwo>;
+    // days with puzzle specific values override them in parsed input
+    fn apply_params(_input: &mut Self::Input, params: &Params) -> Result<()> {
+        params.check_known(Self::DAY, &[])
+    }
+    // days with step-wise simulations send frames of part to sink
+    fn visualize(_input: &Self::Input, _part: Part, _sink: &mut dyn FrameSink) -> Result<()> {
+        Err(anyhow!("day {:02} has no visualization", Self::DAY))
+    }
+    // slow but simple solver of part, answers must equal those of part_one() and part_two()
+    fn reference(_input: &Self::Input, _part: Part) -> Result<Answer> {
+        Err(anyhow!("day {:02} has no reference solver", Self::DAY))
+    }
+    // answers of all PARTS in one pass over reader, which holds only the state of the puzzle
+    // instead of the whole input; answers and failed parts must equal those of part_one() and
+    // part_two(), only errors of reading or parsing fail all parts
+    fn solve_reader(_reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        Err(anyhow!("day {:02} has no streaming solver", Self::DAY))
+    }
+    // days with puzzle state worth inspecting offer commands in the REPL
+    fn explore(_input: &Self::Input) -> Result<Box<dyn Explore>> {
+        Err(anyhow!("day {:02} has no REPL commands", Self::DAY))
+    }
+}
+
+// object safe view of a Solution, which allows to enumerate all days generically
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn parts(&self) -> &'static [Part];
+    fn slow_parts(&self) -> &'static [Part];
+    fn reference_parts(&self) -> &'static [Part];
+    fn streams(&self) -> bool;
+    fn version(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn parse_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Any>>;
+    fn solve(&self, parsed: &dyn Any, part: Part) -> Result<Answer>;
+    fn visualize(&self, parsed: &dyn Any, part: Part, sink: &mut dyn FrameSink) -> Result<()>;
+    fn reference(&self, parsed: &dyn Any, part: Part) -> Result<Answer>;
+    fn explore(&self, parsed: &dyn Any) -> Result<Box<dyn Explore>>;
+    fn solve_reader(&self, reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>>;
+    fn run(&self, input: &str, part: Part) -> Result<Answer> {
+        let parsed = self.parse(input)?;
+        self.solve(parsed.as_ref(), part)
+    }
+}
+
+pub struct Registered<S>(PhantomData<fn() -> S>);
+
+impl<S: Solution> Registered<S> {
+    pub const fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: Solution> Default for Registered<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Solution> DynSolution for Registered<S> {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+    fn parts(&self) -> &'static [Part] {
+        S::PARTS
+    }
+    fn slow_parts(&self) -> &'static [Part] {
+        S::SLOW_PARTS
+    }
+    fn reference_parts(&self) -> &'static [Part] {
+        S::REFERENCE_PARTS
+    }
+    fn streams(&self) -> bool {
+        S::STREAMS
+    }
+    fn version(&self) -> &'static str {
+        S::VERSION
+    }
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        self.parse_with_params(input, &Params::new())
+    }
+    fn parse_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Any>> {
+        let mut parsed = S::parse(input).map_err(in_day::<S>)?;
+        S::apply_params(&mut parsed, params)?;
+        Ok(Box::new(parsed))
+    }
+    fn solve(&self, parsed: &dyn Any, part: Part) -> Result<Answer> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or(anyhow!("day {} got input of wrong type", S::DAY))?;
+        match part {
+            Part::One => S::part_one(input).map(Into::into),
+            Part::Two => S::part_two(input).map(Into::into),
+        }
+    }
+    fn visualize(&self, parsed: &dyn Any, part: Part, sink: &mut dyn FrameSink) -> Result<()> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or(anyhow!("day {} got input of wrong type", S::DAY))?;
+        S::visualize(input, part, sink)
+    }
+    fn reference(&self, parsed: &dyn Any, part: Part) -> Result<Answer> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or(anyhow!("day {} got input of wrong type", S::DAY))?;
+        S::reference(input, part)
+    }
+    fn explore(&self, parsed: &dyn Any) -> Result<Box<dyn Explore>> {
+        let input = parsed
+            .downcast_ref::<S::Input>()
+            .ok_or(anyhow!("day {} got input of wrong type", S::DAY))?;
+        S::explore(input)
+    }
+    fn solve_reader(&self, reader: &mut dyn BufRead) -> Result<Vec<Result<Answer>>> {
+        S::solve_reader(reader).map_err(in_day::<S>)
+    }
+}
+
+// parse errors of days do not know their day
+fn in_day<S: Solution>(err: anyhow::Error) -> anyhow::Error {
+    match err.downcast::<ParseError>() {
+        Ok(parse_error) => parse_error.in_day(S::DAY).into(),
+        Err(err) => err,
+    }
+}
+
+thread_local! {
+    // nesting depth of catch_panic() on this thread
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+    // location of last panic caught on this thread
+    static CAUGHT_AT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+// replaces default panic hook, which would print caught panics and backtraces to stderr,
+// by a hook recording their location; other panics are still printed by the default hook
+fn install_quiet_hook() {
+    QUIET_HOOK.call_once(|| {
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(Cell::get) == 0 {
+                return default_hook(info);
+            }
+            let location = info.location().map(|location| location.to_string());
+            CAUGHT_AT.with(|caught_at| caught_at.replace(location));
+        }));
+    });
+}
+
+// turns panics of a solver, e.g. from expect("bad input"), into errors
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    install_quiet_hook();
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+    result.unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|m| m.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown panic"));
+        match CAUGHT_AT.with(RefCell::take) {
+            Some(location) => Err(anyhow!("panicked at {}: {}", location, message)),
+            None => Err(anyhow!("panicked: {}", message)),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{days, generate, input};
+
+    #[test]
+    fn test_registry() {
+        let registered_days: Vec<u8> = days::registry().iter().map(|s| s.day()).collect();
+        let expected_days: Vec<u8> = (1..=25).collect();
+        assert_eq!(registered_days, expected_days);
+        for day in expected_days {
+            assert_eq!(days::get(day).map(|s| s.day()), Some(day));
+        }
+        assert!(days::get(26).is_none());
+        assert_eq!(days::get(25).unwrap().parts(), &[Part::One]);
+
+        let result = catch_panic(|| days::get(1).unwrap().run("1000\nx\n\n3", Part::One));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "day 01 line 2 column 1: expected number, found 'x'"
+        );
+        let result: Result<u8> = catch_panic(|| panic!("bad input"));
+        let message = result.unwrap_err().to_string();
+        assert!(
+            message.starts_with("panicked at src/solution.rs:"),
+            "{}",
+            message
+        );
+        assert!(message.ends_with(": bad input"), "{}", message);
+        assert_eq!(catch_panic(|| Ok(1)).unwrap(), 1);
+
+        let mut params = Params::new();
+        params.insert("row", 10);
+        assert_eq!(params.get("row"), Some(10));
+        assert!(params.check_known(15, &["row", "max_range"]).is_ok());
+        let result = days::get(1).unwrap().parse_with_params("1000", &params);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "day 01 has no parameter 'row'"
+        );
+    }
+
+    #[test]
+    fn test_malformed_inputs() {
+        let monkeys =
+            include_str!("../assets/day_11_example.txt").replacen("old * 19", "old / 0", 1);
+        let cases = [
+            (11, monkeys.as_str(), "day 11 line 3 column 24: expected operator + or *, found '/'"),
+            (13, "[1]\n[2]\n\n[[1]]\n[1]", "day 13 line 5 column 1: expected packet different from left packet, found '[1]'"),
+            (16, "Valve AA has flow rate=0; tunnels lead to valves BB\nValve BB has flow rate=1; tunnel leads to valve CC\nValve CC has flow rate=2; tunnel leads to valve BB", "day 16 line 1 column 50: expected valve with tunnel back to AA, found 'BB'"),
+            (21, "root: abcd + humn\nhumn: 5", "day 21 line 1 column 7: expected monkey with a job, found 'abcd'"),
+            (22, "  #\n...\n\n10", "day 22 line 1 column 1: expected free tile '.' in top row of map, found '  #'"),
+        ];
+        for (day, input, message) in cases {
+            let err = catch_panic(|| days::get(day).unwrap().run(input, Part::One)).unwrap_err();
+            assert!(err.downcast_ref::<ParseError>().is_some(), "{}", err);
+            assert_eq!(err.to_string(), message);
+        }
+        // valid input with too few elves only fails part 2
+        let day_01 = days::get(1).unwrap();
+        assert_eq!(
+            day_01.run("100\n200\n\n50\n", Part::One).unwrap(),
+            Answer::from(300)
+        );
+        let err = catch_panic(|| day_01.run("100\n200\n\n50\n", Part::Two)).unwrap_err();
+        assert_eq!(err.to_string(), "need at least 3 elves");
+        let day_07 = days::get(7).unwrap();
+        let err = catch_panic(|| day_07.run("$ cd /\n$ ls\n80000000 a\n", Part::Two));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "files exceed filesystem of 70000000"
+        );
+        let err = catch_panic(|| day_07.run("$ cd /\n$ ls\n1 a\n", Part::Two));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "enough free space already: 69999999"
+        );
+    }
+
+    #[test]
+    fn test_solve_reader() -> Result<()> {
+        let streaming_days: Vec<u8> = days::registry()
+            .iter()
+            .filter(|s| s.streams())
+            .map(|s| s.day())
+            .collect();
+        assert_eq!(streaming_days, [1, 2, 3, 4, 6, 9, 10, 25]);
+        // answers and errors of parts as text
+        let texts = |results: Vec<Result<Answer>>| -> Vec<String> {
+            results
+                .into_iter()
+                .map(|result| result.map_or_else(|err| format!("{:#}", err), |a| a.to_string()))
+                .collect()
+        };
+        for day in streaming_days {
+            let solution = days::get(day).unwrap();
+            let mut inputs = vec![
+                input::embedded(day)?.to_string(),
+                generate::generate(day, 1, 500)?,
+            ];
+            if day == 10 {
+                // test pattern of example has no letters, which only fails part 2
+                inputs.push(include_str!("../assets/day_10_example.txt").to_string());
+            }
+            if day == 1 {
+                // too few elves only fail part 2, blank lines may not follow each other
+                inputs.extend(
+                    [
+                        "100\n200\n\n50\n",
+                        "1\n\n\n2\n\n3\n\n4\n",
+                        "1\n\n2\n\n3\n\n",
+                    ]
+                    .map(String::from),
+                );
+            }
+            for input in inputs {
+                // parse errors fail all parts
+                let results = solution.parse(&input).map(|parsed| {
+                    let parts = solution.parts().iter();
+                    texts(
+                        parts
+                            .map(|part| solution.solve(parsed.as_ref(), *part))
+                            .collect(),
+                    )
+                });
+                // small buffer splits lines and signal of day 6 between reads
+                let mut reader = std::io::BufReader::with_capacity(7, input.as_bytes());
+                assert_eq!(
+                    solution
+                        .solve_reader(&mut reader)
+                        .map(texts)
+                        .map_err(|err| err.to_string()),
+                    results.map_err(|err| err.to_string()),
+                    "day {:02}",
+                    day
+                );
+            }
+        }
+        // streamed signal of day 6 is checked like parsed signal
+        for bad_signal in ["abcd ef", "  abcd\n\nef\n", "abc1d", "ab\r\ncd"] {
+            let day_06 = days::get(6).unwrap();
+            let err = day_06.solve_reader(&mut bad_signal.as_bytes()).unwrap_err();
+            let expected = day_06.run(bad_signal, Part::One).unwrap_err();
+            assert_eq!(err.to_string(), expected.to_string());
+        }
+        assert!(days::get(6)
+            .unwrap()
+            .solve_reader(&mut "abé".as_bytes())
+            .is_err());
+        let example = include_str!("../assets/day_10_example.txt");
+        let results = days::get(10)
+            .unwrap()
+            .solve_reader(&mut example.as_bytes())?;
+        assert_eq!(results[0].as_ref().unwrap(), &Answer::from(13_140));
+        assert!(results[1].is_err());
+
+        let result = days::get(1)
+            .unwrap()
+            .solve_reader(&mut "1000\nx\n\n3".as_bytes());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "day 01 line 2 column 1: expected number, found 'x'"
+        );
+        let result = days::get(5).unwrap().solve_reader(&mut "".as_bytes());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "day 05 has no streaming solver"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,547 @@
+//!submit.rs
+
+use crate::answer::Answer;
+use crate::fetch::{download_error, FetchConfig, YEAR};
+use crate::selection::Part;
+use anyhow::{anyhow, Context, Result};
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// submissions of an account, one json object per line next to its cached inputs
+pub const HISTORY_FILE: &str = "submissions.jsonl";
+
+// cooldown after wrong answers, if the response does not name one
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    // answer was not checked, because the last submission was too recent
+    Wait(Duration),
+    // part is already solved or not unlocked yet
+    WrongLevel,
+}
+
+impl Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Response::Correct => write!(f, "correct"),
+            Response::TooHigh => write!(f, "too high"),
+            Response::TooLow => write!(f, "too low"),
+            Response::Wrong => write!(f, "wrong"),
+            Response::Wait(wait) => write!(f, "wait {}s", wait.as_secs()),
+            Response::WrongLevel => write!(f, "wrong level"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Response {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value {
+            "correct" => Ok(Response::Correct),
+            "too high" => Ok(Response::TooHigh),
+            "too low" => Ok(Response::TooLow),
+            "wrong" => Ok(Response::Wrong),
+            "wrong level" => Ok(Response::WrongLevel),
+            wait => wait
+                .strip_prefix("wait ")
+                .and_then(|w| w.strip_suffix('s'))
+                .and_then(|w| w.parse::<u64>().ok())
+                .map(|w| Response::Wait(Duration::from_secs(w)))
+                .ok_or(anyhow!("bad response '{}'", value)),
+        }
+    }
+}
+
+impl Response {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Response::TooHigh | Response::TooLow | Response::Wrong)
+    }
+}
+
+// visible text of html page, lower case and with single spaces
+fn page_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            c if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text.split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// "1m 5s" of "you have 1m 5s left to wait"
+fn parse_left_to_wait(text: &str) -> Option<Duration> {
+    let (before, _) = text.split_once(" left to wait")?;
+    let (_, wait) = before.rsplit_once("you have ")?;
+    let mut seconds = 0;
+    for token in wait.split_whitespace() {
+        let unit = match token.chars().last()? {
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        seconds += token[..token.len() - 1].parse::<u64>().ok()? * unit;
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+// "one minute" or "5 minutes" of "please wait 5 minutes before trying again"
+fn parse_please_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("please wait ")?;
+    let mut tokens = rest.split_whitespace();
+    let amount = match tokens.next()? {
+        "one" => 1,
+        amount => amount.parse::<u64>().ok()?,
+    };
+    let unit = match tokens.next()?.trim_end_matches(['.', ',']) {
+        "second" | "seconds" => 1,
+        "minute" | "minutes" => 60,
+        _ => return None,
+    };
+    Some(Duration::from_secs(amount * unit))
+}
+
+// response and cooldown until next submission
+pub fn parse_response(html: &str) -> Result<(Response, Duration)> {
+    let text = page_text(html);
+    if text.contains("that's the right answer") {
+        return Ok((Response::Correct, Duration::ZERO));
+    }
+    if text.contains("that's not the right answer") {
+        let response = if text.contains("your answer is too high") {
+            Response::TooHigh
+        } else if text.contains("your answer is too low") {
+            Response::TooLow
+        } else {
+            Response::Wrong
+        };
+        let cooldown = parse_please_wait(&text).unwrap_or(DEFAULT_COOLDOWN);
+        return Ok((response, cooldown));
+    }
+    if text.contains("you gave an answer too recently") {
+        let wait = parse_left_to_wait(&text).unwrap_or(DEFAULT_COOLDOWN);
+        return Ok((Response::Wait(wait), wait));
+    }
+    if text.contains("you don't seem to be solving the right level") {
+        return Ok((Response::WrongLevel, Duration::ZERO));
+    }
+    let excerpt: String = text.chars().take(200).collect();
+    Err(anyhow!("unexpected response: {}", excerpt))
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub response: Response,
+    // unix seconds of submission and end of cooldown
+    pub time: u64,
+    pub wait_until: u64,
+}
+
+impl TryFrom<&str> for Submission {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let json: serde_json::Value = serde_json::from_str(value)?;
+        let number = |key: &str| {
+            json[key]
+                .as_u64()
+                .ok_or(anyhow!("missing number '{}' of submission", key))
+        };
+        let answer = match &json["answer"] {
+            serde_json::Value::Number(n) => Answer::Number(
+                n.as_i64()
+                    .ok_or(anyhow!("bad answer {} of submission", n))?,
+            ),
+            serde_json::Value::String(t) => Answer::Text(t.to_owned()),
+            _ => return Err(anyhow!("missing answer of submission")),
+        };
+        let response = json["response"]
+            .as_str()
+            .ok_or(anyhow!("missing response of submission"))?;
+        Ok(Self {
+            day: u8::try_from(number("day")?)?,
+            part: Part::try_from(number("part")?.to_string().as_str())?,
+            answer,
+            response: Response::try_from(response)?,
+            time: number("time")?,
+            wait_until: number("wait_until")?,
+        })
+    }
+}
+
+impl Submission {
+    pub fn to_json(&self) -> serde_json::Value {
+        let answer = match &self.answer {
+            Answer::Number(n) => serde_json::Value::from(*n),
+            Answer::Text(t) => serde_json::Value::from(t.as_str()),
+        };
+        serde_json::json!({
+            "day": self.day,
+            "part": u8::from(self.part),
+            "answer": answer,
+            "response": self.response.to_string(),
+            "time": self.time,
+            "wait_until": self.wait_until,
+        })
+    }
+}
+
+// order of numeric answers, big numbers may be stored as text
+fn compare(answer: &Answer, other: &Answer) -> Option<Ordering> {
+    let numeric = |answer: &Answer| answer.to_string().parse::<i128>().ok();
+    Some(numeric(answer)?.cmp(&numeric(other)?))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl History {
+    // missing history file is an empty history
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut submissions = Vec::new();
+        if path.is_file() {
+            let history = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read history {}", path.display()))?;
+            for (index, line) in history.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let submission = Submission::try_from(line).with_context(|| {
+                    format!("bad line {} of history {}", index + 1, path.display())
+                })?;
+                submissions.push(submission);
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            submissions,
+        })
+    }
+    pub fn submissions(&self) -> &[Submission] {
+        &self.submissions
+    }
+    pub fn append(&mut self, submission: Submission) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("failed to open history {}", self.path.display()))?;
+        writeln!(file, "{}", submission.to_json())?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+    // reason, why answer must not be submitted at unix time now
+    pub fn check(&self, day: u8, part: Part, answer: &Answer, now: u64) -> Result<()> {
+        // cooldown applies to all days of an account
+        if let Some(wait_until) = self.submissions.iter().map(|s| s.wait_until).max() {
+            if wait_until > now {
+                return Err(anyhow!(
+                    "cooldown of last submission, retry in {}s",
+                    wait_until - now
+                ));
+            }
+        }
+        let answer_text = answer.to_string();
+        for submission in self
+            .submissions
+            .iter()
+            .filter(|s| s.day == day && s.part == part)
+        {
+            let same_answer = submission.answer.to_string() == answer_text;
+            match submission.response {
+                Response::Correct => {
+                    return Err(anyhow!(
+                        "day {:02} part {} is already solved with answer {}",
+                        day,
+                        part,
+                        submission.answer
+                    ))
+                }
+                response if response.is_wrong() && same_answer => {
+                    return Err(anyhow!(
+                        "answer {} is known to be wrong ({})",
+                        answer,
+                        response
+                    ))
+                }
+                // answers beyond known bounds are wrong as well
+                Response::TooHigh
+                    if compare(answer, &submission.answer).is_some_and(Ordering::is_ge) =>
+                {
+                    return Err(anyhow!(
+                        "answer {} is too high, {} already was",
+                        answer,
+                        submission.answer
+                    ))
+                }
+                Response::TooLow
+                    if compare(answer, &submission.answer).is_some_and(Ordering::is_le) =>
+                {
+                    return Err(anyhow!(
+                        "answer {} is too low, {} already was",
+                        answer,
+                        submission.answer
+                    ))
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn history_path(config: &FetchConfig) -> PathBuf {
+    config.account_dir().join(HISTORY_FILE)
+}
+
+pub fn answer_url(config: &FetchConfig, day: u8) -> String {
+    format!("{}/{}/day/{}/answer", config.base_url, YEAR, day)
+}
+
+// posts answer unless history refuses it, records response in history
+pub fn submit(
+    config: &FetchConfig,
+    history: &mut History,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+    now: u64,
+) -> Result<Response> {
+    if answer.is_multi_line() {
+        return Err(anyhow!("multi-line answers cannot be submitted"));
+    }
+    history
+        .check(day, part, answer, now)
+        .with_context(|| format!("refused to submit day {:02} part {}", day, part))?;
+    let html = config
+        .agent()
+        .post(&answer_url(config, day))
+        .set("Cookie", &format!("session={}", config.session()?))
+        .send_form(&[
+            ("level", u8::from(part).to_string().as_str()),
+            ("answer", answer.to_string().as_str()),
+        ])
+        .map_err(download_error)
+        .and_then(|response| {
+            response
+                .into_string()
+                .map_err(|err| download_error(err.into()))
+        })
+        .with_context(|| format!("failed to submit answer to {}", answer_url(config, day)))?;
+    let (response, cooldown) = parse_response(&html)?;
+    history.append(Submission {
+        day,
+        part,
+        answer: answer.clone(),
+        response,
+        time: now,
+        wait_until: now + cooldown.as_secs(),
+    })?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::fetch::{BASE_URL_VAR, CACHE_DIR_VAR, SESSION_VAR};
+    use crate::mock_server::MockServer;
+
+    const TOO_HIGH: &str = "<main><article><p>That's not the right answer; \
+        your answer is too high.  If you're stuck, make sure you're using the full input data; \
+        <span>please wait 5 minutes before trying again.</span></p></article></main>";
+    const CORRECT: &str = "<article><p>That's the right answer!  You are <em>one gold star</em> closer.</p></article>";
+
+    #[test]
+    fn test_parse_response() -> Result<()> {
+        assert_eq!(
+            parse_response(TOO_HIGH)?,
+            (Response::TooHigh, Duration::from_secs(300))
+        );
+        assert_eq!(
+            parse_response(CORRECT)?,
+            (Response::Correct, Duration::ZERO)
+        );
+        let too_low = "<p>That's not the right answer; your answer is too low. Please wait one minute before trying again.</p>";
+        assert_eq!(
+            parse_response(too_low)?,
+            (Response::TooLow, Duration::from_secs(60))
+        );
+        let wait =
+            "<p>You gave an answer too recently; you have to wait after submitting an answer \
+            before trying again.  You have 1m 5s left to wait.</p>";
+        let wait_time = Duration::from_secs(65);
+        assert_eq!(
+            parse_response(wait)?,
+            (Response::Wait(wait_time), wait_time)
+        );
+        let level =
+            "<p>You don't seem to be solving the right level.  Did you already complete it?</p>";
+        assert_eq!(parse_response(level)?.0, Response::WrongLevel);
+        assert!(parse_response("<p>Maintenance</p>").is_err());
+
+        for response in [
+            Response::TooLow,
+            Response::Wait(wait_time),
+            Response::WrongLevel,
+        ] {
+            assert_eq!(Response::try_from(response.to_string().as_str())?, response);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_history() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("aoc_2022_history_{}", std::process::id()));
+        let path = dir.join(HISTORY_FILE);
+        let mut history = History::load(&path)?;
+        assert!(history.check(1, Part::One, &Answer::from(5), 0).is_ok());
+        history.append(Submission {
+            day: 1,
+            part: Part::One,
+            answer: Answer::from(500),
+            response: Response::TooHigh,
+            time: 100,
+            wait_until: 160,
+        })?;
+        history.append(Submission {
+            day: 1,
+            part: Part::One,
+            answer: Answer::from(100),
+            response: Response::TooLow,
+            time: 200,
+            wait_until: 260,
+        })?;
+        let history = History::load(&path)?;
+        assert_eq!(history.submissions().len(), 2);
+        assert!(history
+            .check(1, Part::One, &Answer::from(300), 250)
+            .is_err());
+        assert!(history.check(1, Part::One, &Answer::from(300), 260).is_ok());
+        assert!(history
+            .check(1, Part::One, &Answer::from(500), 300)
+            .is_err());
+        assert!(history
+            .check(1, Part::One, &Answer::from(600), 300)
+            .is_err());
+        assert!(history
+            .check(1, Part::One, &Answer::from(100), 300)
+            .is_err());
+        assert!(history.check(1, Part::Two, &Answer::from(600), 300).is_ok());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_submit() -> Result<()> {
+        let server = MockServer::start(|request| match request.body.as_str() {
+            "level=1&answer=24000" => (200, String::from(CORRECT)),
+            _ => (200, String::from(TOO_HIGH)),
+        })?;
+        let cache_dir =
+            std::env::temp_dir().join(format!("aoc_2022_submit_{}", std::process::id()));
+        let config = FetchConfig::new(None, |name| match name {
+            BASE_URL_VAR => Some(server.base_url.clone()),
+            SESSION_VAR => Some(String::from("secret")),
+            CACHE_DIR_VAR => Some(cache_dir.display().to_string()),
+            _ => None,
+        })?;
+        let mut history = History::load(&history_path(&config))?;
+
+        let response = submit(
+            &config,
+            &mut history,
+            1,
+            Part::One,
+            &Answer::from(30_000),
+            1_000,
+        )?;
+        assert_eq!(response, Response::TooHigh);
+        // cooldown and known wrong answers are refused without request
+        assert!(submit(
+            &config,
+            &mut history,
+            1,
+            Part::One,
+            &Answer::from(24_000),
+            1_100
+        )
+        .is_err());
+        assert!(submit(
+            &config,
+            &mut history,
+            1,
+            Part::One,
+            &Answer::from(30_000),
+            1_300
+        )
+        .is_err());
+        assert_eq!(server.requests().len(), 1);
+        let response = submit(
+            &config,
+            &mut history,
+            1,
+            Part::One,
+            &Answer::from(24_000),
+            1_300,
+        )?;
+        assert_eq!(response, Response::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2022/day/1/answer");
+        assert_eq!(requests[0].header("cookie"), Some("session=secret"));
+        assert_eq!(
+            History::load(&history_path(&config))?.submissions().len(),
+            2
+        );
+        assert!(submit(
+            &config,
+            &mut history,
+            1,
+            Part::One,
+            &Answer::from(24_000),
+            2_000
+        )
+        .is_err());
+        std::fs::remove_dir_all(&cache_dir)?;
+        Ok(())
+    }
+}
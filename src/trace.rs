@@ -0,0 +1,209 @@
+//!trace.rs
+
+use anyhow::{anyhow, Result};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::RwLock;
+
+// environment variable with filter, if --trace is not given
+pub const ENV_FILTER: &str = "AOC_TRACE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl TryFrom<&str> for Level {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(anyhow!(
+                "bad level '{}', expected off, error, warn, info, debug or trace",
+                value
+            )),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        // padding of {:5}
+        f.pad(name)
+    }
+}
+
+// most verbose level enabled for modules, None is off
+fn level_filter(value: &str) -> Result<Option<Level>> {
+    if value.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    Level::try_from(value).map(Some)
+}
+
+// comma separated levels of modules and a default level, e.g. "day_22=trace,info";
+// modules are given by last part of their path like day_22, or by path like days
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    modules: Vec<(String, Option<Level>)>,
+}
+
+impl TryFrom<&str> for Filter {
+    type Error = anyhow::Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let mut filter = Filter::default();
+        for directive in value.split(',').map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim().trim_start_matches("advent_of_code_2022::");
+                    if module.is_empty() {
+                        return Err(anyhow!("missing module of trace filter '{}'", directive));
+                    }
+                    filter
+                        .modules
+                        .push((module.to_string(), level_filter(level.trim())?));
+                }
+                None => filter.default = level_filter(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl Filter {
+    // level of most specific module matching target, which is a module path
+    pub fn level(&self, target: &str) -> Option<Level> {
+        let path = target.trim_start_matches("advent_of_code_2022::");
+        self.modules
+            .iter()
+            .filter(|(module, _)| {
+                path == module
+                    || path.starts_with(&format!("{}::", module))
+                    || path.ends_with(&format!("::{}", module))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        self.level(target).is_some_and(|max| level <= max)
+    }
+    fn max_level(&self) -> Option<Level> {
+        self.modules
+            .iter()
+            .map(|(_, level)| *level)
+            .chain([self.default])
+            .max()
+            .flatten()
+    }
+}
+
+// most verbose level of current filter, which skips disabled events without locking it
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILTER: RwLock<Option<Filter>> = RwLock::new(None);
+
+pub fn set_filter(filter: Filter) {
+    let max_level = filter.max_level().map_or(0, |level| level as u8);
+    *FILTER.write().unwrap() = Some(filter);
+    MAX_LEVEL.store(max_level, Ordering::Relaxed);
+}
+
+// sets filter of --trace, or of environment variable if it is not given
+pub fn init(filter: Option<&str>) -> Result<()> {
+    let from_env = std::env::var(ENV_FILTER).ok();
+    if let Some(filter) = filter.or(from_env.as_deref()) {
+        set_filter(Filter::try_from(filter)?);
+    }
+    Ok(())
+}
+
+pub fn enabled(target: &str, level: Level) -> bool {
+    if level as u8 > MAX_LEVEL.load(Ordering::Relaxed) {
+        return false;
+    }
+    FILTER
+        .read()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|filter| filter.enabled(target, level))
+}
+
+pub fn format_event(target: &str, level: Level, message: std::fmt::Arguments) -> String {
+    let path = target.trim_start_matches("advent_of_code_2022::");
+    format!("{:5} {}: {}", level, path, message)
+}
+
+// events are written to stderr, so they do not mix with answers
+pub fn emit(target: &str, level: Level, message: std::fmt::Arguments) {
+    eprintln!("{}", format_event(target, level, message));
+}
+
+// emits event of level for module of call site, if filter enables it, e.g.
+// trace::event!(Debug, "position {}", position)
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        if $crate::trace::enabled(module_path!(), $crate::trace::Level::$level) {
+            $crate::trace::emit(
+                module_path!(),
+                $crate::trace::Level::$level,
+                format_args!($($arg)+),
+            );
+        }
+    };
+}
+pub(crate) use event;
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_filter() -> Result<()> {
+        let filter = Filter::try_from("info, days=debug,day_22=trace,days::day_20=off")?;
+        assert!(filter.enabled("advent_of_code_2022::fetch", Level::Info));
+        assert!(!filter.enabled("advent_of_code_2022::fetch", Level::Debug));
+        assert!(filter.enabled("advent_of_code_2022::days::day_01", Level::Debug));
+        assert!(!filter.enabled("advent_of_code_2022::days::day_01", Level::Trace));
+        assert!(filter.enabled("advent_of_code_2022::days::day_22", Level::Trace));
+        assert!(!filter.enabled("advent_of_code_2022::days::day_20", Level::Error));
+        assert_eq!(filter.max_level(), Some(Level::Trace));
+        // days does not match days_2
+        assert_eq!(
+            filter.level("advent_of_code_2022::days_2"),
+            Some(Level::Info)
+        );
+
+        let filter = Filter::try_from("day_22=TRACE")?;
+        assert_eq!(filter.level("advent_of_code_2022::days::day_20"), None);
+        assert_eq!(Filter::try_from("")?, Filter::default());
+        assert_eq!(Filter::default().max_level(), None);
+        assert!(Filter::try_from("day_22=verbose").is_err());
+        assert!(Filter::try_from("=debug").is_err());
+
+        let event = format_event(
+            "advent_of_code_2022::days::day_22",
+            Level::Info,
+            format_args!("position {}", 3),
+        );
+        assert_eq!(event, "INFO  days::day_22: position 3");
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+//!visualize.rs
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+pub const DEFAULT_FPS: f64 = 10.0;
+
+// state of a step-wise simulation, which can be drawn as text frame
+pub trait Render {
+    fn render(&self) -> String;
+}
+
+// receives successive frames of a simulation
+pub trait FrameSink {
+    // false stops the simulation, e.g. if user quits
+    fn frame(&mut self, frame: &dyn Render) -> Result<bool>;
+}
+
+// collects rendered frames, e.g. for tests
+impl FrameSink for Vec<String> {
+    fn frame(&mut self, frame: &dyn Render) -> Result<bool> {
+        self.push(frame.render());
+        Ok(true)
+    }
+}
+
+// draws frames in terminal; commands are read line by line from stdin:
+// "p" pauses and resumes, enter steps one frame while paused, "q" quits
+pub struct Terminal {
+    delay: Duration,
+    paused: bool,
+    commands: mpsc::Receiver<String>,
+    frame_number: usize,
+}
+
+impl Terminal {
+    pub fn new(fps: f64) -> Self {
+        let (sender, commands) = mpsc::channel();
+        // blocks on stdin until process exits
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            delay: Duration::from_secs_f64(1.0 / fps),
+            paused: false,
+            commands,
+            frame_number: 0,
+        }
+    }
+}
+
+impl FrameSink for Terminal {
+    fn frame(&mut self, frame: &dyn Render) -> Result<bool> {
+        let help = if self.paused {
+            "paused: enter steps, p + enter resumes, q + enter quits"
+        } else {
+            "p + enter pauses, q + enter quits"
+        };
+        let mut out = std::io::stdout().lock();
+        // clear screen and move cursor to top left corner
+        write!(
+            out,
+            "\x1b[2J\x1b[H{}\n\nframe {}  {}\n",
+            frame.render(),
+            self.frame_number,
+            help
+        )?;
+        out.flush()?;
+        self.frame_number += 1;
+        let command = if self.paused {
+            match self.commands.recv() {
+                Ok(command) => Some(command),
+                Err(_) => {
+                    // stdin closed, nobody can step anymore
+                    self.paused = false;
+                    None
+                }
+            }
+        } else {
+            std::thread::sleep(self.delay);
+            self.commands.try_recv().ok()
+        };
+        match command.as_deref().map(str::trim) {
+            Some("q") => Ok(false),
+            Some("p") => {
+                self.paused = !self.paused;
+                Ok(true)
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+// writes frames as dir/frame_NNNNN.txt, starting with frame_00000.txt
+pub struct FrameDump {
+    dir: PathBuf,
+    frame_number: usize,
+}
+
+impl FrameDump {
+    pub fn new(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create frame dir {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            frame_number: 0,
+        })
+    }
+    pub fn frame_path(dir: &Path, frame_number: usize) -> PathBuf {
+        dir.join(format!("frame_{:05}.txt", frame_number))
+    }
+}
+
+impl FrameSink for FrameDump {
+    fn frame(&mut self, frame: &dyn Render) -> Result<bool> {
+        let path = Self::frame_path(&self.dir, self.frame_number);
+        std::fs::write(&path, frame.render())
+            .with_context(|| format!("failed to write frame {}", path.display()))?;
+        self.frame_number += 1;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::days;
+    use crate::selection::Part;
+
+    #[test]
+    fn test_frame_dump() -> Result<()> {
+        let solution = days::get(17).unwrap();
+        let input = include_str!("../assets/day_17_example.txt");
+        let parsed = solution.parse(input)?;
+        let dir = std::env::temp_dir().join(format!("aoc_2022_frames_{}", std::process::id()));
+        let mut frame_dump = FrameDump::new(&dir)?;
+        solution.visualize(parsed.as_ref(), Part::One, &mut frame_dump)?;
+        // snapshot of chamber after three rocks
+        let frame = std::fs::read_to_string(FrameDump::frame_path(&dir, 3))?;
+        assert_eq!(
+            frame,
+            "|..#....|\n\
+             |..#....|\n\
+             |####...|\n\
+             |..###..|\n\
+             |...#...|\n\
+             |..####.|\n\
+             +-------+"
+        );
+        assert!(FrameDump::frame_path(&dir, 2_022).is_file());
+        assert!(!FrameDump::frame_path(&dir, 2_023).is_file());
+        std::fs::remove_dir_all(&dir)?;
+
+        // days without simulation have no frames
+        let day_01 = days::get(1).unwrap();
+        let mut frames: Vec<String> = Vec::new();
+        let result = day_01.visualize(day_01.parse("1000")?.as_ref(), Part::One, &mut frames);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "day 01 has no visualization"
+        );
+        assert!(frames.is_empty());
+        Ok(())
+    }
+}